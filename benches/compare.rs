@@ -0,0 +1,587 @@
+//! Compares `LinkedList` and its variants against the standard collections.
+//!
+//! This is a plain `std::time::Instant` harness so it runs on stable without
+//! any extra dependencies:
+//!
+//! ```text
+//! cargo bench --bench compare
+//! cargo bench --bench compare --features unsafe-backend -- --max-size 10000
+//! ```
+//!
+//! Every (operation, size, collection) cell is warmed up once and then
+//! sampled until it has `MAX_SAMPLES` samples or has used up its time budget.
+//! The median time per element is printed as a table, and the full
+//! statistics are written as CSV (to `target/dll-bench.csv` unless `--csv`
+//! says otherwise).
+//!
+//! Operations that are quadratic for a collection (`bubble_sort`, `Vec`
+//! front insertion, ...) are capped at a smaller size, and operations a
+//! collection has no API for are skipped; both show up as `-` in the table.
+use std::collections::LinkedList as StdLinkedList;
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use dll::LinkedList;
+#[cfg(feature = "unsafe-backend")]
+use dll::RawLinkedList;
+use dll::UnrolledList;
+
+const SIZES: [usize; 6] = [10, 100, 1_000, 10_000, 100_000, 1_000_000];
+const MIN_SAMPLES: usize = 3;
+const MAX_SAMPLES: usize = 15;
+const BUDGET: Duration = Duration::from_millis(500);
+/// How many lookups the `get` benchmark does per sample.
+const GETS: usize = 100;
+/// How many middle insertions the `insert_middle` benchmark does per sample.
+const INSERTS: usize = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    PushFront,
+    PushBack,
+    PopFront,
+    PopBack,
+    Get,
+    Iterate,
+    Retain,
+    Sort,
+    InsertMiddle,
+}
+
+impl Op {
+    const ALL: [Op; 9] = [
+        Op::PushFront,
+        Op::PushBack,
+        Op::PopFront,
+        Op::PopBack,
+        Op::Get,
+        Op::Iterate,
+        Op::Retain,
+        Op::Sort,
+        Op::InsertMiddle,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Op::PushFront => "push_front",
+            Op::PushBack => "push_back",
+            Op::PopFront => "pop_front",
+            Op::PopBack => "pop_back",
+            Op::Get => "get",
+            Op::Iterate => "iterate",
+            Op::Retain => "retain",
+            Op::Sort => "sort",
+            Op::InsertMiddle => "insert_middle",
+        }
+    }
+}
+
+/// A collection under test. Each method does one logical operation so the
+/// harness can drive every collection the same way.
+trait Subject: Sized {
+    const NAME: &'static str;
+
+    fn new() -> Self;
+    fn push_front(&mut self, value: u64);
+    fn push_back(&mut self, value: u64);
+    fn pop_front(&mut self) -> Option<u64>;
+    fn pop_back(&mut self) -> Option<u64>;
+    fn get(&self, index: usize) -> Option<u64>;
+    fn sum(&self) -> u64;
+    fn retain_odd(&mut self);
+    fn sort(&mut self);
+    fn insert_middle(&mut self, value: u64);
+
+    /// The largest size `op` is run at, `0` if the collection can't do it.
+    fn limit(_op: Op) -> usize {
+        usize::MAX
+    }
+
+    fn filled(n: usize) -> Self {
+        let mut subject = Self::new();
+        let mut rng = Rng::new(n as u64);
+        for _ in 0..n {
+            subject.push_back(rng.next());
+        }
+        subject
+    }
+}
+
+/// Inserts into the middle of a list that only has end operations, the way a
+/// user of the list would have to: move half of it aside and put it back.
+macro_rules! insert_middle_by_popping {
+    ($self:ident, $value:ident) => {{
+        let mut moved = Vec::with_capacity($self.len() / 2 + 1);
+        for _ in 0..$self.len() / 2 {
+            moved.extend($self.pop_back());
+        }
+        $self.push_back($value);
+        while let Some(value) = moved.pop() {
+            $self.push_back(value);
+        }
+    }};
+}
+
+impl Subject for LinkedList<u64> {
+    const NAME: &'static str = "LinkedList";
+
+    fn new() -> Self {
+        LinkedList::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        LinkedList::push_front(self, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        LinkedList::push_back(self, value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        LinkedList::pop_front(self)
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        LinkedList::pop_back(self)
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        LinkedList::get(self, index)
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, u64::wrapping_add)
+    }
+    fn retain_odd(&mut self) {
+        self.retain(|x| x % 2 != 0)
+    }
+    fn sort(&mut self) {
+        LinkedList::sort(self, |a, b| a.cmp(b))
+    }
+    fn insert_middle(&mut self, value: u64) {
+        insert_middle_by_popping!(self, value)
+    }
+    fn limit(op: Op) -> usize {
+        match op {
+            Op::Sort => 1_000, // bubble sort
+            Op::InsertMiddle => 100_000,
+            _ => usize::MAX,
+        }
+    }
+}
+
+#[cfg(feature = "unsafe-backend")]
+impl Subject for RawLinkedList<u64> {
+    const NAME: &'static str = "RawLinkedList";
+
+    fn new() -> Self {
+        RawLinkedList::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        RawLinkedList::push_front(self, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        RawLinkedList::push_back(self, value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        RawLinkedList::pop_front(self)
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        RawLinkedList::pop_back(self)
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        RawLinkedList::get(self, index)
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, u64::wrapping_add)
+    }
+    fn retain_odd(&mut self) {
+        self.retain(|x| x % 2 != 0)
+    }
+    fn sort(&mut self) {
+        RawLinkedList::sort(self, |a, b| a.cmp(b))
+    }
+    fn insert_middle(&mut self, value: u64) {
+        insert_middle_by_popping!(self, value)
+    }
+    fn limit(op: Op) -> usize {
+        match op {
+            Op::InsertMiddle => 100_000,
+            _ => usize::MAX,
+        }
+    }
+}
+
+/// Elements per node for the unrolled list; 32 `u64`s fill four cache lines.
+const UNROLL: usize = 32;
+
+impl Subject for UnrolledList<u64, UNROLL> {
+    const NAME: &'static str = "UnrolledList";
+
+    fn new() -> Self {
+        UnrolledList::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        UnrolledList::push_front(self, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        UnrolledList::push_back(self, value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        UnrolledList::pop_front(self)
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        UnrolledList::pop_back(self)
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        UnrolledList::get(self, index).copied()
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, |a, b| a.wrapping_add(*b))
+    }
+    fn retain_odd(&mut self) {
+        self.retain(|x| x % 2 != 0)
+    }
+    fn sort(&mut self) {
+        UnrolledList::sort(self, |a, b| a.cmp(b))
+    }
+    fn insert_middle(&mut self, value: u64) {
+        self.insert(self.len() / 2, value)
+    }
+}
+
+impl Subject for Vec<u64> {
+    const NAME: &'static str = "Vec";
+
+    fn new() -> Self {
+        Vec::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        self.insert(0, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        self.push(value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        self.pop()
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        self.as_slice().get(index).copied()
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, |a, b| a.wrapping_add(*b))
+    }
+    fn retain_odd(&mut self) {
+        self.retain(|x| x % 2 != 0)
+    }
+    fn sort(&mut self) {
+        self.as_mut_slice().sort()
+    }
+    fn insert_middle(&mut self, value: u64) {
+        self.insert(self.len() / 2, value)
+    }
+    fn limit(op: Op) -> usize {
+        match op {
+            Op::PushFront | Op::PopFront => 100_000,
+            _ => usize::MAX,
+        }
+    }
+}
+
+impl Subject for VecDeque<u64> {
+    const NAME: &'static str = "VecDeque";
+
+    fn new() -> Self {
+        VecDeque::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        VecDeque::push_front(self, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        VecDeque::push_back(self, value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        VecDeque::pop_front(self)
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        VecDeque::pop_back(self)
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        VecDeque::get(self, index).copied()
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, |a, b| a.wrapping_add(*b))
+    }
+    fn retain_odd(&mut self) {
+        self.retain(|x| x % 2 != 0)
+    }
+    fn sort(&mut self) {
+        self.make_contiguous().sort()
+    }
+    fn insert_middle(&mut self, value: u64) {
+        self.insert(self.len() / 2, value)
+    }
+}
+
+impl Subject for StdLinkedList<u64> {
+    const NAME: &'static str = "std LinkedList";
+
+    fn new() -> Self {
+        StdLinkedList::new()
+    }
+    fn push_front(&mut self, value: u64) {
+        StdLinkedList::push_front(self, value)
+    }
+    fn push_back(&mut self, value: u64) {
+        StdLinkedList::push_back(self, value)
+    }
+    fn pop_front(&mut self) -> Option<u64> {
+        StdLinkedList::pop_front(self)
+    }
+    fn pop_back(&mut self) -> Option<u64> {
+        StdLinkedList::pop_back(self)
+    }
+    fn get(&self, index: usize) -> Option<u64> {
+        self.iter().nth(index).copied()
+    }
+    fn sum(&self) -> u64 {
+        self.iter().fold(0, |a, b| a.wrapping_add(*b))
+    }
+    fn retain_odd(&mut self) {
+        let kept: StdLinkedList<u64> = std::mem::take(self)
+            .into_iter()
+            .filter(|x| x % 2 != 0)
+            .collect();
+        *self = kept;
+    }
+    fn sort(&mut self) {
+        unreachable!("std::collections::LinkedList has no sort")
+    }
+    fn insert_middle(&mut self, value: u64) {
+        let mut back = self.split_off(self.len() / 2);
+        self.push_back(value);
+        self.append(&mut back);
+    }
+    fn limit(op: Op) -> usize {
+        match op {
+            Op::Sort => 0,
+            _ => usize::MAX,
+        }
+    }
+}
+
+/// A xorshift generator, so every collection sees the same data.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Runs `op` once on a fresh collection of `n` elements. Returns the time the
+/// operation itself took and how many elements it touched.
+fn run_once<S: Subject>(op: Op, n: usize) -> (Duration, usize) {
+    let mut rng = Rng::new(n as u64 ^ 0xD11);
+    match op {
+        Op::PushFront | Op::PushBack => {
+            let mut subject = S::new();
+            let start = Instant::now();
+            for i in 0..n as u64 {
+                if op == Op::PushFront {
+                    subject.push_front(i);
+                } else {
+                    subject.push_back(i);
+                }
+            }
+            let elapsed = start.elapsed();
+            black_box(&subject);
+            (elapsed, n)
+        }
+        Op::PopFront | Op::PopBack => {
+            let mut subject = S::filled(n);
+            let start = Instant::now();
+            for _ in 0..n {
+                if op == Op::PopFront {
+                    black_box(subject.pop_front());
+                } else {
+                    black_box(subject.pop_back());
+                }
+            }
+            (start.elapsed(), n)
+        }
+        Op::Get => {
+            let subject = S::filled(n);
+            let indices: Vec<usize> = (0..GETS).map(|_| rng.next() as usize % n).collect();
+            let start = Instant::now();
+            for &index in &indices {
+                black_box(subject.get(index));
+            }
+            (start.elapsed(), GETS)
+        }
+        Op::Iterate => {
+            let subject = S::filled(n);
+            let start = Instant::now();
+            black_box(subject.sum());
+            (start.elapsed(), n)
+        }
+        Op::Retain => {
+            let mut subject = S::filled(n);
+            let start = Instant::now();
+            subject.retain_odd();
+            let elapsed = start.elapsed();
+            black_box(&subject);
+            (elapsed, n)
+        }
+        Op::Sort => {
+            let mut subject = S::filled(n);
+            let start = Instant::now();
+            subject.sort();
+            let elapsed = start.elapsed();
+            black_box(&subject);
+            (elapsed, n)
+        }
+        Op::InsertMiddle => {
+            let mut subject = S::filled(n);
+            let start = Instant::now();
+            for _ in 0..INSERTS {
+                subject.insert_middle(rng.next());
+            }
+            let elapsed = start.elapsed();
+            black_box(&subject);
+            (elapsed, INSERTS)
+        }
+    }
+}
+
+/// Nanoseconds per element over all samples of one cell.
+struct Summary {
+    samples: usize,
+    min: f64,
+    median: f64,
+    mean: f64,
+    stddev: f64,
+}
+
+fn measure<S: Subject>(op: Op, n: usize) -> Option<Summary> {
+    if n > S::limit(op) {
+        return None;
+    }
+
+    run_once::<S>(op, n); // warm-up
+    let started = Instant::now();
+    let mut samples = Vec::with_capacity(MAX_SAMPLES);
+    while samples.len() < MIN_SAMPLES || (samples.len() < MAX_SAMPLES && started.elapsed() < BUDGET)
+    {
+        let (elapsed, elements) = run_once::<S>(op, n);
+        samples.push(elapsed.as_nanos() as f64 / elements as f64);
+    }
+
+    samples.sort_by(f64::total_cmp);
+    let count = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / count;
+    let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count;
+    Some(Summary {
+        samples: samples.len(),
+        min: samples[0],
+        median: samples[samples.len() / 2],
+        mean,
+        stddev: variance.sqrt(),
+    })
+}
+
+type Measure = fn(Op, usize) -> Option<Summary>;
+
+fn subjects() -> Vec<(&'static str, Measure)> {
+    let mut subjects: Vec<(&'static str, Measure)> = vec![(
+        <LinkedList<u64> as Subject>::NAME,
+        measure::<LinkedList<u64>>,
+    )];
+    #[cfg(feature = "unsafe-backend")]
+    subjects.push((
+        <RawLinkedList<u64> as Subject>::NAME,
+        measure::<RawLinkedList<u64>>,
+    ));
+    subjects.push((
+        <UnrolledList<u64, UNROLL> as Subject>::NAME,
+        measure::<UnrolledList<u64, UNROLL>>,
+    ));
+    subjects.push((<Vec<u64> as Subject>::NAME, measure::<Vec<u64>>));
+    subjects.push((<VecDeque<u64> as Subject>::NAME, measure::<VecDeque<u64>>));
+    subjects.push((
+        <StdLinkedList<u64> as Subject>::NAME,
+        measure::<StdLinkedList<u64>>,
+    ));
+    subjects
+}
+
+fn main() {
+    let mut max_size = usize::MAX;
+    let mut csv_path = String::from("target/dll-bench.csv");
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-size" => {
+                max_size = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .expect("--max-size takes a number");
+            }
+            "--csv" => csv_path = args.next().expect("--csv takes a path"),
+            _ => {} // `cargo bench` passes `--bench`
+        }
+    }
+
+    let subjects = subjects();
+    let mut csv = String::from("op,size,collection,samples,min_ns,median_ns,mean_ns,stddev_ns\n");
+
+    println!("median ns per element (per lookup for get, per insertion for insert_middle)");
+    for op in Op::ALL {
+        println!();
+        print!("{:<14}{:>10}", op.name(), "size");
+        for (name, _) in &subjects {
+            print!("{:>16}", name);
+        }
+        println!();
+
+        for n in SIZES.into_iter().filter(|&n| n <= max_size) {
+            print!("{:<14}{:>10}", "", n);
+            for (name, measure) in &subjects {
+                match measure(op, n) {
+                    Some(summary) => {
+                        print!("{:>16.1}", summary.median);
+                        csv.push_str(&format!(
+                            "{},{},{},{},{:.2},{:.2},{:.2},{:.2}\n",
+                            op.name(),
+                            n,
+                            name,
+                            summary.samples,
+                            summary.min,
+                            summary.median,
+                            summary.mean,
+                            summary.stddev
+                        ));
+                    }
+                    None => print!("{:>16}", "-"),
+                }
+            }
+            println!();
+        }
+    }
+
+    match fs::write(&csv_path, csv) {
+        Ok(()) => println!("\nwrote {}", csv_path),
+        Err(err) => eprintln!("\ncould not write {}: {}", csv_path, err),
+    }
+}
@@ -0,0 +1,566 @@
+/// An arena-backed doubly-linked list.
+///
+/// `ArenaList` keeps every node in a single `Vec` and links them together by
+/// index instead of by pointer. There is no `Rc`, `RefCell` or per-node
+/// allocation: pushing reuses a vacated slot from the free list when there is
+/// one, and the nodes of a list that is built up front end up next to each
+/// other in memory. `compact` restores that layout after heavy churn.
+///
+/// ```text
+///  slots:   0        1        2        3
+///        --------------------------------------
+///        | a    | free   | b      | c      |
+///        | 2 -> |        | 3 ->   | -> NIL |
+///        --------------------------------------
+///  head = 0, tail = 3, free = 1
+/// ```
+///
+/// Links are `u32` slot indices and `u32::MAX` is `NIL`, so the slots are
+/// numbered `0..u32::MAX` and a list holds at most `u32::MAX` elements.
+use core::fmt;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::mem;
+
+/// Marks the absence of a link.
+const NIL: u32 = u32::MAX;
+
+struct Slot<T> {
+    data: Option<T>, // `None` while the slot is on the free list
+    next: u32,       // next node, or next free slot
+    prev: u32,
+}
+
+/// A doubly-linked list stored in a `Vec`.
+pub struct ArenaList<T> {
+    slots: Vec<Slot<T>>,
+    head: u32,
+    tail: u32,
+    free: u32,
+    num_elements: usize,
+}
+
+impl<T> ArenaList<T> {
+    /// Creates an empty `ArenaList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let list: ArenaList<u32> = ArenaList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty `ArenaList` with room for `capacity` elements before
+    /// it has to reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaList {
+            slots: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            free: NIL,
+            num_elements: 0,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the number of slots in the arena, occupied or free.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.pop_front();
+    /// list.push_back(3); // reuses the slot vacated by 1
+    /// assert_eq!(list.capacity(), 2);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Adds an element to the head of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_front(1);
+    /// list.push_front(2);
+    /// assert_eq!(list.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        let head = self.head;
+        self.link(NIL, head, value);
+    }
+
+    /// Adds an element to the tail of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.to_string(), "1 -> 2");
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        let tail = self.tail;
+        self.link(tail, NIL, value);
+    }
+
+    /// Removes an element from the head of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// assert_eq!(list.pop_front(), Some(2));
+    /// assert_eq!(list.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.head {
+            NIL => None,
+            head => Some(self.unlink(head)),
+        }
+    }
+
+    /// Removes an element from the tail of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// assert_eq!(list.pop_back(), Some(1));
+    /// assert_eq!(list.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match self.tail {
+            NIL => None,
+            tail => Some(self.unlink(tail)),
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting everything after it
+    /// towards the tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.insert(1, 2);
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.num_elements, "insertion index out of bounds");
+        if index == self.num_elements {
+            self.push_back(value);
+        } else {
+            let next = self.index_of(index);
+            let prev = self.slots[next as usize].prev;
+            self.link(prev, next, value);
+        }
+    }
+
+    /// Removes and returns the element at position `index`, or `None` if the
+    /// index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// assert_eq!(list.remove(1), Some(2));
+    /// assert_eq!(list.remove(5), None);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let node = self.index_of(index);
+        Some(self.unlink(node))
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// list.retain(|x| x % 2 == 1);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let mut current = self.head;
+        while current != NIL {
+            let slot = &self.slots[current as usize];
+            let next = slot.next;
+            if !f(slot.data.as_ref().unwrap()) {
+                self.unlink(current);
+            }
+            current = next;
+        }
+    }
+
+    /// Clears the list, removing all values and releasing the arena.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.free = NIL;
+        self.num_elements = 0;
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.get(1), Some(&2));
+    /// assert_eq!(list.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        self.slots[self.index_of(index) as usize].data.as_ref()
+    }
+
+    /// Returns a mutable reference to the element at the given index, or
+    /// `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let node = self.index_of(index);
+        self.slots[node as usize].data.as_mut()
+    }
+
+    /// Returns an iterator over the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> ArenaIter<'_, T> {
+        ArenaIter {
+            slots: &self.slots,
+            current: self.head,
+        }
+    }
+
+    /// Returns a mutable iterator over the list.
+    ///
+    /// The slots are borrowed through `Vec::iter_mut` and put in link order
+    /// up front, which keeps this free of `unsafe` at the cost of a buffer
+    /// of one reference per element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    /// assert_eq!(list.to_string(), "10 -> 11");
+    /// ```
+    pub fn iter_mut(&mut self) -> ArenaIterMut<'_, T> {
+        // Number the occupied slots in link order.
+        let mut position = vec![0; self.slots.len()];
+        let mut current = self.head;
+        for i in 0..self.num_elements {
+            position[current as usize] = i;
+            current = self.slots[current as usize].next;
+        }
+        let mut items: Vec<Option<&mut T>> = (0..self.num_elements).map(|_| None).collect();
+        for (slot, &i) in self.slots.iter_mut().zip(&position) {
+            if let Some(data) = slot.data.as_mut() {
+                items[i] = Some(data);
+            }
+        }
+        ArenaIterMut {
+            items: items.into_iter(),
+        }
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// The sort is stable and runs in O(n log n): the nodes are ordered by
+    /// their data and then relinked in place, so no element is moved or
+    /// cloned. The comparator must define a total order, see
+    /// `LinkedList::sort`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.push_back(2);
+    /// list.sort(|a, b| a.cmp(b));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn sort(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let mut order = self.order();
+        let slots = &self.slots;
+        order.sort_by(|&a, &b| {
+            let a = slots[a as usize].data.as_ref().unwrap();
+            let b = slots[b as usize].data.as_ref().unwrap();
+            compare(a, b)
+        });
+        self.relink(&order);
+    }
+
+    /// Re-lays the nodes out in traversal order, so that walking the list
+    /// walks the arena front to back, and releases the free slots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::ArenaList;
+    /// let mut list: ArenaList<u32> = ArenaList::new();
+    /// for i in 0..10 {
+    ///     list.push_front(i);
+    /// }
+    /// list.retain(|x| x % 2 == 0);
+    /// assert_eq!(list.capacity(), 10);
+    /// list.compact();
+    /// assert_eq!(list.capacity(), 5);
+    /// assert_eq!(list.to_string(), "8 -> 6 -> 4 -> 2 -> 0");
+    /// ```
+    pub fn compact(&mut self) {
+        let order = self.order();
+        let mut old = mem::take(&mut self.slots);
+        let len = order.len() as u32;
+        self.slots = order
+            .iter()
+            .enumerate()
+            .map(|(i, &index)| {
+                let i = i as u32;
+                Slot {
+                    data: old[index as usize].data.take(),
+                    prev: if i == 0 { NIL } else { i - 1 },
+                    next: if i + 1 == len { NIL } else { i + 1 },
+                }
+            })
+            .collect();
+        self.head = if len == 0 { NIL } else { 0 };
+        self.tail = if len == 0 { NIL } else { len - 1 };
+        self.free = NIL;
+    }
+
+    /// Returns the slot indices of the nodes in traversal order.
+    fn order(&self) -> Vec<u32> {
+        let mut order = Vec::with_capacity(self.num_elements);
+        let mut current = self.head;
+        while current != NIL {
+            order.push(current);
+            current = self.slots[current as usize].next;
+        }
+        order
+    }
+
+    /// Rewrites the links so that the nodes appear in the given order.
+    fn relink(&mut self, order: &[u32]) {
+        let mut prev = NIL;
+        for &index in order {
+            self.slots[index as usize].prev = prev;
+            if prev != NIL {
+                self.slots[prev as usize].next = index;
+            }
+            prev = index;
+        }
+        if prev != NIL {
+            self.slots[prev as usize].next = NIL;
+        }
+        self.head = order.first().copied().unwrap_or(NIL);
+        self.tail = prev;
+    }
+
+    /// Returns the slot index of the node at `index`, walking from whichever
+    /// end is closer. `index` must be in bounds.
+    fn index_of(&self, index: usize) -> u32 {
+        if index < self.num_elements / 2 {
+            let mut current = self.head;
+            for _ in 0..index {
+                current = self.slots[current as usize].next;
+            }
+            current
+        } else {
+            let mut current = self.tail;
+            for _ in index + 1..self.num_elements {
+                current = self.slots[current as usize].prev;
+            }
+            current
+        }
+    }
+
+    /// Stores `value` in a free slot and links it between `prev` and `next`,
+    /// either of which may be `NIL` at the ends of the list.
+    fn link(&mut self, prev: u32, next: u32, value: T) {
+        let slot = Slot {
+            data: Some(value),
+            next,
+            prev,
+        };
+        let index = match self.free {
+            NIL => {
+                let index = u32::try_from(self.slots.len())
+                    .ok()
+                    .filter(|&index| index != NIL)
+                    .expect("ArenaList is full");
+                self.slots.push(slot);
+                index
+            }
+            index => {
+                self.free = self.slots[index as usize].next;
+                self.slots[index as usize] = slot;
+                index
+            }
+        };
+
+        match prev {
+            NIL => self.head = index,
+            prev => self.slots[prev as usize].next = index,
+        }
+        match next {
+            NIL => self.tail = index,
+            next => self.slots[next as usize].prev = index,
+        }
+        self.num_elements += 1;
+    }
+
+    /// Unlinks the node in slot `index`, puts the slot on the free list and
+    /// returns its data.
+    fn unlink(&mut self, index: u32) -> T {
+        let slot = &mut self.slots[index as usize];
+        let (prev, next) = (slot.prev, slot.next);
+        let data = slot.data.take().unwrap();
+        slot.next = self.free;
+        slot.prev = NIL;
+        self.free = index;
+
+        match prev {
+            NIL => self.head = next,
+            prev => self.slots[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.slots[next as usize].prev = prev,
+        }
+        self.num_elements -= 1;
+        data
+    }
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Display> Display for ArenaList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An immutable iterator over the elements of an `ArenaList`.
+pub struct ArenaIter<'a, T> {
+    slots: &'a [Slot<T>],
+    current: u32,
+}
+
+impl<'a, T> Iterator for ArenaIter<'a, T> {
+    type Item = &'a T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == NIL {
+            return None;
+        }
+        let slot = &self.slots[self.current as usize];
+        self.current = slot.next;
+        slot.data.as_ref()
+    }
+}
+
+/// A mutable iterator over the elements of an `ArenaList`.
+pub struct ArenaIterMut<'a, T> {
+    items: std::vec::IntoIter<Option<&'a mut T>>,
+}
+
+impl<'a, T> Iterator for ArenaIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        // Every position was filled, since the list links each occupied
+        // slot exactly once.
+        self.items.next().flatten()
+    }
+}
@@ -0,0 +1,303 @@
+/// A linked list with a size limit.
+///
+/// A `BoundedList` holds at most `capacity` elements and, optionally, at most
+/// a given total weight, where each element's weight comes from the `Weigh`
+/// trait (the length of a log line, say). When a push would break a limit,
+/// the list's `OverflowPolicy` decides what happens: the push is refused,
+/// elements are evicted from one end until the new one fits, or a callback
+/// gets to make room.
+///
+/// Pushes return the elements they evicted, unless an eviction callback is
+/// set, in which case the callback receives them instead.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use super::list_iter::ListIter;
+use super::LinkedList;
+
+/// Measures how much of a `BoundedList`'s weight limit an element uses.
+pub trait Weigh {
+    /// Returns the weight of the element.
+    fn weight(&self) -> usize;
+}
+
+impl Weigh for String {
+    /// A string weighs its length in bytes.
+    fn weight(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Weigh for &str {
+    /// A string weighs its length in bytes.
+    fn weight(&self) -> usize {
+        self.len()
+    }
+}
+
+type MakeRoom<T> = Box<dyn FnMut(&mut BoundedList<T>) -> bool>;
+
+/// What a `BoundedList` does when a push would break one of its limits.
+pub enum OverflowPolicy<T: Clone + Display> {
+    /// Refuses the push and hands the value back.
+    Reject,
+    /// Evicts elements from the front until the new one fits.
+    EvictFront,
+    /// Evicts elements from the back until the new one fits.
+    EvictBack,
+    /// Calls the callback until the new element fits. The callback should
+    /// make room, for example by popping and flushing elements somewhere
+    /// else, and return `true` to have the push try again, or return `false`
+    /// to refuse it. A callback that returns `true` without shrinking the
+    /// list's length or weight has the push refused too, rather than being
+    /// called again forever. A policy the callback sets with `set_policy`
+    /// replaces this one and handles whatever overflow is left.
+    Block(MakeRoom<T>),
+}
+
+/// A doubly-linked list with a maximum length and an optional maximum
+/// total weight.
+pub struct BoundedList<T: Clone + Display> {
+    list: LinkedList<T>,
+    capacity: usize,
+    weight: usize,
+    weight_limit: Option<usize>,
+    weigh: fn(&T) -> usize,
+    policy: OverflowPolicy<T>,
+    policy_replaced: bool,
+    on_evict: Option<Box<dyn FnMut(T)>>,
+}
+
+impl<T: Clone + Display> BoundedList<T> {
+    /// Creates an empty list that holds up to `capacity` elements and
+    /// handles overflow according to `policy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{BoundedList, OverflowPolicy};
+    /// let mut tail = BoundedList::new(3, OverflowPolicy::EvictFront);
+    /// for line in 1..=5 {
+    ///     tail.push_back(line).unwrap();
+    /// }
+    /// assert_eq!(tail.to_string(), "3 -> 4 -> 5");
+    /// ```
+    pub fn new(capacity: usize, policy: OverflowPolicy<T>) -> Self {
+        BoundedList {
+            list: LinkedList::new(),
+            capacity,
+            weight: 0,
+            weight_limit: None,
+            weigh: |_| 0,
+            policy,
+            policy_replaced: false,
+            on_evict: None,
+        }
+    }
+
+    /// Sets a callback that receives every element the list evicts. Pushes
+    /// then return no evicted elements.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{BoundedList, OverflowPolicy};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let dropped = Rc::new(RefCell::new(Vec::new()));
+    /// let log = Rc::clone(&dropped);
+    /// let mut list = BoundedList::new(1, OverflowPolicy::EvictFront);
+    /// list.set_eviction_callback(move |value| log.borrow_mut().push(value));
+    /// list.push_back(1).unwrap();
+    /// assert_eq!(list.push_back(2), Ok(vec![]));
+    /// assert_eq!(*dropped.borrow(), [1]);
+    /// ```
+    pub fn set_eviction_callback(&mut self, callback: impl FnMut(T) + 'static) {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    /// Replaces the overflow policy.
+    pub fn set_policy(&mut self, policy: OverflowPolicy<T>) {
+        self.policy = policy;
+        self.policy_replaced = true;
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the maximum number of elements the list holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the total weight of the elements, or 0 if the list has no
+    /// weight limit.
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    /// Adds an element to the front of the list.
+    ///
+    /// Returns the elements evicted to make room for it, or gives the value
+    /// back if the list refused it. A value that breaks a limit on its own
+    /// is always refused.
+    pub fn push_front(&mut self, value: T) -> Result<Vec<T>, T> {
+        let evicted = match self.make_room(&value) {
+            Some(evicted) => evicted,
+            None => return Err(value),
+        };
+        self.weight += (self.weigh)(&value);
+        self.list.push_front(value);
+        Ok(evicted)
+    }
+
+    /// Adds an element to the back of the list.
+    ///
+    /// Returns the elements evicted to make room for it, or gives the value
+    /// back if the list refused it. A value that breaks a limit on its own
+    /// is always refused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{BoundedList, OverflowPolicy};
+    /// let mut list = BoundedList::new(2, OverflowPolicy::Reject);
+    /// assert_eq!(list.push_back(1), Ok(vec![]));
+    /// assert_eq!(list.push_back(2), Ok(vec![]));
+    /// assert_eq!(list.push_back(3), Err(3));
+    ///
+    /// list.set_policy(OverflowPolicy::EvictBack);
+    /// assert_eq!(list.push_back(3), Ok(vec![2]));
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn push_back(&mut self, value: T) -> Result<Vec<T>, T> {
+        let evicted = match self.make_room(&value) {
+            Some(evicted) => evicted,
+            None => return Err(value),
+        };
+        self.weight += (self.weigh)(&value);
+        self.list.push_back(value);
+        Ok(evicted)
+    }
+
+    /// Removes an element from the front of the list and returns it.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let value = self.list.pop_front()?;
+        self.weight -= (self.weigh)(&value);
+        Some(value)
+    }
+
+    /// Removes an element from the back of the list and returns it.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let value = self.list.pop_back()?;
+        self.weight -= (self.weigh)(&value);
+        Some(value)
+    }
+
+    /// Clears the list, removing all values.
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.weight = 0;
+    }
+
+    /// Returns an iterator over the list.
+    pub fn iter(&self) -> ListIter<T> {
+        self.list.iter()
+    }
+
+    /// Applies the overflow policy until an element weighing as much as
+    /// `value` fits. Returns the evicted elements, or `None` if the element
+    /// is refused.
+    fn make_room(&mut self, value: &T) -> Option<Vec<T>> {
+        let weight = (self.weigh)(value);
+        if self.capacity == 0 || self.weight_limit.is_some_and(|limit| weight > limit) {
+            return None;
+        }
+        let mut evicted = Vec::new();
+        while !self.fits(weight) {
+            let value = match &mut self.policy {
+                OverflowPolicy::Reject => return None,
+                OverflowPolicy::EvictFront => self.pop_front(),
+                OverflowPolicy::EvictBack => self.pop_back(),
+                OverflowPolicy::Block(_) => {
+                    // The callback needs the whole list, so take the policy
+                    // out while it runs.
+                    let mut policy = std::mem::replace(&mut self.policy, OverflowPolicy::Reject);
+                    let before = (self.len(), self.weight);
+                    self.policy_replaced = false;
+                    let retry = match &mut policy {
+                        OverflowPolicy::Block(callback) => callback(self),
+                        _ => unreachable!(),
+                    };
+                    // Keep whatever the callback swapped in.
+                    if !self.policy_replaced {
+                        self.policy = policy;
+                    }
+                    let made_room = self.len() < before.0 || self.weight < before.1;
+                    if !retry || !made_room {
+                        return None;
+                    }
+                    continue;
+                }
+            };
+            match (value, self.on_evict.as_mut()) {
+                (Some(value), Some(on_evict)) => on_evict(value),
+                (Some(value), None) => evicted.push(value),
+                (None, _) => unreachable!("an empty list always has room"),
+            }
+        }
+        Some(evicted)
+    }
+
+    /// Returns `true` if one more element of the given weight is within the
+    /// limits.
+    fn fits(&self, weight: usize) -> bool {
+        self.len() < self.capacity
+            && self
+                .weight_limit
+                .is_none_or(|limit| self.weight + weight <= limit)
+    }
+}
+
+impl<T: Clone + Display + Weigh> BoundedList<T> {
+    /// Limits the total weight of the elements as well as their number.
+    ///
+    /// The limit is checked by later pushes; elements already in the list
+    /// are not evicted to meet it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{BoundedList, OverflowPolicy};
+    /// let mut log = BoundedList::new(100, OverflowPolicy::EvictFront);
+    /// log.set_weight_limit(12);
+    /// log.push_back(String::from("started")).unwrap();
+    /// log.push_back(String::from("ready")).unwrap();
+    /// assert_eq!(log.weight(), 12);
+    /// let evicted = log.push_back(String::from("stop")).unwrap();
+    /// assert_eq!(evicted, ["started"]);
+    /// assert_eq!(log.weight(), 9);
+    /// ```
+    pub fn set_weight_limit(&mut self, limit: usize) {
+        if self.weight_limit.is_none() {
+            self.weigh = T::weight;
+            self.weight = self.list.iter().map(|value| value.weight()).sum();
+        }
+        self.weight_limit = Some(limit);
+    }
+}
+
+impl<T: Clone + Display> Display for BoundedList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
@@ -0,0 +1,337 @@
+/// A circular doubly-linked list with a rotating current position.
+///
+/// The elements of a `CircularList` form a ring: stepping forward from the
+/// last element lands on the first one again, and stepping back from the
+/// first lands on the last. The list keeps one position in the ring as
+/// "current"; `advance` and `retreat` rotate it, and `insert_here` and
+/// `remove_here` edit the ring at that position. That is the shape of a
+/// round-robin scheduler, or of the Josephus problem.
+///
+/// A ring of `Rc`s would be a reference cycle and leak, so the ring is only
+/// closed virtually. The current node is the start of an ordinary strong
+/// chain of `next` links, and the last node's `next` is `None`, which means
+/// "wrap around to the current node". All `prev` links are weak, including
+/// the current node's link back to the last one.
+///
+/// ```text
+///           strong        strong        (wraps)
+///  current ------> [b] ------> [c] - - - - > current
+///     ^  \                                    |
+///     |   `- - - - - - weak prev - - - - - - -'
+///   list
+/// ```
+///
+/// Rotating the current position moves the break in the chain along with it,
+/// so dropping the list frees every node.
+use core::fmt;
+use core::marker::PhantomData;
+use std::cell::RefCell;
+use std::clone::Clone;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+use super::list_iter::ListIter;
+use super::list_node::{ListNode, StrongPointer};
+
+type Link<T> = StrongPointer<ListNode<T>>;
+
+/// A circular doubly-linked list.
+pub struct CircularList<T: Clone + Display> {
+    current: Option<Link<T>>,
+    num_elements: usize,
+}
+
+impl<T: Clone + Display> CircularList<T> {
+    /// Creates an empty `CircularList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let list: CircularList<u32> = CircularList::new();
+    /// assert_eq!(list.len(), 0);
+    /// assert_eq!(list.current(), None);
+    /// ```
+    pub fn new() -> Self {
+        CircularList {
+            current: None,
+            num_elements: 0,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the element at the current position, or `None` if the list
+    /// is empty.
+    pub fn current(&self) -> Option<T> {
+        self.current.as_ref().map(|node| node.borrow().data.clone())
+    }
+
+    /// Adds an element to the end of the ring, right behind the current
+    /// position. The current position doesn't move unless the list was
+    /// empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// assert_eq!(list.current(), Some(1));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        match self.current.as_ref() {
+            Some(current) => {
+                let last = Self::last_of(current);
+                let new_node = Rc::new(RefCell::new(ListNode::from(value)));
+                new_node.borrow_mut().prev = Some(Rc::downgrade(&last));
+                current.borrow_mut().prev = Some(Rc::downgrade(&new_node));
+                last.borrow_mut().next = Some(new_node);
+                self.num_elements += 1;
+            }
+            None => self.insert_here(value),
+        }
+    }
+
+    /// Inserts an element at the current position. The new element becomes
+    /// current, and the old current element comes right after it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// list.insert_here(3);
+    /// list.insert_here(2);
+    /// list.insert_here(1);
+    /// assert_eq!(list.current(), Some(1));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn insert_here(&mut self, value: T) {
+        let new_node = Rc::new(RefCell::new(ListNode::from(value)));
+        match self.current.take() {
+            Some(old) => {
+                let last = Self::last_of(&old);
+                new_node.borrow_mut().prev = Some(Rc::downgrade(&last));
+                old.borrow_mut().prev = Some(Rc::downgrade(&new_node));
+                new_node.borrow_mut().next = Some(old);
+            }
+            None => {
+                // A ring of one: the node is its own (weak) predecessor.
+                let weak_self = Rc::downgrade(&new_node);
+                new_node.borrow_mut().prev = Some(weak_self);
+            }
+        }
+        self.current = Some(new_node);
+        self.num_elements += 1;
+    }
+
+    /// Removes the element at the current position and returns it. The
+    /// element after it becomes current.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.remove_here(), Some(1));
+    /// assert_eq!(list.current(), Some(2));
+    /// assert_eq!(list.remove_here(), Some(2));
+    /// assert_eq!(list.remove_here(), None);
+    /// ```
+    pub fn remove_here(&mut self) -> Option<T> {
+        self.current.take().map(|old| {
+            self.num_elements -= 1;
+            if self.num_elements > 0 {
+                let last = Self::last_of(&old);
+                let next = old.borrow_mut().next.take().unwrap();
+                next.borrow_mut().prev = Some(Rc::downgrade(&last));
+                self.current = Some(next);
+            }
+            old.borrow_mut().prev = None;
+            let data = old.borrow().data.clone();
+            data
+        })
+    }
+
+    /// Moves the current position `k` steps forward around the ring.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// for i in 1..=3 {
+    ///     list.push_back(i);
+    /// }
+    /// list.advance(1);
+    /// assert_eq!(list.current(), Some(2));
+    /// list.advance(5);
+    /// assert_eq!(list.current(), Some(1));
+    /// ```
+    pub fn advance(&mut self, k: usize) {
+        if self.num_elements < 2 {
+            return;
+        }
+        for _ in 0..k % self.num_elements {
+            // The old current node becomes the last one: its successor is
+            // now owned by the list, and it is owned by the old last node.
+            let old = self.current.take().unwrap();
+            let next = old.borrow_mut().next.take().unwrap();
+            let last = Self::last_of(&old);
+            last.borrow_mut().next = Some(old);
+            self.current = Some(next);
+        }
+    }
+
+    /// Moves the current position `k` steps backward around the ring.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// for i in 1..=3 {
+    ///     list.push_back(i);
+    /// }
+    /// list.retreat(1);
+    /// assert_eq!(list.current(), Some(3));
+    /// list.retreat(4);
+    /// assert_eq!(list.current(), Some(2));
+    /// ```
+    pub fn retreat(&mut self, k: usize) {
+        if self.num_elements < 2 {
+            return;
+        }
+        for _ in 0..k % self.num_elements {
+            // The last node becomes current, and the one before it becomes
+            // the new last node.
+            let old = self.current.take().unwrap();
+            let last = Self::last_of(&old);
+            let before_last = Self::last_of(&last);
+            before_last.borrow_mut().next = None;
+            last.borrow_mut().next = Some(old);
+            self.current = Some(last);
+        }
+    }
+
+    /// Clears the list, removing all values.
+    pub fn clear(&mut self) {
+        while self.remove_here().is_some() {}
+    }
+
+    /// Returns an iterator over one lap of the ring, starting at the current
+    /// position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// for i in 1..=3 {
+    ///     list.push_back(i);
+    /// }
+    /// list.advance(1);
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), [2, 3, 1]);
+    /// ```
+    pub fn iter(&self) -> ListIter<T> {
+        ListIter {
+            current: self.current.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that goes around the ring forever, starting at
+    /// the current position. It only ends if the list is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::CircularList;
+    /// let mut list: CircularList<u32> = CircularList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let laps: Vec<u32> = list.cycle().take(5).collect();
+    /// assert_eq!(laps, [1, 2, 1, 2, 1]);
+    /// ```
+    pub fn cycle(&self) -> CycleIter<'_, T> {
+        CycleIter {
+            list: self,
+            next: self.current.clone(),
+        }
+    }
+
+    /// Returns the last node of the ring, the one before `node`.
+    fn last_of(node: &Link<T>) -> Link<T> {
+        node.borrow()
+            .prev
+            .as_ref()
+            .and_then(|prev| prev.upgrade())
+            .unwrap()
+    }
+}
+
+impl<T: Clone + Display> Default for CircularList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Drop for CircularList<T> {
+    // The forward links form a plain chain, so unlink it iteratively just like
+    // `LinkedList` does.
+    fn drop(&mut self) {
+        let mut current = self.current.take();
+        while let Some(node) = current {
+            current = Rc::try_unwrap(node)
+                .ok()
+                .and_then(|node| node.into_inner().next);
+        }
+    }
+}
+
+impl<T: Clone + Display> Display for CircularList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An endless iterator around a `CircularList`.
+pub struct CycleIter<'a, T: Clone + Display> {
+    list: &'a CircularList<T>,
+    next: Option<Link<T>>,
+}
+
+impl<T: Clone + Display> Iterator for CycleIter<'_, T> {
+    type Item = T;
+
+    /// Returns the next element around the ring.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            let node = node.borrow();
+            self.next = node.next.clone().or_else(|| self.list.current.clone());
+            node.data.clone()
+        })
+    }
+}
@@ -0,0 +1,463 @@
+/// A lock-free doubly-linked deque.
+///
+/// `ConcurrentDeque` can be pushed to and popped from at both ends by any
+/// number of threads at once through a shared reference, using nothing but
+/// `std::sync::atomic`. No operation ever blocks waiting for another thread.
+///
+/// # Design
+///
+/// The deque follows Maged Michael's CAS-based deque ("CAS-Based Lock-Free
+/// Algorithm for Shared Deques", Euro-Par 2003). Nodes are doubly linked, and
+/// the two ends live together in a single immutable *anchor* which is swapped
+/// out with one CAS per push or pop. That CAS is the linearization point of
+/// every operation, which also lets the anchor carry an exact element count.
+///
+/// A push links its node in two steps: the anchor CAS publishes the new end
+/// and marks the anchor as "pushing right" (or left), and a stabilizing step
+/// then points the old end's forward link at the new node before the anchor
+/// is marked stable again. Any thread that finds an unstable anchor finishes
+/// that step for it before doing anything else.
+///
+/// We chose this design over Sundell–Tsigas or Harris-style lists with back
+/// links because their backward links are only hints that may keep pointing
+/// at deleted nodes indefinitely. That needs per-node reference counting to
+/// reclaim safely. Here, a node is unreachable as soon as the anchor no
+/// longer includes it, so a small epoch-based collector (see `epoch`) is
+/// enough to free popped nodes safely.
+///
+/// Elements are cloned out on pop and dropped when their node is reclaimed,
+/// matching how `LinkedList` hands out clones of its data.
+use core::fmt;
+use core::marker::PhantomData;
+use std::clone::Clone;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering::SeqCst};
+
+use self::epoch::{Collector, Guard};
+
+mod epoch;
+
+struct Node<T> {
+    data: T,
+    left: AtomicPtr<Node<T>>,
+    right: AtomicPtr<Node<T>>,
+}
+
+/// Picks one of a node's two links.
+type Link<T> = fn(&Node<T>) -> &AtomicPtr<Node<T>>;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Stable,
+    PushingRight,
+    PushingLeft,
+}
+
+/// A snapshot of both ends of the deque. Anchors are never mutated once
+/// published; every change installs a new one.
+struct Anchor<T> {
+    left: *mut Node<T>,
+    right: *mut Node<T>,
+    status: Status,
+    len: usize,
+}
+
+/// A lock-free, multi-producer/multi-consumer deque.
+pub struct ConcurrentDeque<T: Clone + Display> {
+    anchor: AtomicPtr<Anchor<T>>,
+    collector: Collector,
+    marker: PhantomData<*mut Node<T>>,
+}
+
+// Elements are pushed on one thread and cloned or dropped on another, so we
+// need both bounds to share the deque.
+unsafe impl<T: Clone + Display + Send + Sync> Send for ConcurrentDeque<T> {}
+unsafe impl<T: Clone + Display + Send + Sync> Sync for ConcurrentDeque<T> {}
+
+impl<T: Clone + Display> ConcurrentDeque<T> {
+    /// Creates an empty `ConcurrentDeque`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// assert_eq!(deque.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        let anchor = Anchor {
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+            status: Status::Stable,
+            len: 0,
+        };
+        ConcurrentDeque {
+            anchor: AtomicPtr::new(Box::into_raw(Box::new(anchor))),
+            collector: Collector::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements at a single instant.
+    ///
+    /// The count lives in the anchor, so this is exact with respect to every
+    /// push and pop that completed before the call, and consistent with some
+    /// order of the ones running alongside it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_front(2);
+    /// assert_eq!(deque.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        let _guard = self.collector.pin();
+        unsafe { (*self.anchor.load(SeqCst)).len }
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds an element to the front of the deque.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_front(1);
+    /// deque.push_front(2);
+    /// assert_eq!(deque.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&self, value: T) {
+        let guard = self.collector.pin();
+        let node = Node::alloc(value);
+        loop {
+            let current = self.anchor.load(SeqCst);
+            let anchor = unsafe { &*current };
+            if anchor.right.is_null() {
+                if self.swap_anchor(&guard, current, node, node, Status::Stable, 1) {
+                    return;
+                }
+            } else if anchor.status == Status::Stable {
+                unsafe { (*node).right.store(anchor.left, SeqCst) };
+                let (right, len) = (anchor.right, anchor.len + 1);
+                if self.swap_anchor(&guard, current, node, right, Status::PushingLeft, len) {
+                    self.stabilize(&guard, self.anchor.load(SeqCst));
+                    return;
+                }
+            } else {
+                self.stabilize(&guard, current);
+            }
+        }
+    }
+
+    /// Adds an element to the back of the deque.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.to_string(), "1 -> 2");
+    /// ```
+    pub fn push_back(&self, value: T) {
+        let guard = self.collector.pin();
+        let node = Node::alloc(value);
+        loop {
+            let current = self.anchor.load(SeqCst);
+            let anchor = unsafe { &*current };
+            if anchor.right.is_null() {
+                if self.swap_anchor(&guard, current, node, node, Status::Stable, 1) {
+                    return;
+                }
+            } else if anchor.status == Status::Stable {
+                unsafe { (*node).left.store(anchor.right, SeqCst) };
+                let (left, len) = (anchor.left, anchor.len + 1);
+                if self.swap_anchor(&guard, current, left, node, Status::PushingRight, len) {
+                    self.stabilize(&guard, self.anchor.load(SeqCst));
+                    return;
+                }
+            } else {
+                self.stabilize(&guard, current);
+            }
+        }
+    }
+
+    /// Removes an element from the front of the deque and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.pop_front(), Some(1));
+    /// assert_eq!(deque.pop_front(), Some(2));
+    /// assert_eq!(deque.pop_front(), None);
+    /// ```
+    pub fn pop_front(&self) -> Option<T> {
+        let guard = self.collector.pin();
+        loop {
+            let current = self.anchor.load(SeqCst);
+            let anchor = unsafe { &*current };
+            let node = anchor.left;
+            if node.is_null() {
+                return None;
+            }
+            let popped = if anchor.left == anchor.right {
+                let null = ptr::null_mut();
+                self.swap_anchor(&guard, current, null, null, Status::Stable, 0)
+            } else if anchor.status == Status::Stable {
+                let next = unsafe { (*node).right.load(SeqCst) };
+                let (right, len) = (anchor.right, anchor.len - 1);
+                self.swap_anchor(&guard, current, next, right, Status::Stable, len)
+            } else {
+                self.stabilize(&guard, current);
+                false
+            };
+            if popped {
+                return Some(unsafe { Node::retire(&guard, node) });
+            }
+        }
+    }
+
+    /// Removes an element from the back of the deque and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// assert_eq!(deque.pop_back(), Some(2));
+    /// assert_eq!(deque.pop_back(), Some(1));
+    /// assert_eq!(deque.pop_back(), None);
+    /// ```
+    pub fn pop_back(&self) -> Option<T> {
+        let guard = self.collector.pin();
+        loop {
+            let current = self.anchor.load(SeqCst);
+            let anchor = unsafe { &*current };
+            let node = anchor.right;
+            if node.is_null() {
+                return None;
+            }
+            let popped = if anchor.left == anchor.right {
+                let null = ptr::null_mut();
+                self.swap_anchor(&guard, current, null, null, Status::Stable, 0)
+            } else if anchor.status == Status::Stable {
+                let prev = unsafe { (*node).left.load(SeqCst) };
+                let (left, len) = (anchor.left, anchor.len - 1);
+                self.swap_anchor(&guard, current, left, prev, Status::Stable, len)
+            } else {
+                self.stabilize(&guard, current);
+                false
+            };
+            if popped {
+                return Some(unsafe { Node::retire(&guard, node) });
+            }
+        }
+    }
+
+    /// Returns an iterator over a snapshot of the deque.
+    ///
+    /// The snapshot is linearizable: it is exactly the contents of the deque
+    /// at one instant between the call and its return. Taking it walks the
+    /// nodes and then checks that the anchor didn't move in the meantime,
+    /// retrying if it did, so under heavy write traffic this can take a few
+    /// attempts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::concurrent::ConcurrentDeque;
+    /// let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// let mut iter = deque.iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> SnapshotIter<T> {
+        let guard = self.collector.pin();
+        loop {
+            let current = self.anchor.load(SeqCst);
+            let anchor = unsafe { &*current };
+            if anchor.status != Status::Stable {
+                self.stabilize(&guard, current);
+                continue;
+            }
+
+            // In a stable anchor every link between the two ends is in place.
+            let mut items = Vec::with_capacity(anchor.len);
+            let mut node = anchor.left;
+            for i in 0..anchor.len {
+                let n = unsafe { &*node };
+                items.push(n.data.clone());
+                if i + 1 < anchor.len {
+                    node = n.right.load(SeqCst);
+                }
+            }
+            if self.anchor.load(SeqCst) == current {
+                return SnapshotIter {
+                    items: items.into_iter(),
+                };
+            }
+        }
+    }
+
+    /// Tries to replace `current` with a new anchor, retiring `current` if
+    /// the swap succeeds.
+    fn swap_anchor(
+        &self,
+        guard: &Guard<'_>,
+        current: *mut Anchor<T>,
+        left: *mut Node<T>,
+        right: *mut Node<T>,
+        status: Status,
+        len: usize,
+    ) -> bool {
+        let new = Box::into_raw(Box::new(Anchor {
+            left,
+            right,
+            status,
+            len,
+        }));
+        match self.anchor.compare_exchange(current, new, SeqCst, SeqCst) {
+            Ok(_) => {
+                unsafe { guard.defer_destroy(current) };
+                true
+            }
+            Err(_) => {
+                // Never published, so nobody else can have seen it.
+                drop(unsafe { Box::from_raw(new) });
+                false
+            }
+        }
+    }
+
+    /// Finishes linking a half-pushed end and marks the anchor stable.
+    fn stabilize(&self, guard: &Guard<'_>, current: *mut Anchor<T>) {
+        let anchor = unsafe { &*current };
+        let (new_end, inner_link, outer_link): (_, Link<T>, Link<T>) = match anchor.status {
+            Status::Stable => return,
+            Status::PushingRight => (anchor.right, Node::left_link, Node::right_link),
+            Status::PushingLeft => (anchor.left, Node::right_link, Node::left_link),
+        };
+
+        // The old end is only guaranteed to still be in the deque while the
+        // anchor hasn't moved on, so re-check after reading it.
+        let old_end = inner_link(unsafe { &*new_end }).load(SeqCst);
+        if self.anchor.load(SeqCst) != current {
+            return;
+        }
+        let link = outer_link(unsafe { &*old_end });
+        let target = link.load(SeqCst);
+        if target != new_end {
+            if self.anchor.load(SeqCst) != current {
+                return;
+            }
+            if link
+                .compare_exchange(target, new_end, SeqCst, SeqCst)
+                .is_err()
+            {
+                return;
+            }
+        }
+        let (left, right, len) = (anchor.left, anchor.right, anchor.len);
+        self.swap_anchor(guard, current, left, right, Status::Stable, len);
+    }
+}
+
+impl<T> Node<T> {
+    fn alloc(data: T) -> *mut Node<T> {
+        Box::into_raw(Box::new(Node {
+            data,
+            left: AtomicPtr::new(ptr::null_mut()),
+            right: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+
+    fn left_link(&self) -> &AtomicPtr<Node<T>> {
+        &self.left
+    }
+
+    fn right_link(&self) -> &AtomicPtr<Node<T>> {
+        &self.right
+    }
+
+    /// Clones the data out of a node this thread just unlinked and hands the
+    /// node to the collector.
+    unsafe fn retire(guard: &Guard<'_>, node: *mut Node<T>) -> T
+    where
+        T: Clone,
+    {
+        let data = (*node).data.clone();
+        guard.defer_destroy(node);
+        data
+    }
+}
+
+impl<T: Clone + Display> Default for ConcurrentDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Drop for ConcurrentDeque<T> {
+    fn drop(&mut self) {
+        // Nobody else can be inside an operation, so we can walk the nodes
+        // directly. Links towards the inside are always in place, but the
+        // outermost link on a half-pushed end may not be yet, so we walk away
+        // from the end that is (or might be) still being pushed.
+        let anchor = unsafe { Box::from_raw(*self.anchor.get_mut()) };
+        let (mut node, step): (_, Link<T>) = match anchor.status {
+            Status::PushingLeft => (anchor.left, Node::right_link),
+            _ => (anchor.right, Node::left_link),
+        };
+        for _ in 0..anchor.len {
+            let boxed = unsafe { Box::from_raw(node) };
+            node = step(&boxed).load(SeqCst);
+        }
+    }
+}
+
+impl<T: Clone + Display> Display for ConcurrentDeque<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over a consistent snapshot of a `ConcurrentDeque`.
+pub struct SnapshotIter<T> {
+    items: std::vec::IntoIter<T>,
+}
+
+impl<T> Iterator for SnapshotIter<T> {
+    type Item = T;
+
+    /// Returns the next element of the snapshot.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.items.next()
+    }
+}
@@ -0,0 +1,184 @@
+/// A tiny epoch-based memory reclamation scheme.
+///
+/// Lock-free structures unlink nodes with a single CAS, but other threads may
+/// still be reading a node they loaded just before it was unlinked, so it
+/// can't be freed on the spot. Instead every operation "pins" the current
+/// global epoch for as long as it touches shared nodes, and unlinked nodes
+/// are "retired" into a bag tagged with the epoch they were retired in.
+///
+/// The global epoch only advances once every pinned thread has caught up with
+/// it, so once it has moved two steps past a retired node's tag, no thread
+/// can still hold a reference to that node and it is freed.
+///
+/// Each `Collector` keeps a push-only, lock-free list of participant slots.
+/// Pinning claims a free slot with a CAS (allocating a new one if all are in
+/// use), so no operation ever waits on another thread.
+use core::marker::PhantomData;
+use std::cell::UnsafeCell;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize, Ordering::SeqCst};
+
+/// A retired allocation waiting for it to be safe to free.
+struct Deferred {
+    ptr: *mut u8,
+    free: unsafe fn(*mut u8),
+    epoch: usize,
+}
+
+/// A participant record. `state` is `(epoch << 1) | 1` while pinned.
+struct Slot {
+    in_use: AtomicBool,
+    state: AtomicUsize,
+    garbage: UnsafeCell<Vec<Deferred>>,
+    next: *mut Slot,
+}
+
+pub(crate) struct Collector {
+    epoch: AtomicUsize,
+    slots: AtomicPtr<Slot>,
+}
+
+// Slots are only ever mutated by the thread that has claimed them, and the
+// deferred pointers are owned allocations handed over by `defer_destroy`.
+unsafe impl Send for Collector {}
+unsafe impl Sync for Collector {}
+
+/// Keeps the current thread pinned until dropped.
+pub(crate) struct Guard<'a> {
+    collector: &'a Collector,
+    slot: &'a Slot,
+    marker: PhantomData<*mut ()>, // a guard must stay on its own thread
+}
+
+unsafe fn free_box<T>(ptr: *mut u8) {
+    drop(Box::from_raw(ptr as *mut T));
+}
+
+impl Collector {
+    pub(crate) fn new() -> Self {
+        Collector {
+            epoch: AtomicUsize::new(0),
+            slots: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    /// Pins the current thread, protecting every node it loads from being
+    /// freed until the returned guard is dropped.
+    pub(crate) fn pin(&self) -> Guard<'_> {
+        let slot = self.claim_slot();
+        let epoch = self.epoch.load(SeqCst);
+        slot.state.store((epoch << 1) | 1, SeqCst);
+        fence(SeqCst);
+        Guard {
+            collector: self,
+            slot,
+            marker: PhantomData,
+        }
+    }
+
+    fn claim_slot(&self) -> &Slot {
+        let mut current = self.slots.load(SeqCst);
+        while let Some(slot) = unsafe { current.as_ref() } {
+            if slot
+                .in_use
+                .compare_exchange(false, true, SeqCst, SeqCst)
+                .is_ok()
+            {
+                return slot;
+            }
+            current = slot.next;
+        }
+
+        // Every slot is taken: publish a fresh one at the head of the list.
+        let slot = Box::into_raw(Box::new(Slot {
+            in_use: AtomicBool::new(true),
+            state: AtomicUsize::new(0),
+            garbage: UnsafeCell::new(Vec::new()),
+            next: ptr::null_mut(),
+        }));
+        loop {
+            let head = self.slots.load(SeqCst);
+            unsafe { (*slot).next = head };
+            if self
+                .slots
+                .compare_exchange(head, slot, SeqCst, SeqCst)
+                .is_ok()
+            {
+                return unsafe { &*slot };
+            }
+        }
+    }
+
+    /// Advances the global epoch if every pinned slot has observed the
+    /// current one, and returns the (possibly new) global epoch.
+    fn try_advance(&self) -> usize {
+        let epoch = self.epoch.load(SeqCst);
+        let mut current = self.slots.load(SeqCst);
+        while let Some(slot) = unsafe { current.as_ref() } {
+            let state = slot.state.load(SeqCst);
+            if state & 1 == 1 && state >> 1 != epoch {
+                return epoch;
+            }
+            current = slot.next;
+        }
+        match self
+            .epoch
+            .compare_exchange(epoch, epoch + 1, SeqCst, SeqCst)
+        {
+            Ok(_) => epoch + 1,
+            Err(actual) => actual,
+        }
+    }
+}
+
+impl Drop for Collector {
+    // Dropping the collector means no thread can be pinned any more, so all
+    // remaining garbage is freed straight away.
+    fn drop(&mut self) {
+        let mut current = *self.slots.get_mut();
+        while !current.is_null() {
+            let slot = unsafe { Box::from_raw(current) };
+            for deferred in slot.garbage.into_inner() {
+                unsafe { (deferred.free)(deferred.ptr) };
+            }
+            current = slot.next;
+        }
+    }
+}
+
+impl Guard<'_> {
+    /// Schedules a `Box` allocation to be freed once no pinned thread can
+    /// still be reading it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must come from `Box::<T>::into_raw`, must already be unreachable
+    /// for threads that pin after this call, and must not be retired twice.
+    pub(crate) unsafe fn defer_destroy<T>(&self, ptr: *mut T) {
+        let epoch = self.collector.epoch.load(SeqCst);
+        (*self.slot.garbage.get()).push(Deferred {
+            ptr: ptr as *mut u8,
+            free: free_box::<T>,
+            epoch,
+        });
+    }
+}
+
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        self.slot.state.store(0, SeqCst);
+        let epoch = self.collector.try_advance();
+
+        // We still own the slot, so its garbage bag is ours alone.
+        let garbage = unsafe { &mut *self.slot.garbage.get() };
+        garbage.retain(|deferred| {
+            if deferred.epoch + 2 <= epoch {
+                unsafe { (deferred.free)(deferred.ptr) };
+                false
+            } else {
+                true
+            }
+        });
+        self.slot.in_use.store(false, SeqCst);
+    }
+}
@@ -0,0 +1,496 @@
+/// Container traits for code that shouldn't care what it is backed by.
+///
+/// `Deque`, `Stack` and `Queue` describe the three usual ways of using a
+/// sequence from its ends, and are implemented for `GenericLinkedList`,
+/// `VecDeque` and `Vec`. Code written against the traits can switch between
+/// them after benchmarking. Peeking returns a clone, since a linked list
+/// can't hand out a plain reference into a node's cell.
+///
+/// Every container implements all three, but not equally well: popping from
+/// the front of a `Vec` shifts all of its elements, so `Vec` is only a good
+/// `Stack`.
+///
+/// `LinkedStack` and `LinkedQueue` wrap a `LinkedList` and expose only the
+/// operations of a stack or a queue, for when the other end should be out of
+/// reach.
+use core::fmt;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use super::list_iter::ListIter;
+use super::observer::ObserverFamily;
+use super::{GenericLinkedList, LinkedList};
+
+/// A double-ended queue: pushes, pops and peeks at both ends.
+pub trait Deque<T> {
+    /// Adds an element to the front.
+    fn push_front(&mut self, value: T);
+
+    /// Adds an element to the back.
+    fn push_back(&mut self, value: T);
+
+    /// Removes the front element and returns it.
+    fn pop_front(&mut self) -> Option<T>;
+
+    /// Removes the back element and returns it.
+    fn pop_back(&mut self) -> Option<T>;
+
+    /// Returns a copy of the front element.
+    fn peek_front(&self) -> Option<T>;
+
+    /// Returns a copy of the back element.
+    fn peek_back(&self) -> Option<T>;
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A last-in, first-out stack.
+pub trait Stack<T> {
+    /// Adds an element to the top.
+    fn push(&mut self, value: T);
+
+    /// Removes the top element and returns it.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Returns a copy of the top element.
+    fn peek(&self) -> Option<T>;
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A first-in, first-out queue.
+pub trait Queue<T> {
+    /// Adds an element to the back.
+    fn enqueue(&mut self, value: T);
+
+    /// Removes the front element and returns it.
+    fn dequeue(&mut self) -> Option<T>;
+
+    /// Returns a copy of the front element, the next one to be dequeued.
+    fn front(&self) -> Option<T>;
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+// GenericLinkedList
+/////////////////////////////////////////////////////////////////////////
+
+impl<T: Clone + Display, P: ObserverFamily> Deque<T> for GenericLinkedList<T, P> {
+    fn push_front(&mut self, value: T) {
+        GenericLinkedList::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        GenericLinkedList::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        GenericLinkedList::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        GenericLinkedList::pop_back(self)
+    }
+
+    fn peek_front(&self) -> Option<T> {
+        self.head.as_ref().map(|node| P::borrow(node).data.clone())
+    }
+
+    fn peek_back(&self) -> Option<T> {
+        self.tail.as_ref().map(|node| P::borrow(node).data.clone())
+    }
+
+    fn len(&self) -> usize {
+        GenericLinkedList::len(self)
+    }
+}
+
+/// The top of the stack is the back of the list, as with `Vec`.
+impl<T: Clone + Display, P: ObserverFamily> Stack<T> for GenericLinkedList<T, P> {
+    fn push(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek(&self) -> Option<T> {
+        Deque::peek_back(self)
+    }
+
+    fn len(&self) -> usize {
+        GenericLinkedList::len(self)
+    }
+}
+
+impl<T: Clone + Display, P: ObserverFamily> Queue<T> for GenericLinkedList<T, P> {
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn front(&self) -> Option<T> {
+        Deque::peek_front(self)
+    }
+
+    fn len(&self) -> usize {
+        GenericLinkedList::len(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+// VecDeque
+/////////////////////////////////////////////////////////////////////////
+
+impl<T: Clone> Deque<T> for VecDeque<T> {
+    fn push_front(&mut self, value: T) {
+        VecDeque::push_front(self, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        VecDeque::push_back(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecDeque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        VecDeque::pop_back(self)
+    }
+
+    fn peek_front(&self) -> Option<T> {
+        VecDeque::front(self).cloned()
+    }
+
+    fn peek_back(&self) -> Option<T> {
+        VecDeque::back(self).cloned()
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+}
+
+impl<T: Clone> Stack<T> for VecDeque<T> {
+    fn push(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek(&self) -> Option<T> {
+        self.back().cloned()
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+}
+
+impl<T: Clone> Queue<T> for VecDeque<T> {
+    fn enqueue(&mut self, value: T) {
+        self.push_back(value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+
+    fn front(&self) -> Option<T> {
+        VecDeque::front(self).cloned()
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+// Vec
+/////////////////////////////////////////////////////////////////////////
+
+/// The front operations are O(n), since they shift every element.
+impl<T: Clone> Deque<T> for Vec<T> {
+    fn push_front(&mut self, value: T) {
+        self.insert(0, value);
+    }
+
+    fn push_back(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        if Vec::is_empty(self) {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn peek_front(&self) -> Option<T> {
+        self.first().cloned()
+    }
+
+    fn peek_back(&self) -> Option<T> {
+        self.last().cloned()
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl<T: Clone> Stack<T> for Vec<T> {
+    fn push(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn peek(&self) -> Option<T> {
+        self.last().cloned()
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+/// `dequeue` is O(n), since it shifts every element.
+impl<T: Clone> Queue<T> for Vec<T> {
+    fn enqueue(&mut self, value: T) {
+        Vec::push(self, value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        Deque::pop_front(self)
+    }
+
+    fn front(&self) -> Option<T> {
+        self.first().cloned()
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+// Restricted wrappers
+/////////////////////////////////////////////////////////////////////////
+
+/// A stack backed by a `LinkedList`, with no way to reach the bottom.
+pub struct LinkedStack<T: Clone + Display> {
+    list: LinkedList<T>,
+}
+
+impl<T: Clone + Display> LinkedStack<T> {
+    /// Creates an empty stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedStack;
+    /// let mut stack = LinkedStack::new();
+    /// stack.push(1);
+    /// stack.push(2);
+    /// assert_eq!(stack.peek(), Some(2));
+    /// assert_eq!(stack.pop(), Some(2));
+    /// assert_eq!(stack.len(), 1);
+    /// ```
+    pub fn new() -> Self {
+        LinkedStack {
+            list: LinkedList::new(),
+        }
+    }
+
+    /// Adds an element to the top of the stack.
+    pub fn push(&mut self, value: T) {
+        self.list.push_back(value);
+    }
+
+    /// Removes the top element and returns it.
+    pub fn pop(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    /// Returns a copy of the top element.
+    pub fn peek(&self) -> Option<T> {
+        Stack::peek(&self.list)
+    }
+
+    /// Returns the number of elements on the stack.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Removes every element.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Returns an iterator from the bottom of the stack to the top.
+    pub fn iter(&self) -> ListIter<T> {
+        self.list.iter()
+    }
+}
+
+impl<T: Clone + Display> Stack<T> for LinkedStack<T> {
+    fn push(&mut self, value: T) {
+        LinkedStack::push(self, value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        LinkedStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<T> {
+        LinkedStack::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        LinkedStack::len(self)
+    }
+}
+
+impl<T: Clone + Display> Default for LinkedStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Display for LinkedStack<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
+
+/// A queue backed by a `LinkedList`: elements go in at the back and come out
+/// at the front, and nothing else.
+pub struct LinkedQueue<T: Clone + Display> {
+    list: LinkedList<T>,
+}
+
+impl<T: Clone + Display> LinkedQueue<T> {
+    /// Creates an empty queue.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedQueue;
+    /// let mut queue = LinkedQueue::new();
+    /// queue.enqueue(1);
+    /// queue.enqueue(2);
+    /// assert_eq!(queue.front(), Some(1));
+    /// assert_eq!(queue.dequeue(), Some(1));
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    pub fn new() -> Self {
+        LinkedQueue {
+            list: LinkedList::new(),
+        }
+    }
+
+    /// Adds an element to the back of the queue.
+    pub fn enqueue(&mut self, value: T) {
+        self.list.push_back(value);
+    }
+
+    /// Removes the front element and returns it.
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    /// Returns a copy of the front element.
+    pub fn front(&self) -> Option<T> {
+        Queue::front(&self.list)
+    }
+
+    /// Returns the number of elements in the queue.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Removes every element.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Returns an iterator from the front of the queue to the back.
+    pub fn iter(&self) -> ListIter<T> {
+        self.list.iter()
+    }
+}
+
+impl<T: Clone + Display> Queue<T> for LinkedQueue<T> {
+    fn enqueue(&mut self, value: T) {
+        LinkedQueue::enqueue(self, value);
+    }
+
+    fn dequeue(&mut self) -> Option<T> {
+        LinkedQueue::dequeue(self)
+    }
+
+    fn front(&self) -> Option<T> {
+        LinkedQueue::front(self)
+    }
+
+    fn len(&self) -> usize {
+        LinkedQueue::len(self)
+    }
+}
+
+impl<T: Clone + Display> Default for LinkedQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Display for LinkedQueue<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
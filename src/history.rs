@@ -0,0 +1,429 @@
+/// An undo/redo history.
+///
+/// `History` owns a piece of state and the edits that led to it. Each edit
+/// is a `Command` that knows how to apply itself and how to revert itself,
+/// and the edits are kept in a `LinkedList` in the order they were made,
+/// with a cursor on the last one that is currently applied:
+///
+/// ```text
+///            applied             cursor     undone
+///  head [type "a"] <-> [type "b"] <-> [bold] <-> [paste] tail
+/// ```
+///
+/// Undoing reverts the cursor's entry and steps back along its `prev` link;
+/// redoing steps forward and applies the entry it lands on. A new edit made
+/// after undoing drops the undone entries behind the cursor, so the history
+/// branches off from there. When the history is deeper than its limit, the
+/// oldest entries fall off the front, baking their changes into the state.
+///
+/// Commands can be grouped into a transaction between `begin` and `commit`.
+/// They are applied as they come in and recorded as a single entry, so one
+/// `undo` reverts all of them.
+///
+/// `set` records whole states instead of commands, swapping the old state
+/// into the entry so that undoing can swap it back.
+use core::fmt;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+/// A reversible change to a state of type `S`.
+///
+/// `revert` is only ever called right after `apply` (or after a `redo` that
+/// applied it again), so a command can remember in `apply` whatever it needs
+/// to undo itself.
+pub trait Command<S> {
+    /// Makes the change.
+    fn apply(&mut self, state: &mut S);
+
+    /// Undoes the change made by the last `apply`.
+    fn revert(&mut self, state: &mut S);
+}
+
+/// A command that replaces the whole state, used by `History::set`. It
+/// holds the state it is going to swap in.
+struct Replace<S>(S);
+
+impl<S> Command<S> for Replace<S> {
+    fn apply(&mut self, state: &mut S) {
+        std::mem::swap(state, &mut self.0);
+    }
+
+    fn revert(&mut self, state: &mut S) {
+        std::mem::swap(state, &mut self.0);
+    }
+}
+
+type Commands<S> = Vec<Box<dyn Command<S>>>;
+
+/// One step of the history: a labelled group of commands.
+struct Entry<S> {
+    label: String,
+    commands: Rc<RefCell<Commands<S>>>,
+}
+
+impl<S> Entry<S> {
+    fn apply(&self, state: &mut S) {
+        for command in self.commands.borrow_mut().iter_mut() {
+            command.apply(state);
+        }
+    }
+
+    fn revert(&self, state: &mut S) {
+        for command in self.commands.borrow_mut().iter_mut().rev() {
+            command.revert(state);
+        }
+    }
+}
+
+// The list clones its data on the way out, so entries share their commands.
+impl<S> Clone for Entry<S> {
+    fn clone(&self) -> Self {
+        Entry {
+            label: self.label.clone(),
+            commands: Rc::clone(&self.commands),
+        }
+    }
+}
+
+impl<S> Display for Entry<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+type EntryNode<S> = StrongPointer<ListNode<Entry<S>>>;
+
+/// A transaction that has been started but not committed yet.
+struct Transaction<S> {
+    label: String,
+    commands: Commands<S>,
+    // How many `begin`s are still waiting for their `commit`.
+    depth: usize,
+}
+
+/// A state together with the undo/redo history of the commands applied to
+/// it.
+pub struct History<S> {
+    state: S,
+    entries: LinkedList<Entry<S>>,
+    // The last applied entry, or `None` if everything has been undone.
+    cursor: Option<EntryNode<S>>,
+    max_depth: usize,
+    transaction: Option<Transaction<S>>,
+}
+
+impl<S> History<S> {
+    /// Creates a history of `state` that remembers up to `max_depth` entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::history::History;
+    /// let history = History::new(String::new(), 100);
+    /// assert_eq!(history.state(), "");
+    /// assert!(!history.can_undo());
+    /// ```
+    pub fn new(state: S, max_depth: usize) -> Self {
+        History {
+            state,
+            entries: LinkedList::new(),
+            cursor: None,
+            max_depth,
+            transaction: None,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns the number of entries in the history, applied or undone.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the history has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of entries the history keeps.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Returns `true` if there is an entry to undo.
+    pub fn can_undo(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// Returns `true` if there is an entry to redo.
+    pub fn can_redo(&self) -> bool {
+        self.next_entry().is_some()
+    }
+
+    /// Returns the label of the entry `undo` would revert.
+    pub fn undo_label(&self) -> Option<String> {
+        self.cursor
+            .as_ref()
+            .map(|node| node.borrow().data.label.clone())
+    }
+
+    /// Returns the label of the entry `redo` would apply.
+    pub fn redo_label(&self) -> Option<String> {
+        self.next_entry()
+            .map(|node| node.borrow().data.label.clone())
+    }
+
+    /// Applies `command` to the state and records it under `label`. Inside a
+    /// transaction the command joins the transaction instead.
+    ///
+    /// Any undone entries are dropped, and if the history is now deeper than
+    /// its limit the oldest entry is forgotten.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::history::{Command, History};
+    /// struct Push(char);
+    ///
+    /// impl Command<String> for Push {
+    ///     fn apply(&mut self, text: &mut String) {
+    ///         text.push(self.0);
+    ///     }
+    ///     fn revert(&mut self, text: &mut String) {
+    ///         text.pop();
+    ///     }
+    /// }
+    ///
+    /// let mut history = History::new(String::new(), 100);
+    /// history.execute("type a", Push('a'));
+    /// history.execute("type b", Push('b'));
+    /// assert_eq!(history.state(), "ab");
+    /// history.undo();
+    /// assert_eq!(history.state(), "a");
+    /// history.execute("type c", Push('c'));
+    /// assert_eq!(history.state(), "ac");
+    /// assert!(!history.redo());
+    /// ```
+    pub fn execute<C: Command<S> + 'static>(&mut self, label: impl Into<String>, command: C) {
+        let mut command: Box<dyn Command<S>> = Box::new(command);
+        command.apply(&mut self.state);
+        match self.transaction.as_mut() {
+            Some(transaction) => transaction.commands.push(command),
+            None => self.record(label.into(), vec![command]),
+        }
+    }
+
+    /// Replaces the state with `state` and records the change under `label`,
+    /// keeping the old state around for `undo`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::history::History;
+    /// let mut history = History::new(0, 10);
+    /// history.set("one", 1);
+    /// history.set("two", 2);
+    /// history.undo();
+    /// assert_eq!(*history.state(), 1);
+    /// history.redo();
+    /// assert_eq!(*history.state(), 2);
+    /// ```
+    pub fn set(&mut self, label: impl Into<String>, state: S)
+    where
+        S: 'static,
+    {
+        self.execute(label, Replace(state));
+    }
+
+    /// Starts a transaction: the commands executed until the matching
+    /// `commit` are recorded as a single entry labelled `label`.
+    ///
+    /// Transactions nest. An inner `begin` only has to be matched by a
+    /// `commit`, and its commands become part of the outer transaction.
+    pub fn begin(&mut self, label: impl Into<String>) {
+        match self.transaction.as_mut() {
+            Some(transaction) => transaction.depth += 1,
+            None => {
+                self.transaction = Some(Transaction {
+                    label: label.into(),
+                    commands: Vec::new(),
+                    depth: 1,
+                })
+            }
+        }
+    }
+
+    /// Ends the innermost transaction. Ending the outermost one records its
+    /// commands, if there were any, as one entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transaction is in progress.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::history::History;
+    /// let mut history = History::new(0, 10);
+    /// history.begin("count to three");
+    /// for i in 1..=3 {
+    ///     history.set("count", i);
+    /// }
+    /// history.commit();
+    /// assert_eq!(history.len(), 1);
+    /// history.undo();
+    /// assert_eq!(*history.state(), 0);
+    /// ```
+    pub fn commit(&mut self) {
+        let transaction = self
+            .transaction
+            .as_mut()
+            .expect("no transaction in progress");
+        transaction.depth -= 1;
+        if transaction.depth == 0 {
+            let transaction = self.transaction.take().unwrap();
+            if !transaction.commands.is_empty() {
+                self.record(transaction.label, transaction.commands);
+            }
+        }
+    }
+
+    /// Abandons the transaction in progress, including any outer ones it is
+    /// nested in, and reverts the commands executed in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no transaction is in progress.
+    pub fn rollback(&mut self) {
+        let transaction = self.transaction.take().expect("no transaction in progress");
+        for mut command in transaction.commands.into_iter().rev() {
+            command.revert(&mut self.state);
+        }
+    }
+
+    /// Returns `true` if a transaction is in progress.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Reverts the last applied entry. Returns `false` if there was nothing
+    /// to undo.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a transaction is in progress.
+    pub fn undo(&mut self) -> bool {
+        assert!(self.transaction.is_none(), "transaction in progress");
+        match self.cursor.take() {
+            Some(node) => {
+                node.borrow().data.revert(&mut self.state);
+                self.cursor = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies the entry after the cursor again. Returns `false` if there was
+    /// nothing to redo.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a transaction is in progress.
+    pub fn redo(&mut self) -> bool {
+        assert!(self.transaction.is_none(), "transaction in progress");
+        match self.next_entry() {
+            Some(node) => {
+                node.borrow().data.apply(&mut self.state);
+                self.cursor = Some(node);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forgets every entry and keeps the current state.
+    pub fn clear(&mut self) {
+        self.cursor = None;
+        self.entries.clear();
+    }
+
+    /// Returns the entry `redo` would apply.
+    fn next_entry(&self) -> Option<EntryNode<S>> {
+        match self.cursor.as_ref() {
+            Some(node) => node.borrow().next.clone(),
+            None => self.entries.head.clone(),
+        }
+    }
+
+    /// Records already applied `commands` as a new entry after the cursor.
+    fn record(&mut self, label: String, commands: Commands<S>) {
+        // Branch off: the undone entries can't be redone on top of this one.
+        while !self.is_tail(self.cursor.as_ref()) {
+            self.entries.pop_back();
+        }
+        let entry = Entry {
+            label,
+            commands: Rc::new(RefCell::new(commands)),
+        };
+        self.entries.push_back(entry);
+        self.cursor = self.entries.tail.clone();
+        while self.entries.len() > self.max_depth {
+            if self.is_tail(self.entries.head.as_ref()) {
+                self.cursor = None;
+            }
+            self.entries.pop_front();
+        }
+    }
+
+    /// Returns `true` if `node` is the last entry, or if `node` is `None`
+    /// and there are no entries.
+    fn is_tail(&self, node: Option<&EntryNode<S>>) -> bool {
+        match (node, self.entries.tail.as_ref()) {
+            (Some(node), Some(tail)) => Rc::ptr_eq(node, tail),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<S> Drop for History<S> {
+    // The cursor is a second strong pointer into the list, so let go of it
+    // before the list unlinks its nodes.
+    fn drop(&mut self) {
+        self.cursor = None;
+    }
+}
+
+impl<S> Display for History<S> {
+    /// Lists the entry labels from oldest to newest, marking the last
+    /// applied one with a `*`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut current = self.entries.head.clone();
+        let mut first = true;
+        while let Some(node) = current {
+            if !first {
+                write!(f, " -> ")?;
+            }
+            first = false;
+            write!(f, "{}", node.borrow().data)?;
+            if self
+                .cursor
+                .as_ref()
+                .is_some_and(|cursor| Rc::ptr_eq(cursor, &node))
+            {
+                write!(f, "*")?;
+            }
+            current = node.borrow().next.clone();
+        }
+        Ok(())
+    }
+}
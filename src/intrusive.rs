@@ -0,0 +1,357 @@
+/// An intrusive doubly-linked list.
+///
+/// In `LinkedList` every element is copied into a `ListNode` allocated by the
+/// list. An `IntrusiveList` turns that around: the value carries its own
+/// links in a `Links<Self>` field, so the value's `Rc` allocation *is* the
+/// node. Linking a value in allocates nothing, and since the value knows its
+/// neighbours, it can be unlinked in O(1) given just a reference to it.
+///
+/// ```text
+///              Rc<Job>                  Rc<Job>
+///        .-----------------.      .-----------------.
+///  head  | name: "build"   |      | name: "test"    |  tail
+///  ----> | queue: next ----+----> | queue: next     | <----
+///        |        prev     | <----+------- prev     |
+///        '-----------------'      '-----------------'
+/// ```
+///
+/// The type says where its links are by implementing `Adapter`. A value can
+/// be in several lists at once if it has a `Links` field for each of them,
+/// with one `Adapter<Tag>` impl per field; the tag is any type that names
+/// the field. Each field can only be in one list at a time: pushing a value
+/// whose links are already in use hands it back instead.
+use core::fmt;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+/// The links an `IntrusiveList` threads through a value.
+pub struct Links<T> {
+    next: RefCell<Option<Rc<T>>>,
+    prev: RefCell<Option<Weak<T>>>,
+    // The list the links are in, if any.
+    owner: RefCell<Option<Weak<()>>>,
+}
+
+impl<T> Links<T> {
+    /// Creates unlinked links.
+    pub fn new() -> Self {
+        Links {
+            next: RefCell::new(None),
+            prev: RefCell::new(None),
+            owner: RefCell::new(None),
+        }
+    }
+
+    /// Returns `true` if these links are in a list.
+    pub fn is_linked(&self) -> bool {
+        self.owner.borrow().is_some()
+    }
+
+    fn is_owned_by(&self, id: &Rc<()>) -> bool {
+        self.owner
+            .borrow()
+            .as_ref()
+            .is_some_and(|owner| std::ptr::eq(owner.as_ptr(), Rc::as_ptr(id)))
+    }
+
+    fn reset(&self) -> Option<Rc<T>> {
+        self.prev.replace(None);
+        self.owner.replace(None);
+        self.next.replace(None)
+    }
+}
+
+impl<T> Default for Links<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tells an `IntrusiveList` where a type keeps its links.
+///
+/// `Tag` tells apart the `Links` fields of a type that can be in more than
+/// one list; a type with a single field can leave it out.
+///
+/// # Example
+///
+/// ```
+/// # use dll::intrusive::{Adapter, IntrusiveList, Links};
+/// # use std::rc::Rc;
+/// struct Task {
+///     id: u32,
+///     all: Links<Task>,
+///     ready: Links<Task>,
+/// }
+///
+/// struct Ready;
+///
+/// impl Adapter for Task {
+///     fn links(&self) -> &Links<Self> {
+///         &self.all
+///     }
+/// }
+///
+/// impl Adapter<Ready> for Task {
+///     fn links(&self) -> &Links<Self> {
+///         &self.ready
+///     }
+/// }
+///
+/// let task = Rc::new(Task { id: 7, all: Links::new(), ready: Links::new() });
+/// let mut all: IntrusiveList<Task> = IntrusiveList::new();
+/// let mut ready: IntrusiveList<Task, Ready> = IntrusiveList::new();
+/// assert!(all.push_back(Rc::clone(&task)).is_ok());
+/// assert!(ready.push_back(Rc::clone(&task)).is_ok());
+/// assert!(ready.remove(&task).is_some());
+/// assert_eq!(all.front().map(|task| task.id), Some(7));
+/// ```
+pub trait Adapter<Tag = ()>: Sized {
+    /// Returns the links this adapter uses.
+    fn links(&self) -> &Links<Self>;
+}
+
+/// A doubly-linked list of `Rc<T>` that keeps its links inside the values.
+pub struct IntrusiveList<T: Adapter<Tag>, Tag = ()> {
+    head: Option<Rc<T>>,
+    tail: Option<Rc<T>>,
+    num_elements: usize,
+    // Identifies this list to the links of its values.
+    id: Rc<()>,
+    marker: PhantomData<Tag>,
+}
+
+impl<T: Adapter<Tag>, Tag> IntrusiveList<T, Tag> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        IntrusiveList {
+            head: None,
+            tail: None,
+            num_elements: 0,
+            id: Rc::new(()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the first value.
+    pub fn front(&self) -> Option<Rc<T>> {
+        self.head.clone()
+    }
+
+    /// Returns the last value.
+    pub fn back(&self) -> Option<Rc<T>> {
+        self.tail.clone()
+    }
+
+    /// Returns `true` if `value` is in this list.
+    pub fn contains(&self, value: &T) -> bool {
+        Self::links(value).is_owned_by(&self.id)
+    }
+
+    /// Links `value` in at the front. If its links are already in a list,
+    /// `value` is handed back.
+    pub fn push_front(&mut self, value: Rc<T>) -> Result<(), Rc<T>> {
+        let next = self.head.clone();
+        self.splice(None, next, value)
+    }
+
+    /// Links `value` in at the back. If its links are already in a list,
+    /// `value` is handed back.
+    pub fn push_back(&mut self, value: Rc<T>) -> Result<(), Rc<T>> {
+        let prev = self.tail.clone();
+        self.splice(prev, None, value)
+    }
+
+    /// Links `value` in right after `anchor`. If `value`'s links are already
+    /// in a list, or `anchor` isn't in this one, `value` is handed back.
+    pub fn insert_after(&mut self, anchor: &T, value: Rc<T>) -> Result<(), Rc<T>> {
+        if !self.contains(anchor) {
+            return Err(value);
+        }
+        // `anchor` is a plain reference, so find the list's `Rc` to it
+        // through its successor's `prev` link, or the tail.
+        let next = Self::links(anchor).next.borrow().clone();
+        let prev = match next.as_ref() {
+            Some(next) => Self::prev_of(next),
+            None => self.tail.clone(),
+        };
+        self.splice(prev, next, value)
+    }
+
+    /// Unlinks the first value and returns it.
+    pub fn pop_front(&mut self) -> Option<Rc<T>> {
+        let head = self.head.clone()?;
+        self.remove(&head)
+    }
+
+    /// Unlinks the last value and returns it.
+    pub fn pop_back(&mut self) -> Option<Rc<T>> {
+        let tail = self.tail.clone()?;
+        self.remove(&tail)
+    }
+
+    /// Unlinks `value` in O(1) and returns the list's pointer to it, or
+    /// `None` if it isn't in this list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::intrusive::{Adapter, IntrusiveList, Links};
+    /// # use std::rc::Rc;
+    /// struct Item(u32, Links<Item>);
+    ///
+    /// impl Adapter for Item {
+    ///     fn links(&self) -> &Links<Self> {
+    ///         &self.1
+    ///     }
+    /// }
+    ///
+    /// let items: Vec<Rc<Item>> = (0..3).map(|i| Rc::new(Item(i, Links::new()))).collect();
+    /// let mut list = IntrusiveList::new();
+    /// for item in &items {
+    ///     list.push_back(Rc::clone(item)).ok();
+    /// }
+    /// assert!(list.remove(&items[1]).is_some());
+    /// assert!(list.remove(&items[1]).is_none());
+    /// let left: Vec<u32> = list.iter().map(|item| item.0).collect();
+    /// assert_eq!(left, [0, 2]);
+    /// ```
+    pub fn remove(&mut self, value: &T) -> Option<Rc<T>> {
+        if !self.contains(value) {
+            return None;
+        }
+        let links = Self::links(value);
+        let prev = links.prev.borrow().as_ref().and_then(Weak::upgrade);
+        let next = links.reset();
+        self.set_prev(next.as_ref(), prev.as_ref());
+        let this = self.set_next(prev.as_ref(), next);
+        self.num_elements -= 1;
+        this
+    }
+
+    /// Returns an iterator over the values, front to back.
+    pub fn iter(&self) -> IntrusiveIter<T, Tag> {
+        IntrusiveIter {
+            current: self.head.clone(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Unlinks every value.
+    pub fn clear(&mut self) {
+        self.tail = None;
+        let mut current = self.head.take();
+        while let Some(value) = current {
+            current = Self::links(&value).reset();
+        }
+        self.num_elements = 0;
+    }
+
+    fn links(value: &T) -> &Links<T> {
+        <T as Adapter<Tag>>::links(value)
+    }
+
+    fn prev_of(value: &Rc<T>) -> Option<Rc<T>> {
+        Self::links(value)
+            .prev
+            .borrow()
+            .as_ref()
+            .and_then(Weak::upgrade)
+    }
+
+    /// Links `value` in between two adjacent values, `None` standing for the
+    /// list itself as in `GenericLinkedList::splice`.
+    fn splice(
+        &mut self,
+        prev: Option<Rc<T>>,
+        next: Option<Rc<T>>,
+        value: Rc<T>,
+    ) -> Result<(), Rc<T>> {
+        let links = Self::links(&value);
+        if links.is_linked() {
+            return Err(value);
+        }
+        links.owner.replace(Some(Rc::downgrade(&self.id)));
+        links.prev.replace(prev.as_ref().map(Rc::downgrade));
+        links.next.replace(next.clone());
+        self.set_prev(next.as_ref(), Some(&value));
+        self.set_next(prev.as_ref(), Some(value));
+        self.num_elements += 1;
+        Ok(())
+    }
+
+    /// Points the `next` link of `value` (or the list's `head`) at `next`
+    /// and returns what it pointed at before.
+    fn set_next(&mut self, value: Option<&Rc<T>>, next: Option<Rc<T>>) -> Option<Rc<T>> {
+        match value {
+            Some(value) => Self::links(value).next.replace(next),
+            None => std::mem::replace(&mut self.head, next),
+        }
+    }
+
+    /// Points the `prev` link of `value` (or the list's `tail`) at `prev`.
+    fn set_prev(&mut self, value: Option<&Rc<T>>, prev: Option<&Rc<T>>) {
+        match value {
+            Some(value) => {
+                Self::links(value).prev.replace(prev.map(Rc::downgrade));
+            }
+            None => self.tail = prev.cloned(),
+        }
+    }
+}
+
+impl<T: Adapter<Tag>, Tag> Default for IntrusiveList<T, Tag> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Adapter<Tag>, Tag> Drop for IntrusiveList<T, Tag> {
+    // Unlink every value, so that they can go into other lists afterwards and
+    // so that a long chain isn't freed recursively.
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Adapter<Tag> + Display, Tag> Display for IntrusiveList<T, Tag> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the values of an `IntrusiveList`.
+pub struct IntrusiveIter<T: Adapter<Tag>, Tag = ()> {
+    current: Option<Rc<T>>,
+    marker: PhantomData<Tag>,
+}
+
+impl<T: Adapter<Tag>, Tag> Iterator for IntrusiveIter<T, Tag> {
+    type Item = Rc<T>;
+
+    /// Returns the next value of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.current.take()?;
+        self.current = <T as Adapter<Tag>>::links(&value).next.borrow().clone();
+        Some(value)
+    }
+}
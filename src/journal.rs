@@ -0,0 +1,196 @@
+/// An operation journal for `GenericLinkedList`.
+///
+/// While journaling is on (see `start_journal`), every call to a mutating
+/// method that changes the list appends an `Op` to the list's `Journal`;
+/// pops on an empty list and out-of-bounds removes are left out. Replaying
+/// the ops in order on an empty list, with `apply` or `replay`, rebuilds the
+/// same list, which is enough to reproduce a bug from a log or to keep a
+/// replica in another process up to date by draining the journal and
+/// shipping the ops over.
+///
+/// `retain` and `sort` take closures, which can't be written down, so they
+/// are journaled by their outcome instead: which elements were kept, and
+/// where each element ended up. That also makes replay deterministic even
+/// if the closure wasn't.
+///
+/// An `Op` is written as one line of text by `Display` and read back by
+/// `FromStr`:
+///
+/// ```text
+///  push_front <value>    pop_front    insert <index> <value>    clear
+///  push_back <value>     pop_back     remove <index>
+///  retain <one 0 or 1 per element>    sort <old index per new position>
+/// ```
+///
+/// Values are written with their own `Display` and parsed with their own
+/// `FromStr`, taking up the rest of the line, so they must not contain line
+/// breaks. Values changed in place through `iter_mut` aren't journaled.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// A single journaled call to a mutating method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op<T> {
+    /// A `push_front` of the value.
+    PushFront(T),
+    /// A `push_back` of the value.
+    PushBack(T),
+    /// A `pop_front`.
+    PopFront,
+    /// A `pop_back`.
+    PopBack,
+    /// An `insert` of `value` at `index`.
+    Insert { index: usize, value: T },
+    /// A `remove` at `index`.
+    Remove { index: usize },
+    /// A `retain`, with whether each element was kept, front to back.
+    Retain { keep: Vec<bool> },
+    /// A `sort`. `order[i]` is the position the element that ended up at
+    /// `i` had before sorting.
+    Sort { order: Vec<usize> },
+    /// A `clear`.
+    Clear,
+}
+
+impl<T: Display> Display for Op<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::PushFront(value) => write!(f, "push_front {}", value),
+            Op::PushBack(value) => write!(f, "push_back {}", value),
+            Op::PopFront => write!(f, "pop_front"),
+            Op::PopBack => write!(f, "pop_back"),
+            Op::Insert { index, value } => write!(f, "insert {} {}", index, value),
+            Op::Remove { index } => write!(f, "remove {}", index),
+            Op::Retain { keep } => {
+                write!(f, "retain ")?;
+                for &kept in keep {
+                    write!(f, "{}", kept as u8)?;
+                }
+                Ok(())
+            }
+            Op::Sort { order } => {
+                write!(f, "sort")?;
+                for index in order {
+                    write!(f, " {}", index)?;
+                }
+                Ok(())
+            }
+            Op::Clear => write!(f, "clear"),
+        }
+    }
+}
+
+impl<T: FromStr> FromStr for Op<T> {
+    type Err = ParseOpError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let error = || ParseOpError(line.to_string());
+        let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let value = |text: &str| text.parse::<T>().map_err(|_| error());
+        let index = |text: &str| text.parse::<usize>().map_err(|_| error());
+        let op = match name {
+            "push_front" => Op::PushFront(value(rest)?),
+            "push_back" => Op::PushBack(value(rest)?),
+            "pop_front" if rest.is_empty() => Op::PopFront,
+            "pop_back" if rest.is_empty() => Op::PopBack,
+            "insert" => {
+                let (at, rest) = rest.split_once(' ').ok_or_else(error)?;
+                Op::Insert {
+                    index: index(at)?,
+                    value: value(rest)?,
+                }
+            }
+            "remove" => Op::Remove {
+                index: index(rest)?,
+            },
+            "retain" => Op::Retain {
+                keep: rest
+                    .chars()
+                    .map(|bit| match bit {
+                        '0' => Ok(false),
+                        '1' => Ok(true),
+                        _ => Err(error()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            "sort" => Op::Sort {
+                order: rest
+                    .split(' ')
+                    .filter(|part| !part.is_empty())
+                    .map(index)
+                    .collect::<Result<_, _>>()?,
+            },
+            "clear" if rest.is_empty() => Op::Clear,
+            _ => return Err(error()),
+        };
+        Ok(op)
+    }
+}
+
+/// The error returned when a line isn't a valid `Op`. It holds the line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOpError(pub String);
+
+impl Display for ParseOpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid journal line: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseOpError {}
+
+/// The ops journaled by one list, oldest first.
+pub struct Journal<T> {
+    ops: Vec<Op<T>>,
+    recording: bool,
+}
+
+impl<T> Journal<T> {
+    pub(crate) fn new() -> Self {
+        Journal {
+            ops: Vec::new(),
+            recording: false,
+        }
+    }
+
+    /// Returns the number of ops in the journal.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns `true` if the journal holds no ops.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Returns an iterator over the ops, oldest first.
+    pub fn iter(&self) -> std::slice::Iter<'_, Op<T>> {
+        self.ops.iter()
+    }
+
+    /// Removes every op from the journal and returns them, oldest first.
+    /// Journaling carries on with an empty journal.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Op<T>> {
+        self.ops.drain(..)
+    }
+
+    /// Returns `true` if the list is journaling.
+    #[inline(always)]
+    pub(crate) fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub(crate) fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Builds an op with `op` and appends it, if the list is journaling.
+    #[inline(always)]
+    pub(crate) fn record(&mut self, op: impl FnOnce() -> Op<T>) {
+        if self.recording {
+            self.ops.push(op());
+        }
+    }
+}
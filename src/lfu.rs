@@ -0,0 +1,314 @@
+/// A least-frequently-used cache with O(1) operations.
+///
+/// `LfuCache` follows the constant-time LFU scheme of Shah, Mitra and Matani.
+/// The cache keeps a `LinkedList` of frequency buckets in increasing order of
+/// frequency, and every bucket holds a `LinkedList` of the keys that have
+/// been used that many times, from least to most recently used. A `HashMap`
+/// maps each key to its value, its bucket node and its key node.
+///
+/// ```text
+///  buckets:  [1: a <-> d] <-> [2: c] <-> [5: b <-> e]
+///             ^ evicted first: `a`, the oldest key used once
+/// ```
+///
+/// Using a key moves its node from its bucket to the back of the next one,
+/// creating that bucket if its frequency isn't there yet and dropping the old
+/// one if it is left empty. Neither step searches, since buckets only ever
+/// grow one at a time and the map points straight at the nodes.
+///
+/// Like `LruCache`, the map shares the list nodes, so the cache empties the
+/// map first when it is dropped.
+use core::fmt;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+type KeyList<K> = Rc<RefCell<LinkedList<K>>>;
+
+/// The keys that have been used `frequency` times.
+#[derive(Clone)]
+struct Bucket<K: Clone + Display> {
+    frequency: usize,
+    keys: KeyList<K>,
+}
+
+impl<K: Clone + Display> Bucket<K> {
+    fn new(frequency: usize) -> Self {
+        Bucket {
+            frequency,
+            keys: Rc::new(RefCell::new(LinkedList::new())),
+        }
+    }
+}
+
+impl<K: Clone + Display> Display for Bucket<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: [{}]", self.frequency, self.keys.borrow())
+    }
+}
+
+/// Where a key's value lives, and where its key sits in the buckets.
+struct Slot<K: Clone + Display, V> {
+    value: V,
+    bucket: StrongPointer<ListNode<Bucket<K>>>,
+    key: StrongPointer<ListNode<K>>,
+}
+
+/// A cache that evicts its least frequently used entry when it is full.
+///
+/// Ties between entries used equally often go to the least recently used
+/// one.
+pub struct LfuCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone,
+{
+    map: HashMap<K, Slot<K, V>>,
+    buckets: LinkedList<Bucket<K>>,
+    capacity: usize,
+}
+
+impl<K, V> LfuCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone,
+{
+    /// Creates an empty cache that holds up to `capacity` entries. A cache
+    /// with no capacity stores nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lfu::LfuCache;
+    /// let cache: LfuCache<&str, u32> = LfuCache::new(2);
+    /// assert_eq!(cache.len(), 0);
+    /// assert_eq!(cache.capacity(), 2);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        LfuCache {
+            map: HashMap::new(),
+            buckets: LinkedList::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the maximum number of entries the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the cache holds `key`. Doesn't count as a use.
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns how many times `key` has been used, counting the `put` that
+    /// stored it, or `None` if the cache doesn't hold it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lfu::LfuCache;
+    /// let mut cache = LfuCache::new(2);
+    /// cache.put("a", 1);
+    /// cache.get(&"a");
+    /// assert_eq!(cache.frequency(&"a"), Some(2));
+    /// assert_eq!(cache.frequency(&"b"), None);
+    /// ```
+    pub fn frequency(&self, key: &K) -> Option<usize> {
+        self.map
+            .get(key)
+            .map(|slot| slot.bucket.borrow().data.frequency)
+    }
+
+    /// Returns a copy of the value stored under `key` and counts it as a
+    /// use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lfu::LfuCache;
+    /// let mut cache = LfuCache::new(2);
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    /// assert_eq!(cache.get(&"b"), Some(2));
+    /// cache.put("c", 3); // evicts "a", the only entry used just once
+    /// assert_eq!(cache.get(&"a"), None);
+    /// ```
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let slot = self.map.get_mut(key)?;
+        Self::touch(&mut self.buckets, slot);
+        Some(slot.value.clone())
+    }
+
+    /// Returns a copy of the value stored under `key` without counting it as
+    /// a use.
+    pub fn peek(&self, key: &K) -> Option<V> {
+        self.map.get(key).map(|slot| slot.value.clone())
+    }
+
+    /// Stores a value under `key`. If the key was already present its value
+    /// is replaced, which counts as a use, and the old value is returned.
+    /// Otherwise, if the cache is full, the least frequently used entry is
+    /// evicted to make room.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lfu::LfuCache;
+    /// let mut cache = LfuCache::new(2);
+    /// assert_eq!(cache.put("a", 1), None);
+    /// assert_eq!(cache.put("a", 10), Some(1));
+    /// cache.put("b", 2);
+    /// assert_eq!(cache.to_string(), "1: [b] -> 2: [a]");
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(slot) = self.map.get_mut(&key) {
+            Self::touch(&mut self.buckets, slot);
+            return Some(std::mem::replace(&mut slot.value, value));
+        }
+        if self.capacity == 0 {
+            return None;
+        }
+        if self.len() >= self.capacity {
+            self.pop_lfu();
+        }
+
+        let bucket = match self.buckets.head.clone() {
+            Some(first) if first.borrow().data.frequency == 1 => first,
+            _ => self.buckets.insert_after(None, Bucket::new(1)),
+        };
+        let key_node = {
+            let bucket = bucket.borrow();
+            let mut keys = bucket.data.keys.borrow_mut();
+            keys.push_back(key.clone());
+            keys.tail.clone().unwrap()
+        };
+        self.map.insert(
+            key,
+            Slot {
+                value,
+                bucket,
+                key: key_node,
+            },
+        );
+        None
+    }
+
+    /// Removes the entry stored under `key` and returns its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.map.remove(key)?;
+        self.release(&slot);
+        Some(slot.value)
+    }
+
+    /// Removes the least frequently used entry and returns it. Of several
+    /// entries used equally often, the least recently used one goes first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lfu::LfuCache;
+    /// let mut cache = LfuCache::new(3);
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    /// cache.put("c", 3);
+    /// cache.get(&"a");
+    /// assert_eq!(cache.pop_lfu(), Some(("b", 2)));
+    /// assert_eq!(cache.pop_lfu(), Some(("c", 3)));
+    /// assert_eq!(cache.pop_lfu(), Some(("a", 1)));
+    /// assert_eq!(cache.pop_lfu(), None);
+    /// ```
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let first = self.buckets.head.clone()?;
+        let key = first.borrow().data.keys.borrow().get(0).unwrap();
+        let slot = self.map.remove(&key).unwrap();
+        self.release(&slot);
+        Some((key, slot.value))
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.buckets.clear();
+    }
+
+    /// Moves the key of `slot` up to the bucket for one more use.
+    fn touch(buckets: &mut LinkedList<Bucket<K>>, slot: &mut Slot<K, V>) {
+        let bucket = slot.bucket.clone();
+        let (frequency, keys, next) = {
+            let node = bucket.borrow();
+            (
+                node.data.frequency,
+                node.data.keys.clone(),
+                node.next.clone(),
+            )
+        };
+        let target = match next {
+            Some(next) if next.borrow().data.frequency == frequency + 1 => next,
+            _ => buckets.insert_after(Some(bucket.clone()), Bucket::new(frequency + 1)),
+        };
+
+        keys.borrow_mut().detach(&slot.key);
+        target
+            .borrow()
+            .data
+            .keys
+            .borrow_mut()
+            .attach_back(slot.key.clone());
+        if keys.borrow().is_empty() {
+            buckets.detach(&bucket);
+        }
+        slot.bucket = target;
+    }
+
+    /// Unlinks the key of a slot that was taken out of the map, along with
+    /// its bucket if that is left empty.
+    fn release(&mut self, slot: &Slot<K, V>) {
+        let keys = slot.bucket.borrow().data.keys.clone();
+        keys.borrow_mut().detach(&slot.key);
+        if keys.borrow().is_empty() {
+            self.buckets.detach(&slot.bucket);
+        }
+    }
+}
+
+impl<K, V> Drop for LfuCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone,
+{
+    // Once the map lets go of the nodes, the lists are their only owners
+    // again and can unlink them iteratively.
+    fn drop(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<K, V> Display for LfuCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone,
+{
+    /// Shows the buckets in order of frequency, each with its keys from
+    /// least to most recently used.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.buckets)
+    }
+}
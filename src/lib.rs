@@ -1,33 +1,99 @@
+pub mod arena;
+pub mod bounded;
+pub mod circular;
+pub mod concurrent;
+pub mod deque;
+pub mod history;
+pub mod intrusive;
+pub mod journal;
+pub mod lfu;
 pub mod list_iter;
 pub mod list_node;
+pub mod lock_coupling;
+pub mod lru;
+pub mod observer;
+pub mod persistent;
+pub mod pointer;
+pub mod pool;
+#[cfg(feature = "unsafe-backend")]
+pub mod raw;
+pub mod rope;
+pub mod self_organizing;
+pub mod skip_list;
+pub mod snapshot;
 pub mod sort;
+pub mod sorted;
+pub mod unrolled;
 
+pub use arena::ArenaList;
+pub use bounded::{BoundedList, OverflowPolicy, Weigh};
+pub use circular::CircularList;
+pub use deque::{Deque, LinkedQueue, LinkedStack, Queue, Stack};
+pub use history::{Command, History};
+pub use intrusive::{Adapter, IntrusiveList, Links};
+pub use journal::{Journal, Op, ParseOpError};
+pub use lfu::LfuCache;
 pub use list_iter::ListIter;
 pub use list_node::ListNode;
 pub use list_node::StrongPointer;
+pub use lock_coupling::LockCouplingList;
+pub use lru::LruCache;
+pub use observer::{ListEvent, ObserverFamily, Subscription};
+pub use persistent::PersistentList;
+pub use pointer::{ArcFamily, PointerFamily, RcFamily};
+pub use pool::PoolStats;
+#[cfg(feature = "unsafe-backend")]
+pub use raw::RawLinkedList;
+pub use rope::Rope;
+pub use self_organizing::{AccessStats, Policy, SelfOrganizingList};
+pub use skip_list::SkipList;
+pub use snapshot::Snapshot;
 pub use sort::bubble_sort; // TODO: upgrade to reasonable sorting algorithm
+pub use sorted::{Comparator, NaturalOrder, SortedLinkedList};
+pub use unrolled::UnrolledList;
 
 use core::fmt;
 use core::marker::PhantomData; // for cursors
-use std::cell::RefCell;
 use std::clone::Clone;
 use std::cmp::Ordering;
 use std::fmt::Display;
 use std::fmt::Formatter;
-use std::rc::Rc;
 
 use self::list_iter::ListIterMut;
+use self::observer::Observers;
+use self::pool::NodePool;
+use self::snapshot::Sharing;
 
 /// A doubly-linked list from hell >:)
 ///
 /// This `LinkedList` allows pushing and popping elements at either end.
-pub struct LinkedList<T: Clone + Display> {
-    pub head: Option<StrongPointer<ListNode<T>>>,
-    pub tail: Option<StrongPointer<ListNode<T>>>,
+pub type LinkedList<T> = GenericLinkedList<T, RcFamily>;
+
+/// A thread-safe doubly-linked list.
+///
+/// `SyncLinkedList` is the same list as `LinkedList`, but it is linked with
+/// `Arc<RwLock<_>>` going forwards and `sync::Weak<RwLock<_>>` going
+/// backwards. This makes it `Send` and `Sync` (whenever `T` is), so it can be
+/// moved into worker threads or shared behind a lock such as
+/// `Arc<RwLock<SyncLinkedList<T>>>`.
+pub type SyncLinkedList<T> = GenericLinkedList<T, ArcFamily>;
+
+/// A doubly-linked list over any `PointerFamily`. The family's
+/// `ObserverFamily` impl picks the type of the callbacks it takes.
+///
+/// Most code should use one of the aliases, `LinkedList` or `SyncLinkedList`,
+/// rather than naming the pointer family directly.
+pub struct GenericLinkedList<T: Clone + Display, P: ObserverFamily> {
+    pub head: Option<P::Strong<ListNode<T, P>>>,
+    pub tail: Option<P::Strong<ListNode<T, P>>>,
     num_elements: usize,
+    pool: NodePool<T, P>,
+    sharing: Sharing<P>,
+    observers: Observers<T, P>,
+    journal: Journal<T>,
 }
 
-impl<T: Clone + Display> LinkedList<T> {
+impl<T: Clone + Display, P: ObserverFamily> GenericLinkedList<T, P> {
     // Creates an empty `LinkedList`.
     ///
     /// # Example
@@ -38,13 +104,69 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.len(), 0);
     /// ```
     pub fn new() -> Self {
-        LinkedList {
+        Self::with_node_pool(0)
+    }
+
+    /// Creates an empty list that keeps up to `capacity` removed nodes and
+    /// reuses them for later pushes instead of allocating new ones.
+    ///
+    /// Nodes are recycled by `pop_front`, `pop_back` and `retain`. A recycled
+    /// node keeps its old value until it is reused or `shrink_pool` is
+    /// called. A capacity of `0` turns the pool off, which is what `new`
+    /// does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut queue: LinkedList<u32> = LinkedList::with_node_pool(16);
+    /// for i in 0..1000 {
+    ///     queue.push_back(i);
+    ///     if queue.len() > 8 {
+    ///         queue.pop_front();
+    ///     }
+    /// }
+    /// // Only the first nine pushes had to allocate.
+    /// assert_eq!(queue.pool_stats().allocated, 9);
+    /// ```
+    pub fn with_node_pool(capacity: usize) -> Self {
+        GenericLinkedList {
             head: None,
             tail: None,
             num_elements: 0,
+            pool: NodePool::new(capacity),
+            sharing: Sharing::new(),
+            observers: Observers::new(),
+            journal: Journal::new(),
         }
     }
 
+    /// Frees every node that is sitting idle in the node pool. The pool
+    /// keeps its capacity and starts filling up again with the next pop.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut list: LinkedList<u32> = LinkedList::with_node_pool(4);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.clear();
+    /// list.push_back(3);
+    /// list.pop_back();
+    /// assert_eq!(list.pool_stats().idle, 1);
+    /// list.shrink_pool();
+    /// assert_eq!(list.pool_stats().idle, 0);
+    /// ```
+    pub fn shrink_pool(&mut self) {
+        self.pool.shrink();
+    }
+
+    /// Returns statistics about the node pool, see `PoolStats`.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
     /// Returns the length of the list.
     ///
     /// # Example
@@ -81,22 +203,14 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.len(), 2);
     /// ```
     pub fn push_front(&mut self, value: T) {
-        let new_node: ListNode<T> = ListNode::from(value);
-        let new_ref: StrongPointer<ListNode<T>> = Rc::new(RefCell::new(new_node));
-        match self.head.take() {
-            Some(old_head) => {
-                // old head's prev now points to the new node (as a weak ptr)
-                old_head.borrow_mut().prev = Some(Rc::downgrade(&new_ref));
-                new_ref.borrow_mut().next = Some(old_head);
-                self.head = Some(new_ref);
-            }
-            None => {
-                // list is empty, so new node is the head and the tail
-                self.head = Some(new_ref.clone());
-                self.tail = Some(new_ref);
-            }
-        }
-        self.num_elements += 1;
+        self.journal.record(|| Op::PushFront(value.clone()));
+        let new_ref: P::Strong<ListNode<T, P>> = self.pool.take(value);
+        let next = self.head.take();
+        self.splice(None, next, new_ref);
+        self.sharing.push_front();
+        self.observers.notify(|| ListEvent::PushFront {
+            value: P::borrow(self.head.as_ref().unwrap()).data.clone(),
+        });
     }
 
     /// Removes an element from the head of the list and returns it.
@@ -116,20 +230,17 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.pop_front(), None);
     /// ```
     pub fn pop_front(&mut self) -> Option<T> {
-        self.head
-            .take()
-            .map(|old_head: StrongPointer<ListNode<T>>| {
-                self.num_elements -= 1;
-                if self.num_elements == 0 {
-                    self.head = None;
-                    self.tail = None;
-                } else {
-                    let mut new_head = old_head.borrow_mut().next.take();
-                    new_head.as_mut().unwrap().borrow_mut().prev = None;
-                    self.head = new_head;
-                }
-                old_head.borrow().data.clone()
-            })
+        let old_head = self.head.take()?;
+        self.journal.record(|| Op::PopFront);
+        let value = if self.sharing.pop_front() {
+            self.unlink_shared(old_head)
+        } else {
+            self.unlink(old_head)
+        };
+        self.observers.notify(|| ListEvent::PopFront {
+            value: value.clone(),
+        });
+        Some(value)
     }
 
     /// Adds an element to the tail of the list.
@@ -146,20 +257,13 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.len(), 2);
     /// ```
     pub fn push_back(&mut self, value: T) {
-        let new_node: ListNode<T> = ListNode::from(value);
-        let new_ref: StrongPointer<ListNode<T>> = Rc::new(RefCell::new(new_node));
-        match self.tail.take() {
-            Some(old_tail) => {
-                old_tail.borrow_mut().next = Some(new_ref.clone());
-                new_ref.borrow_mut().prev = Some(Rc::downgrade(&old_tail.clone()));
-                self.tail = Some(new_ref);
-            }
-            None => {
-                self.head = Some(new_ref.clone());
-                self.tail = Some(new_ref);
-            }
-        }
-        self.num_elements += 1;
+        self.journal.record(|| Op::PushBack(value.clone()));
+        let new_ref: P::Strong<ListNode<T, P>> = self.pool.take(value);
+        let prev = self.tail.take();
+        self.splice(prev, None, new_ref);
+        self.observers.notify(|| ListEvent::PushBack {
+            value: P::borrow(self.tail.as_ref().unwrap()).data.clone(),
+        });
     }
 
     /// Removes an element from the tail of the list and returns it.
@@ -178,24 +282,16 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.pop_front(), None);
     /// ```
     pub fn pop_back(&mut self) -> Option<T> {
-        self.tail
-            .take()
-            .map(|old_tail: StrongPointer<ListNode<T>>| {
-                self.num_elements -= 1;
-                if self.num_elements == 0 {
-                    self.head = None;
-                    self.tail = None;
-                } else {
-                    let mut new_tail = old_tail
-                        .borrow_mut()
-                        .prev
-                        .take()
-                        .map(|prev| prev.upgrade().unwrap());
-                    new_tail.as_mut().unwrap().borrow_mut().next = None;
-                    self.tail = new_tail;
-                }
-                old_tail.borrow().data.clone()
-            })
+        if self.sharing.pop_back_needs_copy(self.num_elements) {
+            self.unshare();
+        }
+        let old_tail = self.tail.take()?;
+        self.journal.record(|| Op::PopBack);
+        let value = self.unlink(old_tail);
+        self.observers.notify(|| ListEvent::PopBack {
+            value: value.clone(),
+        });
+        Some(value)
     }
 
     /// Retains only the elements specified by the predicate.
@@ -221,37 +317,30 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.pop_back(), None);
     /// ```
     pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
-        let mut current = self.head.take();
+        self.unshare();
+        // Whether each element was kept, for the journal.
+        let mut kept = Vec::new();
+        // The index of `curr` in the list as it is at that point, for the
+        // events.
+        let mut index = 0;
+        let mut current = self.head.clone();
         while let Some(curr) = current {
-            let node = curr.borrow();
-            if !f(&node.data) {
-                // We're removing the front: update head pointer
-                if node.prev.is_none() {
-                    self.head = node.next.clone();
-                }
-
-                // We're removing the tail: update tail pointer
-                if node.next.is_none() {
-                    self.tail = curr
-                        .borrow()
-                        .prev
-                        .clone()
-                        .map(|weak_ptr| weak_ptr.upgrade().unwrap());
-                }
-
-                // Remove current node: change prev's next and next's prev
-                node.prev.as_ref().map(|prev| {
-                    prev.upgrade().map(|prev| {
-                        prev.borrow_mut().next = node.next.clone().take();
-                    });
-                });
-                node.next.as_ref().map(|next| {
-                    next.borrow_mut().prev = node.prev.clone().take();
-                });
-                self.num_elements -= 1;
+            let (keep, next) = {
+                let node = P::borrow(&curr);
+                (f(&node.data), node.next.clone())
+            };
+            if self.journal.is_recording() {
+                kept.push(keep);
+            }
+            if keep {
+                index += 1;
+            } else {
+                let value = self.unlink(curr);
+                self.observers.notify(|| ListEvent::Remove { index, value });
             }
-            current = node.next.clone();
+            current = next;
         }
+        self.journal.record(|| Op::Retain { keep: kept });
     }
 
     /// Clears the linked list, removing all values.
@@ -270,9 +359,17 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.pop_back(), None);
     /// ```
     pub fn clear(&mut self) {
+        self.journal.record(|| Op::Clear);
+        let values: Vec<T> = if self.observers.is_active() {
+            self.iter().collect()
+        } else {
+            Vec::new()
+        };
         self.head = None;
         self.tail = None;
         self.num_elements = 0;
+        self.sharing.forget();
+        self.observers.notify(|| ListEvent::Clear { values });
     }
 
     /// Returns `true` if the list contains no elements.
@@ -307,17 +404,473 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.get(3), None);
     /// ```
     pub fn get(&self, index: usize) -> Option<T> {
-        self.get_ptr(index).map(|ptr| ptr.borrow().data.clone())
+        self.get_ptr(index).map(|ptr| P::borrow(&ptr).data.clone())
     }
 
-    pub(crate) fn get_ptr(&self, index: usize) -> Option<StrongPointer<ListNode<T>>> {
+    /// Inserts `value` at `index`, shifting the elements after it back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.insert(1, 2);
+    /// list.insert(0, 0);
+    /// assert_eq!(list.to_string(), "0 -> 1 -> 2 -> 3");
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.num_elements, "insertion index out of bounds");
+        self.journal.record(|| Op::Insert {
+            index,
+            value: value.clone(),
+        });
+        // Unshare before looking `prev` up, so that it is the live node and
+        // not one a snapshot still holds.
+        self.unshare();
+        let prev = match index {
+            0 => None,
+            _ => self.get_ptr(index - 1),
+        };
+        let node = self.insert_after(prev, value);
+        self.observers.notify(|| ListEvent::Insert {
+            index,
+            value: P::borrow(&node).data.clone(),
+        });
+    }
+
+    /// Removes the element at `index` and returns it, or returns `None` if
+    /// the index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// assert_eq!(list.remove(1), Some(2));
+    /// assert_eq!(list.remove(2), None);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        self.journal.record(|| Op::Remove { index });
+        self.unshare();
+        let node = self.get_ptr(index)?;
+        let value = self.unlink(node);
+        self.observers.notify(|| ListEvent::Remove {
+            index,
+            value: value.clone(),
+        });
+        Some(value)
+    }
+
+    /// Registers `observer` to be called with a `ListEvent` after every
+    /// change to the list, and returns a token for `unsubscribe`. See the
+    /// `observer` module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{LinkedList, ListEvent};
+    /// # use std::cell::RefCell;
+    /// # use std::rc::Rc;
+    /// let events = Rc::new(RefCell::new(Vec::new()));
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// let log = Rc::clone(&events);
+    /// let token = list.subscribe(Box::new(move |event: &ListEvent<u32>| {
+    ///     log.borrow_mut().push(event.clone());
+    /// }));
+    /// list.push_back(1);
+    /// list.pop_front();
+    /// assert!(list.unsubscribe(token));
+    /// list.push_back(2);
+    /// assert_eq!(
+    ///     *events.borrow(),
+    ///     [ListEvent::PushBack { value: 1 }, ListEvent::PopFront { value: 1 }]
+    /// );
+    /// ```
+    pub fn subscribe(&mut self, observer: P::Observer<T>) -> Subscription {
+        self.observers.subscribe(observer)
+    }
+
+    /// Removes the subscription for `token`. Returns `false` if there was
+    /// none.
+    pub fn unsubscribe(&mut self, token: Subscription) -> bool {
+        self.observers.unsubscribe(token)
+    }
+
+    /// Turns journaling on: from now on every mutating call is appended to
+    /// the `journal` as an `Op`. See the `journal` module.
+    ///
+    /// The journal first gets a `push_back` for each element already in the
+    /// list, so that replaying it from empty always rebuilds the list. If
+    /// the journal still holds ops from an earlier session, a `clear` goes
+    /// in front of those pushes. Does nothing if the list is already
+    /// journaling.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{LinkedList, Op};
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// list.push_back(2);
+    /// list.start_journal();
+    /// list.push_front(1);
+    /// list.sort(|a, b| b.cmp(a));
+    /// let log: Vec<String> = list.journal().iter().map(|op| op.to_string()).collect();
+    /// assert_eq!(log, ["push_back 2", "push_front 1", "sort 1 0"]);
+    ///
+    /// let ops = log.iter().map(|line| line.parse::<Op<u32>>().unwrap());
+    /// let replica: LinkedList<u32> = LinkedList::replay(ops);
+    /// assert_eq!(replica.to_string(), "2 -> 1");
+    /// ```
+    pub fn start_journal(&mut self) {
+        if self.journal.is_recording() {
+            return;
+        }
+        self.journal.set_recording(true);
+        if !self.journal.is_empty() {
+            self.journal.record(|| Op::Clear);
+        }
+        for value in self.iter() {
+            self.journal.record(|| Op::PushBack(value));
+        }
+    }
+
+    /// Turns journaling off. The ops journaled so far stay in the journal.
+    pub fn stop_journal(&mut self) {
+        self.journal.set_recording(false);
+    }
+
+    /// Returns `true` if the list is journaling.
+    pub fn is_journaling(&self) -> bool {
+        self.journal.is_recording()
+    }
+
+    /// Returns the journal, to read or `drain` the ops recorded so far.
+    pub fn journal(&mut self) -> &mut Journal<T> {
+        &mut self.journal
+    }
+
+    /// Makes the call `op` records. Applying a journal's ops in order to an
+    /// empty list rebuilds the journaled list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `op` can't have been journaled by a list like this one: an
+    /// `Insert` out of bounds, or a `Retain` or `Sort` for a different
+    /// number of elements.
+    pub fn apply(&mut self, op: Op<T>) {
+        match op {
+            Op::PushFront(value) => self.push_front(value),
+            Op::PushBack(value) => self.push_back(value),
+            Op::PopFront => {
+                self.pop_front();
+            }
+            Op::PopBack => {
+                self.pop_back();
+            }
+            Op::Insert { index, value } => self.insert(index, value),
+            Op::Remove { index } => {
+                self.remove(index);
+            }
+            Op::Retain { keep } => {
+                assert_eq!(
+                    keep.len(),
+                    self.num_elements,
+                    "journal doesn't match the list"
+                );
+                let mut keep = keep.into_iter();
+                self.retain(|_| keep.next().unwrap());
+            }
+            Op::Sort { order } => self.permute(order),
+            Op::Clear => self.clear(),
+        }
+    }
+
+    /// Builds a list by applying `ops` in order to an empty one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// list.start_journal();
+    /// for i in 0..5 {
+    ///     list.push_back(i);
+    /// }
+    /// list.retain(|x| x % 2 == 0);
+    /// list.remove(1);
+    ///
+    /// let replica: LinkedList<u32> = LinkedList::replay(list.journal().drain());
+    /// assert_eq!(replica.to_string(), "0 -> 4");
+    /// assert!(list.journal().is_empty());
+    /// ```
+    pub fn replay(ops: impl IntoIterator<Item = Op<T>>) -> Self {
+        let mut list = Self::new();
+        for op in ops {
+            list.apply(op);
+        }
+        list
+    }
+
+    /// Reorders the list so that the element at `order[i]` ends up at `i`,
+    /// which is how a `sort` is journaled.
+    fn permute(&mut self, order: Vec<usize>) {
+        let mut seen = vec![false; self.num_elements];
+        let valid = order.len() == self.num_elements
+            && order
+                .iter()
+                .all(|&index| index < seen.len() && !std::mem::replace(&mut seen[index], true));
+        assert!(valid, "journal doesn't match the list");
+        self.unshare();
+        let values: Vec<T> = self.iter().collect();
+        if self.observers.is_active() {
+            // Report the reordering as moves, building the new order from
+            // the front.
+            let mut positions: Vec<usize> = (0..values.len()).collect();
+            for (to, &index) in order.iter().enumerate() {
+                let from = to + positions[to..].iter().position(|&p| p == index).unwrap();
+                if from != to {
+                    let moved = positions.remove(from);
+                    positions.insert(to, moved);
+                    self.observers.notify(|| ListEvent::Move {
+                        from,
+                        to,
+                        value: values[index].clone(),
+                    });
+                }
+            }
+        }
+        let mut current = self.head.clone();
+        for &index in &order {
+            let node = current.unwrap();
+            P::borrow_mut(&node).data = values[index].clone();
+            current = P::borrow(&node).next.clone();
+        }
+        self.journal.record(|| Op::Sort { order });
+    }
+
+    /// Links `node` in between two adjacent nodes, either of which may be
+    /// `None` at the ends of the list, in which case `head` or `tail` is
+    /// updated instead.
+    #[inline(always)]
+    fn splice(
+        &mut self,
+        prev: Option<P::Strong<ListNode<T, P>>>,
+        next: Option<P::Strong<ListNode<T, P>>>,
+        node: P::Strong<ListNode<T, P>>,
+    ) {
+        self.set_prev(next.as_ref(), Some(&node));
+        {
+            let mut new_node = P::borrow_mut(&node);
+            new_node.prev = prev.as_ref().map(P::downgrade);
+            new_node.next = next;
+        }
+        self.set_next(prev.as_ref(), Some(node));
+        self.num_elements += 1;
+    }
+
+    /// Unlinks `node` from its neighbours, updating `head` or `tail` at the
+    /// ends, and returns its data. The node goes back to the pool.
+    ///
+    /// Callers may already have taken `node` out of `head` or `tail`; those
+    /// links are rewritten here anyway. `node` must not be shared with a
+    /// snapshot (see `unshare`).
+    #[inline(always)]
+    pub(crate) fn unlink(&mut self, node: P::Strong<ListNode<T, P>>) -> T {
+        self.detach(&node);
+        let data = P::borrow(&node).data.clone();
+        self.pool.give(node);
+        data
+    }
+
+    /// Unlinks the shared front `node` without touching its `next` link,
+    /// which the snapshots sharing it still follow, and returns a copy of
+    /// its data. The node stays with the snapshots instead of the pool.
+    fn unlink_shared(&mut self, node: P::Strong<ListNode<T, P>>) -> T {
+        let (next, data) = {
+            let node = P::borrow(&node);
+            (node.next.clone(), node.data.clone())
+        };
+        self.set_prev(next.as_ref(), None);
+        self.set_next(None, next);
+        self.num_elements -= 1;
+        data
+    }
+
+    /// Unlinks `node` like `unlink`, but leaves the node to the caller
+    /// instead of the pool.
+    #[inline(always)]
+    pub(crate) fn detach(&mut self, node: &P::Strong<ListNode<T, P>>) {
+        let (prev, next) = {
+            let mut old = P::borrow_mut(node);
+            let prev = old.prev.take().and_then(|prev| P::upgrade(&prev));
+            (prev, old.next.take())
+        };
+        self.set_prev(next.as_ref(), prev.as_ref());
+        self.set_next(prev.as_ref(), next);
+        self.num_elements -= 1;
+    }
+
+    /// Moves `node`, which must be in this list, to the front.
+    pub(crate) fn move_to_front(&mut self, node: P::Strong<ListNode<T, P>>) {
+        self.unshare();
+        self.detach(&node);
+        let next = self.head.take();
+        self.splice(None, next, node);
+    }
+
+    /// Moves `node`, which must be in this list, to right after `prev`, or to
+    /// the front if `prev` is `None`. `prev` must not be `node`.
+    pub(crate) fn move_after(
+        &mut self,
+        prev: Option<P::Strong<ListNode<T, P>>>,
+        node: P::Strong<ListNode<T, P>>,
+    ) {
+        self.unshare();
+        self.detach(&node);
+        let next = match prev.as_ref() {
+            Some(prev) => P::borrow(prev).next.clone(),
+            None => self.head.clone(),
+        };
+        self.splice(prev, next, node);
+    }
+
+    /// Links a detached `node`, possibly from another list, in at the back.
+    pub(crate) fn attach_back(&mut self, node: P::Strong<ListNode<T, P>>) {
+        let prev = self.tail.take();
+        self.splice(prev, None, node);
+    }
+
+    /// Points the `next` link of `node`, or `head` if it is `None`, at `next`.
+    #[inline(always)]
+    fn set_next(
+        &mut self,
+        node: Option<&P::Strong<ListNode<T, P>>>,
+        next: Option<P::Strong<ListNode<T, P>>>,
+    ) {
+        match node {
+            Some(node) => P::borrow_mut(node).next = next,
+            None => self.head = next,
+        }
+    }
+
+    /// Points the `prev` link of `node`, or `tail` if it is `None`, at `prev`.
+    #[inline(always)]
+    fn set_prev(
+        &mut self,
+        node: Option<&P::Strong<ListNode<T, P>>>,
+        prev: Option<&P::Strong<ListNode<T, P>>>,
+    ) {
+        match node {
+            Some(node) => P::borrow_mut(node).prev = prev.map(P::downgrade),
+            None => self.tail = prev.cloned(),
+        }
+    }
+
+    /// Inserts `value` right after `prev`, or at the front if `prev` is
+    /// `None`, and returns the new node.
+    pub(crate) fn insert_after(
+        &mut self,
+        prev: Option<P::Strong<ListNode<T, P>>>,
+        value: T,
+    ) -> P::Strong<ListNode<T, P>> {
+        self.unshare();
+        let node = self.pool.take(value);
+        let next = match prev.as_ref() {
+            Some(prev) => P::borrow(prev).next.clone(),
+            None => self.head.clone(),
+        };
+        self.splice(prev, next, node.clone());
+        node
+    }
+
+    /// Returns a read-only view of the list as it is now, in O(1).
+    ///
+    /// The snapshot shares the list's nodes rather than copying them. Pushes
+    /// and pops at either end leave shared nodes alone, except for a
+    /// `pop_back` that reaches a node the snapshot can see: the shared nodes
+    /// in front of it are copied then, once. `retain`, `sort` and `iter_mut`
+    /// also copy the shared nodes before changing anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LinkedList;
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let snapshot = list.snapshot();
+    /// list.push_front(0);
+    /// list.pop_back();
+    /// list.push_back(3);
+    /// assert_eq!(list.to_string(), "0 -> 1 -> 3");
+    /// assert_eq!(snapshot.to_string(), "1 -> 2");
+    /// ```
+    pub fn snapshot(&mut self) -> Snapshot<T, P> {
+        let token = self.sharing.share_all(self.num_elements);
+        Snapshot::new(self.head.clone(), self.num_elements, token)
+    }
+
+    /// Replaces the nodes shared with snapshots by private copies, so that
+    /// they can be changed in place. The originals are left as they are,
+    /// apart from the last one no longer leading on to the live list.
+    fn unshare(&mut self) {
+        let (front, shared) = self.sharing.span();
+        if shared == 0 {
+            return;
+        }
+        self.sharing.forget();
+
+        let mut prev = match front {
+            0 => None,
+            _ => self.get_ptr(front - 1),
+        };
+        let mut current = match prev.as_ref() {
+            Some(prev) => P::borrow(prev).next.clone(),
+            None => self.head.clone(),
+        };
+        for i in 0..shared {
+            let original = current.unwrap();
+            let (data, next) = {
+                let node = P::borrow(&original);
+                (node.data.clone(), node.next.clone())
+            };
+            if i + 1 == shared {
+                P::borrow_mut(&original).next = None;
+            }
+            let copy = self.pool.take(data);
+            P::borrow_mut(&copy).prev = prev.as_ref().map(P::downgrade);
+            self.set_next(prev.as_ref(), Some(copy.clone()));
+            prev = Some(copy);
+            current = next;
+        }
+        self.set_prev(current.as_ref(), prev.as_ref());
+        if let Some(last) = prev.as_ref() {
+            P::borrow_mut(last).next = current;
+        }
+    }
+
+    pub(crate) fn get_ptr(&self, index: usize) -> Option<P::Strong<ListNode<T, P>>> {
         if index >= self.num_elements {
             return None;
         }
 
         let mut current = self.head.clone();
         for _ in 0..index {
-            current = current.unwrap().borrow().next.clone();
+            let next = P::borrow(current.as_ref().unwrap()).next.clone();
+            current = next;
         }
         Some(current.unwrap())
     }
@@ -346,38 +899,9 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(iter.next(), None);
     /// assert_eq!(list.len(), 3);
     /// ```
-    pub fn iter(&self) -> ListIter<T> {
+    pub fn iter(&self) -> ListIter<T, P> {
         ListIter {
-            current: self.head.as_ref().map(|node| node.clone()),
-            marker: PhantomData,
-        }
-    }
-
-    /// Returns a mutable iterator over the list.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use dll::LinkedList;
-    ///
-    /// let mut list: LinkedList<u32> = LinkedList::new();
-    ///
-    /// list.push_back(0);
-    /// list.push_back(1);
-    /// list.push_back(2);
-    ///
-    /// for element in list.iter_mut() {
-    ///     *element += 10;
-    /// }
-    ///
-    /// let mut iter = list.iter();
-    /// assert_eq!(iter.next(), Some(10));
-    /// assert_eq!(iter.next(), Some(11));
-    /// assert_eq!(iter.next(), Some(12));
-    /// ```
-    pub fn iter_mut(&self) -> ListIterMut<'_, T> {
-        ListIterMut {
-            current: self.head.as_ref().map(|node| node.clone()),
+            current: self.head.clone(),
             marker: PhantomData,
         }
     }
@@ -422,16 +946,75 @@ impl<T: Clone + Display> LinkedList<T> {
     /// assert_eq!(list.to_string(), "3 -> 2 -> 1");
     /// ```
     pub fn sort(&mut self, compare: impl FnMut(&T, &T) -> Ordering) {
+        self.unshare();
         sort::bubble_sort(self, compare);
     }
 }
 
-impl<T: Clone + Display> Display for LinkedList<T> {
+impl<T: Clone + Display> LinkedList<T> {
+    /// Returns a mutable iterator over the list.
+    ///
+    /// This is only available on the single-threaded `LinkedList`: handing
+    /// out `&mut T` past the end of a borrow relies on `RefCell::as_ptr`,
+    /// which a `RwLock` has no safe counterpart for. Any nodes shared with a
+    /// snapshot are copied first, which is why this takes `&mut self` (it
+    /// used to take `&self`, so callers holding only a shared reference to
+    /// the list need a mutable one now).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dll::LinkedList;
+    ///
+    /// let mut list: LinkedList<u32> = LinkedList::new();
+    ///
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// list.push_back(2);
+    ///
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    ///
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(10));
+    /// assert_eq!(iter.next(), Some(11));
+    /// assert_eq!(iter.next(), Some(12));
+    /// ```
+    pub fn iter_mut(&mut self) -> ListIterMut<'_, T> {
+        self.unshare();
+        ListIterMut {
+            current: self.head.as_ref().map(|node| node.clone()),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone + Display, P: ObserverFamily> Default for GenericLinkedList<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display, P: ObserverFamily> Drop for GenericLinkedList<T, P> {
+    // Unlink iteratively so that dropping a long list doesn't recurse once
+    // per node. Nodes still shared with someone else (an iterator, say) are
+    // left intact for them.
+    fn drop(&mut self) {
+        self.tail = None;
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = P::try_unwrap(node).ok().and_then(|node| node.next);
+        }
+    }
+}
+
+impl<T: Clone + Display, P: ObserverFamily> Display for GenericLinkedList<T, P> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut iter = self.iter();
         if let Some(first) = iter.next() {
             write!(f, "{}", first)?;
-            while let Some(next) = iter.next() {
+            for next in iter {
                 write!(f, " -> {}", next)?;
             }
         }
@@ -1,23 +1,25 @@
 use core::marker::PhantomData;
 use std::fmt::Display;
 
+use super::pointer::{PointerFamily, RcFamily};
 use super::{ListNode, StrongPointer}; // for cursors
 
 /// An immutable iterator over the elements of a `LinkedList`.
-pub struct ListIter<T: Clone + Display> {
-    pub current: Option<StrongPointer<ListNode<T>>>,
-    pub marker: PhantomData<ListNode<T>>,
+pub struct ListIter<T: Clone + Display, P: PointerFamily = RcFamily> {
+    pub current: Option<P::Strong<ListNode<T, P>>>,
+    pub marker: PhantomData<ListNode<T, P>>,
 }
 
 /// Returns an iterator over the elements of the list.
-impl<T: Clone + Display> Iterator for ListIter<T> {
+impl<T: Clone + Display, P: PointerFamily> Iterator for ListIter<T, P> {
     type Item = T;
 
     /// Returns the next element of the list.
     fn next(&mut self) -> Option<Self::Item> {
-        self.current.take().map(|old: StrongPointer<ListNode<T>>| {
-            self.current = old.borrow().next.clone();
-            old.borrow().data.clone()
+        self.current.take().map(|old: P::Strong<ListNode<T, P>>| {
+            let node = P::borrow(&old);
+            self.current = node.next.clone();
+            node.data.clone()
         })
     }
 }
@@ -42,7 +44,7 @@ impl<'a, T: Clone + Display> Iterator for ListIterMut<'a, T> {
             .map(|old: StrongPointer<ListNode<T>>| unsafe {
                 // need this to get 'a
                 let node = &mut *old.as_ptr();
-				self.current = node.next.clone();
+                self.current = node.next.clone();
                 &mut node.data
             })
     }
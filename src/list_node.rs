@@ -13,6 +13,9 @@ use std::cell::RefCell;
 use std::clone::Clone;
 use std::fmt::Display;
 use std::rc::{Rc, Weak};
+use std::sync::{Arc, RwLock};
+
+use super::pointer::{PointerFamily, RcFamily};
 
 /// The node type used to store data.
 ///
@@ -20,22 +23,29 @@ use std::rc::{Rc, Weak};
 /// reference-counted pointer to the previous/next nodes in the list. The next
 /// pointer is a strong reference and the prev pointer is a weak reference.
 ///
+/// The pointer types come from the `PointerFamily` parameter `P`, which
+/// defaults to the single-threaded `RcFamily`.
+///
 /// StrongPointer - a single-threaded reference-counted pointer to a node.
 /// WeakPointer - a version of rc that holds a non-owning reference to the node.
-pub struct ListNode<T: Clone + Display> {
+/// SyncStrongPointer - an atomically reference-counted pointer to a node.
+/// SyncWeakPointer - a version of arc that holds a non-owning reference.
+pub struct ListNode<T: Clone + Display, P: PointerFamily = RcFamily> {
     pub data: T,
-    pub next: Option<StrongPointer<ListNode<T>>>,
-    pub prev: Option<WeakPointer<ListNode<T>>>,
+    pub next: Option<P::Strong<ListNode<T, P>>>,
+    pub prev: Option<P::Weak<ListNode<T, P>>>,
 }
 
 pub type StrongPointer<T> = Rc<RefCell<T>>;
 pub type WeakPointer<T> = Weak<RefCell<T>>;
+pub type SyncStrongPointer<T> = Arc<RwLock<T>>;
+pub type SyncWeakPointer<T> = std::sync::Weak<RwLock<T>>;
 
 /////////////////////////////////////////////////////////////////////////
 // List Node - Core Implementation
 /////////////////////////////////////////////////////////////////////////
 
-impl<T: Clone + Display> ListNode<T> {
+impl<T: Clone + Display, P: PointerFamily> ListNode<T, P> {
     /// Creates a new node with the given value, next, and previous nodes.
     ///
     /// # Example
@@ -54,8 +64,8 @@ impl<T: Clone + Display> ListNode<T> {
     /// ```
     pub fn new(
         data: T,
-        next: Option<StrongPointer<ListNode<T>>>,
-        prev: Option<WeakPointer<ListNode<T>>>,
+        next: Option<P::Strong<ListNode<T, P>>>,
+        prev: Option<P::Weak<ListNode<T, P>>>,
     ) -> Self {
         Self { data, next, prev }
     }
@@ -69,7 +79,7 @@ impl<T: Clone + Display> ListNode<T> {
 ///
 /// The next and previous nodes are set to `None` and the data is cloned into
 /// the node.
-impl<T: Clone + Display> From<T> for ListNode<T> {
+impl<T: Clone + Display, P: PointerFamily> From<T> for ListNode<T, P> {
     /// Creates a new node with the given value and no next or previous nodes.
     ///
     /// Takes ownership of the passed in value of type T.
@@ -92,11 +102,12 @@ impl<T: Clone + Display> From<T> for ListNode<T> {
 // List Node - PartialEq Implementation
 /////////////////////////////////////////////////////////////////////////
 
-impl<T> PartialEq<ListNode<T>> for ListNode<T>
+impl<T, P> PartialEq<ListNode<T, P>> for ListNode<T, P>
 where
     T: Clone + Display + PartialEq,
+    P: PointerFamily,
 {
-    fn eq(&self, other: &ListNode<T>) -> bool {
+    fn eq(&self, other: &ListNode<T, P>) -> bool {
         self.data == other.data
     }
 }
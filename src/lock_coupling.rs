@@ -0,0 +1,615 @@
+/// A doubly-linked list with one lock per node.
+///
+/// `LockCouplingList` lets many threads work on a single list through a shared
+/// reference. Instead of one lock around the whole list, every node carries
+/// its own `Mutex`, and traversals lock nodes "hand over hand": the next node
+/// is locked before the current one is released. Threads working on
+/// different regions of a long list therefore don't block each other, and
+/// a traversal can never be overtaken by a writer.
+///
+/// ```text
+///   head                                                           tail
+/// ---------            --------            --------            ---------
+/// | ghost | === arc ==> | list | === arc ==> | list | === arc ==> | ghost |
+/// |       | <--weak---- | node | <--weak---- | node | <--weak---- |       |
+/// ---------            --------            --------            ---------
+/// ```
+///
+/// The list is bracketed by two ghost nodes that never hold data, so every
+/// element has a real node on each side and inserting or removing is always
+/// the same splice between locked neighbours.
+///
+/// # Deadlock freedom
+///
+/// Every operation that holds more than one lock at a time acquires them in
+/// list order, from the head ghost towards the tail ghost. Because that order
+/// is the same for everyone, no thread can hold a lock another thread needs
+/// while waiting on a lock that thread holds, so there is no cycle of waiting
+/// threads and therefore no deadlock.
+///
+/// Operations at the back of the list find their nodes through the weak
+/// backward links, but they only ever hold one lock while doing so. Once they
+/// know which nodes they need, they release everything, lock those nodes
+/// front to back, and re-check that they are still adjacent and still in the
+/// list, retrying if not.
+///
+/// The forwards that lead iterators off removed nodes have locks of their
+/// own. Those are always taken last and released before any other lock is
+/// taken, so they can't be part of a cycle either.
+///
+/// The one way to break this is to hold a cursor and then call another method
+/// on the same list from the same thread, since the cursor keeps its node
+/// locked.
+use core::fmt;
+use core::marker::PhantomData;
+use std::clone::Clone;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use self::locked::Locked;
+
+type NodeRef<T> = Arc<Mutex<Node<T>>>;
+
+/// Where an iterator parked on a removed node carries on from: the node that
+/// followed it, or whichever node has taken that one's place since.
+type Forward<T> = Arc<Mutex<Weak<Mutex<Node<T>>>>>;
+
+struct Node<T> {
+    data: Option<T>, // `None` only for the two ghost nodes
+    next: Option<NodeRef<T>>,
+    prev: Weak<Mutex<Node<T>>>,
+    removed: bool,
+    forward: Option<Forward<T>>, // set when the node is removed
+    forwarded: Vec<Forward<T>>,  // the forwards that point at this node
+}
+
+/// `Locked` is the only unsafe code in this file. It lives in its own module
+/// so that nothing else can get at its fields.
+mod locked {
+    use std::mem::ManuallyDrop;
+    use std::ops::{Deref, DerefMut};
+    use std::sync::{Arc, MutexGuard};
+
+    use super::{Node, NodeRef};
+
+    /// A node held locked, along with the pointer that keeps it alive.
+    ///
+    /// Hand-over-hand traversal and cursors keep a lock after the local that
+    /// found the node is gone, so the guard can't just borrow from the
+    /// caller's `Arc`; it is stored next to its own `Arc` instead.
+    pub(super) struct Locked<'a, T> {
+        guard: ManuallyDrop<MutexGuard<'a, Node<T>>>,
+        node: NodeRef<T>,
+    }
+
+    impl<'a, T> Locked<'a, T> {
+        pub(super) fn lock(node: NodeRef<T>) -> Self {
+            let guard = node.lock().unwrap_or_else(|poison| poison.into_inner());
+            // SAFETY: the guard borrows the mutex inside the allocation that
+            // `node` owns, which doesn't move and can't be freed while `node`
+            // is alive. `node` is private to this module and never replaced,
+            // and `drop` releases the guard before `node` goes, so the guard
+            // never outlives its mutex, whatever `'a` is. Borrows of the node
+            // only ever come out through `Deref`, tied to `&self`.
+            let guard = unsafe {
+                std::mem::transmute::<MutexGuard<'_, Node<T>>, MutexGuard<'a, Node<T>>>(guard)
+            };
+            Locked {
+                guard: ManuallyDrop::new(guard),
+                node,
+            }
+        }
+
+        /// Returns the pointer to the locked node.
+        pub(super) fn node(&self) -> &NodeRef<T> {
+            &self.node
+        }
+
+        pub(super) fn next(&self) -> NodeRef<T> {
+            self.guard.next.clone().unwrap()
+        }
+
+        pub(super) fn is(&self, node: &NodeRef<T>) -> bool {
+            Arc::ptr_eq(&self.node, node)
+        }
+    }
+
+    impl<T> Drop for Locked<'_, T> {
+        fn drop(&mut self) {
+            // SAFETY: this is the only place the guard is dropped, and `node`
+            // still keeps its mutex alive here.
+            unsafe { ManuallyDrop::drop(&mut self.guard) }
+        }
+    }
+
+    impl<T> Deref for Locked<'_, T> {
+        type Target = Node<T>;
+
+        fn deref(&self) -> &Node<T> {
+            &self.guard
+        }
+    }
+
+    impl<T> DerefMut for Locked<'_, T> {
+        fn deref_mut(&mut self) -> &mut Node<T> {
+            &mut self.guard
+        }
+    }
+}
+
+/// Links a fresh node holding `value` between two locked neighbours.
+fn splice_between<T>(prev: &mut Locked<'_, T>, next: &mut Locked<'_, T>, value: T) {
+    let node = Arc::new(Mutex::new(Node {
+        data: Some(value),
+        next: Some(next.node().clone()),
+        prev: Arc::downgrade(prev.node()),
+        removed: false,
+        forward: None,
+        forwarded: Vec::new(),
+    }));
+    next.prev = Arc::downgrade(&node);
+    prev.next = Some(node);
+}
+
+/// Unlinks the locked node `node` from its two locked neighbours.
+///
+/// The removed node lets go of `next`, so removed nodes never keep each other
+/// alive. It gets a forward to `next` instead, and the forwards that pointed
+/// at it move on to `next` as well, which keeps every forward pointing at a
+/// node that is still in the list.
+fn unlink<T>(prev: &mut Locked<'_, T>, node: &mut Locked<'_, T>, next: &mut Locked<'_, T>) -> T {
+    prev.next = Some(next.node().clone());
+    next.prev = Arc::downgrade(prev.node());
+    node.removed = true;
+    node.next = None;
+
+    let target = Arc::downgrade(next.node());
+    let forward = Arc::new(Mutex::new(target.clone()));
+    node.forward = Some(forward.clone());
+    for moved in node.forwarded.drain(..) {
+        *moved.lock().unwrap_or_else(|poison| poison.into_inner()) = target.clone();
+        next.forwarded.push(moved);
+    }
+    next.forwarded.push(forward);
+    // Forwards whose removed nodes are gone have no one left to lead.
+    next.forwarded
+        .retain(|forward| Arc::strong_count(forward) > 1);
+    node.data.take().unwrap()
+}
+
+/// A doubly-linked list with fine-grained, per-node locking.
+pub struct LockCouplingList<T: Clone + Display> {
+    head: NodeRef<T>,
+    tail: NodeRef<T>,
+    num_elements: AtomicUsize,
+}
+
+impl<T: Clone + Display> LockCouplingList<T> {
+    /// Creates an empty `LockCouplingList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        let tail = Arc::new(Mutex::new(Node {
+            data: None,
+            next: None,
+            prev: Weak::new(),
+            removed: false,
+            forward: None,
+            forwarded: Vec::new(),
+        }));
+        let head = Arc::new(Mutex::new(Node {
+            data: None,
+            next: Some(tail.clone()),
+            prev: Weak::new(),
+            removed: false,
+            forward: None,
+            forwarded: Vec::new(),
+        }));
+        Locked::lock(tail.clone()).prev = Arc::downgrade(&head);
+        LockCouplingList {
+            head,
+            tail,
+            num_elements: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds an element to the head of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_front(1);
+    /// list.push_front(2);
+    /// assert_eq!(list.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&self, value: T) {
+        let mut head = Locked::lock(self.head.clone());
+        let mut first = Locked::lock(head.next());
+        splice_between(&mut head, &mut first, value);
+        self.num_elements.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Adds an element to the tail of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.to_string(), "1 -> 2");
+    /// ```
+    pub fn push_back(&self, value: T) {
+        loop {
+            let Some(last) = self.prev_of(&self.tail) else {
+                continue;
+            };
+            let mut last = Locked::lock(last);
+            let mut tail = Locked::lock(self.tail.clone());
+            if !last.removed && tail.prev.upgrade().is_some_and(|p| last.is(&p)) {
+                splice_between(&mut last, &mut tail, value);
+                self.num_elements.fetch_add(1, Ordering::SeqCst);
+                return;
+            }
+        }
+    }
+
+    /// Removes an element from the head of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// assert_eq!(list.pop_front(), Some(2));
+    /// assert_eq!(list.pop_front(), None);
+    /// ```
+    pub fn pop_front(&self) -> Option<T> {
+        let mut head = Locked::lock(self.head.clone());
+        let mut first = Locked::lock(head.next());
+        if first.is(&self.tail) {
+            return None;
+        }
+        let mut second = Locked::lock(first.next());
+        let data = unlink(&mut head, &mut first, &mut second);
+        self.num_elements.fetch_sub(1, Ordering::SeqCst);
+        Some(data)
+    }
+
+    /// Removes an element from the tail of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// assert_eq!(list.pop_back(), Some(1));
+    /// assert_eq!(list.pop_back(), None);
+    /// ```
+    pub fn pop_back(&self) -> Option<T> {
+        loop {
+            let Some(last) = self.prev_of(&self.tail) else {
+                continue;
+            };
+            if Arc::ptr_eq(&last, &self.head) {
+                return None;
+            }
+            let Some(before) = self.prev_of(&last) else {
+                continue;
+            };
+
+            // Lock front to back, then make sure nothing moved in between.
+            let mut before = Locked::lock(before);
+            let mut last = Locked::lock(last);
+            let mut tail = Locked::lock(self.tail.clone());
+            if !before.removed
+                && !last.removed
+                && before.next.as_ref().is_some_and(|n| last.is(n))
+                && last.next.as_ref().is_some_and(|n| tail.is(n))
+            {
+                let data = unlink(&mut before, &mut last, &mut tail);
+                self.num_elements.fetch_sub(1, Ordering::SeqCst);
+                return Some(data);
+            }
+        }
+    }
+
+    /// Inserts `value` right after the first element matching `after`.
+    ///
+    /// Returns the value back as an error if no element matched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// assert_eq!(list.insert_after(|x| *x == 1, 2), Ok(()));
+    /// assert_eq!(list.insert_after(|x| *x == 9, 4), Err(4));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn insert_after(&self, mut after: impl FnMut(&T) -> bool, value: T) -> Result<(), T> {
+        let mut current = Locked::lock(self.head.clone());
+        loop {
+            let next = Locked::lock(current.next());
+            drop(std::mem::replace(&mut current, next));
+            match current.data.as_ref() {
+                None => return Err(value), // reached the tail ghost
+                Some(data) if after(data) => {
+                    let mut next = Locked::lock(current.next());
+                    splice_between(&mut current, &mut next, value);
+                    self.num_elements.fetch_add(1, Ordering::SeqCst);
+                    return Ok(());
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Removes and returns the first element matching the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// assert_eq!(list.remove_if(|x| x % 2 == 0), Some(2));
+    /// assert_eq!(list.remove_if(|x| *x > 5), None);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn remove_if(&self, mut f: impl FnMut(&T) -> bool) -> Option<T> {
+        let mut prev = Locked::lock(self.head.clone());
+        loop {
+            let mut current = Locked::lock(prev.next());
+            match current.data.as_ref() {
+                None => return None,
+                Some(data) if f(data) => {
+                    let mut next = Locked::lock(current.next());
+                    let data = unlink(&mut prev, &mut current, &mut next);
+                    self.num_elements.fetch_sub(1, Ordering::SeqCst);
+                    return Some(data);
+                }
+                Some(_) => prev = current,
+            }
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// Only the node being examined and its neighbours are locked at any one
+    /// time, so other threads can keep working elsewhere in the list while
+    /// this runs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// list.retain(|x| x % 2 == 1);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn retain(&self, mut f: impl FnMut(&T) -> bool) {
+        let mut prev = Locked::lock(self.head.clone());
+        let mut current = Locked::lock(prev.next());
+        while let Some(data) = current.data.as_ref() {
+            let mut next = Locked::lock(current.next());
+            if f(data) {
+                prev = current;
+            } else {
+                unlink(&mut prev, &mut current, &mut next);
+                self.num_elements.fetch_sub(1, Ordering::SeqCst);
+                drop(current);
+            }
+            current = next;
+        }
+    }
+
+    /// Returns an iterator over the list.
+    ///
+    /// The iterator holds no locks between calls to `next`, so it never gets
+    /// in anyone's way. It is weakly consistent: it yields every element that
+    /// stays in the list for the whole iteration, and may or may not yield
+    /// elements that are added or removed while it runs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> LockCouplingIter<'_, T> {
+        LockCouplingIter {
+            current: Some(self.head.clone()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a cursor positioned on the head ghost, just before the first
+    /// element.
+    ///
+    /// The cursor keeps the node it is on locked until it moves or is
+    /// dropped; see the deadlock note on `LockCouplingList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::LockCouplingList;
+    /// let list: LockCouplingList<u32> = LockCouplingList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// {
+    ///     let mut cursor = list.cursor();
+    ///     assert_eq!(cursor.current(), None);
+    ///     assert!(cursor.move_next());
+    ///     assert_eq!(cursor.current(), Some(&1));
+    ///     cursor.insert_after(2);
+    ///     *cursor.current_mut().unwrap() = 0;
+    /// }
+    /// assert_eq!(list.to_string(), "0 -> 2 -> 3");
+    /// ```
+    pub fn cursor(&self) -> LockCouplingCursor<'_, T> {
+        LockCouplingCursor {
+            list: self,
+            current: Locked::lock(self.head.clone()),
+        }
+    }
+
+    /// Reads the backward link of `node` while holding only its lock.
+    fn prev_of(&self, node: &NodeRef<T>) -> Option<NodeRef<T>> {
+        Locked::lock(node.clone()).prev.upgrade()
+    }
+}
+
+impl<T: Clone + Display> Default for LockCouplingList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Drop for LockCouplingList<T> {
+    // Unlink iteratively so that dropping a long list doesn't recurse once
+    // per node.
+    fn drop(&mut self) {
+        let mut current = Locked::lock(self.head.clone()).next.take();
+        while let Some(node) = current {
+            current = Locked::lock(node).next.take();
+        }
+    }
+}
+
+impl<T: Clone + Display> Display for LockCouplingList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A weakly consistent iterator over the elements of a `LockCouplingList`.
+pub struct LockCouplingIter<'a, T: Clone + Display> {
+    current: Option<NodeRef<T>>,
+    marker: PhantomData<&'a LockCouplingList<T>>,
+}
+
+impl<T: Clone + Display> Iterator for LockCouplingIter<'_, T> {
+    type Item = T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        // A node that was unlinked under us has a forward that leads back
+        // into the list.
+        while let Some(node) = self.current.take() {
+            let node = Locked::lock(node);
+            let next = match node.forward.as_ref() {
+                Some(forward) => forward
+                    .lock()
+                    .unwrap_or_else(|poison| poison.into_inner())
+                    .upgrade()?,
+                None => node.next.clone()?,
+            };
+            drop(node);
+            let next = Locked::lock(next);
+            self.current = Some(next.node().clone());
+            if !next.removed {
+                if let Some(data) = next.data.as_ref() {
+                    return Some(data.clone());
+                }
+            }
+        }
+        None
+    }
+}
+
+/// A cursor over a `LockCouplingList` that holds the node it points at locked.
+pub struct LockCouplingCursor<'a, T: Clone + Display> {
+    list: &'a LockCouplingList<T>,
+    current: Locked<'a, T>,
+}
+
+impl<T: Clone + Display> LockCouplingCursor<'_, T> {
+    /// Moves to the next element, locking it before releasing the current
+    /// one. Returns `false` (and stays put) at the end of the list.
+    pub fn move_next(&mut self) -> bool {
+        let next = self.current.next();
+        if Arc::ptr_eq(&next, &self.list.tail) {
+            return false;
+        }
+        self.current = Locked::lock(next);
+        true
+    }
+
+    /// Returns the current element, or `None` before the first element.
+    pub fn current(&self) -> Option<&T> {
+        self.current.data.as_ref()
+    }
+
+    /// Returns the current element mutably, or `None` before the first
+    /// element.
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        self.current.data.as_mut()
+    }
+
+    /// Inserts `value` right after the cursor.
+    pub fn insert_after(&mut self, value: T) {
+        let mut next = Locked::lock(self.current.next());
+        splice_between(&mut self.current, &mut next, value);
+        self.list.num_elements.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Removes and returns the element right after the cursor, if any.
+    pub fn remove_next(&mut self) -> Option<T> {
+        let mut next = Locked::lock(self.current.next());
+        if next.is(&self.list.tail) {
+            return None;
+        }
+        let mut after = Locked::lock(next.next());
+        let data = unlink(&mut self.current, &mut next, &mut after);
+        self.list.num_elements.fetch_sub(1, Ordering::SeqCst);
+        Some(data)
+    }
+}
@@ -0,0 +1,290 @@
+/// A least-recently-used cache.
+///
+/// `LruCache` is the textbook use of a doubly-linked list. The entries live
+/// in a `LinkedList` ordered from most to least recently used, and a
+/// `HashMap` maps each key to its node. Looking a key up goes through the
+/// map, and promoting the entry to most recent is an O(1) unlink and
+/// re-link at the front, since every node knows its neighbours. Eviction
+/// pops from the back.
+///
+/// ```text
+///  map:  a ----.     b --.     c ---------.
+///              v         v                v
+///  list: head [a] <-> [b] <-> [d] <-> [c] tail
+///        most recent                   least recent
+/// ```
+///
+/// The map holds a second strong pointer to every node, so the nodes are
+/// shared, which is why the cache empties the map before the list when it
+/// is dropped.
+use core::fmt;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::hash::Hash;
+
+use super::list_iter::ListIter;
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+/// A key-value pair stored in a cache's list.
+#[derive(Clone)]
+pub(crate) struct Entry<K, V> {
+    pub(crate) key: K,
+    pub(crate) value: V,
+}
+
+impl<K: Display, V: Display> Display for Entry<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
+    }
+}
+
+type EntryNode<K, V> = StrongPointer<ListNode<Entry<K, V>>>;
+
+/// A cache that evicts its least recently used entry when it is full.
+pub struct LruCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone + Display,
+{
+    map: HashMap<K, EntryNode<K, V>>,
+    list: LinkedList<Entry<K, V>>,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone + Display,
+{
+    /// Creates an empty cache that holds up to `capacity` entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// let cache: LruCache<&str, u32> = LruCache::new(2);
+    /// assert_eq!(cache.len(), 0);
+    /// assert_eq!(cache.capacity(), 2);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        LruCache {
+            map: HashMap::new(),
+            list: LinkedList::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Sets a callback that receives every entry the cache evicts to make
+    /// room, either in `put` or in `resize`. Entries taken out with
+    /// `pop_lru` or `remove` are not passed to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let evicted = Rc::new(RefCell::new(Vec::new()));
+    /// let log = Rc::clone(&evicted);
+    /// let mut cache = LruCache::new(1);
+    /// cache.set_eviction_callback(move |key, value| log.borrow_mut().push((key, value)));
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    /// assert_eq!(*evicted.borrow(), [("a", 1)]);
+    /// ```
+    pub fn set_eviction_callback(&mut self, callback: impl FnMut(K, V) + 'static) {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Returns the maximum number of entries the cache holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns `true` if the cache holds `key`. Doesn't count as a use.
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns a copy of the value stored under `key` and marks the entry
+    /// as the most recently used one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// let mut cache = LruCache::new(2);
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    /// assert_eq!(cache.get(&"a"), Some(1));
+    /// cache.put("c", 3); // evicts "b", since "a" was just used
+    /// assert_eq!(cache.get(&"b"), None);
+    /// ```
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let node = self.map.get(key)?.clone();
+        let value = node.borrow().data.value.clone();
+        self.list.move_to_front(node);
+        Some(value)
+    }
+
+    /// Returns a copy of the value stored under `key` without marking the
+    /// entry as used.
+    pub fn peek(&self, key: &K) -> Option<V> {
+        self.map
+            .get(key)
+            .map(|node| node.borrow().data.value.clone())
+    }
+
+    /// Stores a value under `key` as the most recently used entry. If the
+    /// key was already present its value is replaced and the old value is
+    /// returned; otherwise, if the cache is full, the least recently used
+    /// entry is evicted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// let mut cache = LruCache::new(2);
+    /// assert_eq!(cache.put("a", 1), None);
+    /// assert_eq!(cache.put("a", 10), Some(1));
+    /// cache.put("b", 2);
+    /// cache.put("c", 3);
+    /// assert_eq!(cache.to_string(), "c: 3 -> b: 2");
+    /// ```
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(node) = self.map.get(&key).cloned() {
+            let old = std::mem::replace(&mut node.borrow_mut().data.value, value);
+            self.list.move_to_front(node);
+            return Some(old);
+        }
+        let node = self.list.insert_after(
+            None,
+            Entry {
+                key: key.clone(),
+                value,
+            },
+        );
+        self.map.insert(key, node);
+        self.evict_to(self.capacity);
+        None
+    }
+
+    /// Removes the entry stored under `key` and returns its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let node = self.map.remove(key)?;
+        Some(self.list.unlink(node).value)
+    }
+
+    /// Removes the least recently used entry and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// let mut cache = LruCache::new(3);
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    /// assert_eq!(cache.pop_lru(), Some(("a", 1)));
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let node = self.list.tail.clone()?;
+        self.map.remove(&node.borrow().data.key);
+        let entry = self.list.unlink(node);
+        Some((entry.key, entry.value))
+    }
+
+    /// Changes the capacity of the cache, evicting the least recently used
+    /// entries if it holds more than that.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::lru::LruCache;
+    /// let mut cache = LruCache::new(3);
+    /// for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+    ///     cache.put(key, value);
+    /// }
+    /// cache.resize(1);
+    /// assert_eq!(cache.to_string(), "c: 3");
+    /// ```
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    /// Removes all entries without passing them to the eviction callback.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    /// Returns an iterator over the entries, from the most recently used to
+    /// the least. Iterating doesn't count as a use.
+    pub fn iter(&self) -> LruIter<K, V> {
+        LruIter {
+            inner: self.list.iter(),
+        }
+    }
+
+    fn evict_to(&mut self, capacity: usize) {
+        while self.len() > capacity {
+            if let Some((key, value)) = self.pop_lru() {
+                if let Some(on_evict) = self.on_evict.as_mut() {
+                    on_evict(key, value);
+                }
+            }
+        }
+    }
+}
+
+impl<K, V> Drop for LruCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone + Display,
+{
+    // Once the map lets go of the nodes, the list is their only owner again
+    // and can unlink them iteratively.
+    fn drop(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<K, V> Display for LruCache<K, V>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone + Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
+
+/// An iterator over the entries of an `LruCache` in recency order.
+pub struct LruIter<K: Clone + Display, V: Clone + Display> {
+    inner: ListIter<Entry<K, V>>,
+}
+
+impl<K: Clone + Display, V: Clone + Display> Iterator for LruIter<K, V> {
+    type Item = (K, V);
+
+    /// Returns the next entry, from most to least recently used.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (entry.key, entry.value))
+    }
+}
@@ -0,0 +1,115 @@
+/// Change notifications for `GenericLinkedList`.
+///
+/// `subscribe` registers a callback that the list calls with a `ListEvent`
+/// after every change, which is enough to keep an audit log, a UI or a
+/// secondary index in step with the list. `subscribe` returns a
+/// `Subscription` token that `unsubscribe` takes back. Values changed in
+/// place through `iter_mut` aren't reported.
+///
+/// Events are only built while someone is subscribed. Without subscribers
+/// the list pays one emptiness check per change and doesn't clone anything.
+///
+/// For `LinkedList` a callback is a `Box<dyn FnMut(&ListEvent<T>)>`; for
+/// `SyncLinkedList` it also has to be `Send + Sync`. `ObserverFamily` picks
+/// the callback type for each pointer family.
+use super::pointer::{ArcFamily, PointerFamily, RcFamily};
+
+/// A change made to a list. Indices are positions in the list right after
+/// the change for insertions and moves, and right before it for removals.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ListEvent<T> {
+    /// `value` was pushed onto the front.
+    PushFront { value: T },
+    /// `value` was pushed onto the back.
+    PushBack { value: T },
+    /// `value` was popped off the front.
+    PopFront { value: T },
+    /// `value` was popped off the back.
+    PopBack { value: T },
+    /// `value` was inserted at `index`.
+    Insert { index: usize, value: T },
+    /// `value` was removed from `index`, by `remove` or `retain`.
+    Remove { index: usize, value: T },
+    /// `value` moved from `from` to `to`, with the values in between shifting
+    /// over by one. `sort` reports its work as moves.
+    Move { from: usize, to: usize, value: T },
+    /// All the values were removed at once. They are listed front to back,
+    /// so the one at index `i` was at position `i`.
+    Clear { values: Vec<T> },
+}
+
+/// The callbacks that a list over a pointer family accepts.
+pub trait ObserverFamily: PointerFamily {
+    /// A boxed callback for list events. It has to be `Send + Sync` for the
+    /// thread-safe family, so that the list stays `Send + Sync` too.
+    type Observer<T>;
+
+    /// Calls `observer` with `event`.
+    fn notify<T>(observer: &mut Self::Observer<T>, event: &ListEvent<T>);
+}
+
+impl ObserverFamily for RcFamily {
+    type Observer<T> = Box<dyn FnMut(&ListEvent<T>)>;
+
+    fn notify<T>(observer: &mut Self::Observer<T>, event: &ListEvent<T>) {
+        observer(event)
+    }
+}
+
+impl ObserverFamily for ArcFamily {
+    type Observer<T> = Box<dyn FnMut(&ListEvent<T>) + Send + Sync>;
+
+    fn notify<T>(observer: &mut Self::Observer<T>, event: &ListEvent<T>) {
+        observer(event)
+    }
+}
+
+/// A token for a subscription, used to unsubscribe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Subscription(u64);
+
+/// The subscribers of one list.
+pub(crate) struct Observers<T, P: ObserverFamily> {
+    subscribers: Vec<(Subscription, P::Observer<T>)>,
+    next_token: u64,
+}
+
+impl<T, P: ObserverFamily> Observers<T, P> {
+    pub(crate) fn new() -> Self {
+        Observers {
+            subscribers: Vec::new(),
+            next_token: 0,
+        }
+    }
+
+    pub(crate) fn subscribe(&mut self, observer: P::Observer<T>) -> Subscription {
+        let token = Subscription(self.next_token);
+        self.next_token += 1;
+        self.subscribers.push((token, observer));
+        token
+    }
+
+    pub(crate) fn unsubscribe(&mut self, token: Subscription) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers.retain(|(other, _)| *other != token);
+        self.subscribers.len() != len
+    }
+
+    /// Returns `true` if anyone is listening, so events are worth building.
+    #[inline(always)]
+    pub(crate) fn is_active(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Builds the event with `event` and hands it to every subscriber, in
+    /// the order they subscribed. Does nothing if nobody is subscribed.
+    #[inline(always)]
+    pub(crate) fn notify(&mut self, event: impl FnOnce() -> ListEvent<T>) {
+        if self.is_active() {
+            let event = event();
+            for (_, observer) in self.subscribers.iter_mut() {
+                P::notify(observer, &event);
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+/// A persistent (immutable) list with structural sharing.
+///
+/// Every operation on a `PersistentList` leaves the list alone and returns a
+/// new version instead. The versions share nodes: a node is never changed
+/// after it is built, so any number of lists can point at the same `Rc`'d
+/// tail. An edit at position `i` copies only the `i` nodes in front of it and
+/// reuses the rest, and cloning a list is a single reference count bump,
+/// which makes it cheap to keep a snapshot of every step.
+///
+/// ```text
+///  a = [1, 2, 3]            a --> [1] --.
+///  b = a.update(0, 9)                    >--> [2] --> [3]
+///                           b --> [9] --'
+/// ```
+///
+/// Sharing tails means a node can't know which node comes before it, so
+/// unlike `LinkedList` these nodes only link forwards.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::iter::FromIterator;
+use std::rc::Rc;
+
+type Link<T> = Rc<PersistentNode<T>>;
+
+struct PersistentNode<T> {
+    data: T,
+    next: Option<Link<T>>,
+}
+
+/// An immutable singly-linked list whose versions share their tails.
+pub struct PersistentList<T: Clone + Display> {
+    head: Option<Link<T>>,
+    num_elements: usize,
+}
+
+impl<T: Clone + Display> PersistentList<T> {
+    /// Creates an empty `PersistentList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let list: PersistentList<u32> = PersistentList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        PersistentList {
+            head: None,
+            num_elements: 0,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the first element, or `None` if the list is empty.
+    pub fn first(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.data)
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.iter().nth(index)
+    }
+
+    /// Returns a new list with `value` in front of this one. The new list
+    /// shares every node of this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let empty = PersistentList::new();
+    /// let one = empty.push_front(1);
+    /// let two = one.push_front(2);
+    /// assert_eq!(empty.to_string(), "");
+    /// assert_eq!(one.to_string(), "1");
+    /// assert_eq!(two.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&self, value: T) -> Self {
+        PersistentList {
+            head: Some(Rc::new(PersistentNode {
+                data: value,
+                next: self.head.clone(),
+            })),
+            num_elements: self.num_elements + 1,
+        }
+    }
+
+    /// Returns the first element along with the rest of the list, or `None`
+    /// if the list is empty. The rest is this list's own tail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let list: PersistentList<u32> = (1..=3).collect();
+    /// let (first, rest) = list.pop_front().unwrap();
+    /// assert_eq!(first, 1);
+    /// assert_eq!(rest.to_string(), "2 -> 3");
+    /// assert_eq!(list.len(), 3);
+    /// ```
+    pub fn pop_front(&self) -> Option<(T, Self)> {
+        self.head.as_ref().map(|node| {
+            let rest = PersistentList {
+                head: node.next.clone(),
+                num_elements: self.num_elements - 1,
+            };
+            (node.data.clone(), rest)
+        })
+    }
+
+    /// Returns a new list with `value` inserted at position `index`. The
+    /// `index` elements in front of it are copied; the rest are shared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let list: PersistentList<u32> = [1, 2, 4].into_iter().collect();
+    /// assert_eq!(list.insert(2, 3).to_string(), "1 -> 2 -> 3 -> 4");
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 4");
+    /// ```
+    pub fn insert(&self, index: usize, value: T) -> Self {
+        assert!(index <= self.num_elements, "insertion index out of bounds");
+        let (prefix, rest) = self.split(index);
+        let rest = Some(Rc::new(PersistentNode {
+            data: value,
+            next: rest,
+        }));
+        Self::rebuild(prefix, rest, self.num_elements + 1)
+    }
+
+    /// Returns a new list with the element at `index` replaced by `value`.
+    /// The elements in front of it are copied; the rest are shared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let list: PersistentList<u32> = (1..=3).collect();
+    /// assert_eq!(list.update(1, 20).to_string(), "1 -> 20 -> 3");
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn update(&self, index: usize, value: T) -> Self {
+        assert!(index < self.num_elements, "index out of bounds");
+        let (prefix, rest) = self.split(index);
+        let rest = Some(Rc::new(PersistentNode {
+            data: value,
+            next: rest.and_then(|node| node.next.clone()),
+        }));
+        Self::rebuild(prefix, rest, self.num_elements)
+    }
+
+    /// Returns this list followed by `other`. This list's elements are
+    /// copied; all of `other` is shared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::PersistentList;
+    /// let front: PersistentList<u32> = (1..=2).collect();
+    /// let back: PersistentList<u32> = (3..=4).collect();
+    /// assert_eq!(front.concat(&back).to_string(), "1 -> 2 -> 3 -> 4");
+    /// ```
+    pub fn concat(&self, other: &Self) -> Self {
+        let (prefix, _) = self.split(self.num_elements);
+        Self::rebuild(
+            prefix,
+            other.head.clone(),
+            self.num_elements + other.num_elements,
+        )
+    }
+
+    /// Returns `true` if both lists start at the same node, so they are the
+    /// same version.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns an iterator over the list.
+    pub fn iter(&self) -> PersistentIter<'_, T> {
+        PersistentIter {
+            current: self.head.as_deref(),
+        }
+    }
+
+    /// Copies the first `index` elements and returns them along with the
+    /// node that follows them.
+    fn split(&self, index: usize) -> (Vec<T>, Option<Link<T>>) {
+        let mut prefix = Vec::with_capacity(index);
+        let mut current = self.head.clone();
+        for _ in 0..index {
+            let node = current.unwrap();
+            prefix.push(node.data.clone());
+            current = node.next.clone();
+        }
+        (prefix, current)
+    }
+
+    /// Builds a list of `prefix` followed by the shared `rest`.
+    fn rebuild(prefix: Vec<T>, rest: Option<Link<T>>, num_elements: usize) -> Self {
+        let head = prefix.into_iter().rev().fold(rest, |next, data| {
+            Some(Rc::new(PersistentNode { data, next }))
+        });
+        PersistentList { head, num_elements }
+    }
+}
+
+impl<T: Clone + Display> Clone for PersistentList<T> {
+    /// Returns another handle to the same version, in O(1).
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+            num_elements: self.num_elements,
+        }
+    }
+}
+
+impl<T: Clone + Display> Default for PersistentList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Drop for PersistentList<T> {
+    // Free the nodes nobody else shares one at a time, stopping at the first
+    // node another version still points at.
+    fn drop(&mut self) {
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = Rc::try_unwrap(node).ok().and_then(|node| node.next);
+        }
+    }
+}
+
+impl<T: Clone + Display> FromIterator<T> for PersistentList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<T> = iter.into_iter().collect();
+        let num_elements = values.len();
+        Self::rebuild(values, None, num_elements)
+    }
+}
+
+impl<T: Clone + Display + PartialEq> PartialEq for PersistentList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_elements == other.num_elements && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Clone + Display> Display for PersistentList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the elements of a `PersistentList`.
+pub struct PersistentIter<'a, T> {
+    current: Option<&'a PersistentNode<T>>,
+}
+
+impl<'a, T> Iterator for PersistentIter<'a, T> {
+    type Item = &'a T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current.map(|node| {
+            self.current = node.next.as_deref();
+            &node.data
+        })
+    }
+}
@@ -0,0 +1,145 @@
+/// Pointer families that a list can be built from.
+///
+/// A doubly-linked list needs four things from its pointers: an owning
+/// ("strong") pointer for the forward links, a non-owning ("weak") pointer for
+/// the backward links, a cell that lets us mutate a node through a shared
+/// pointer, and the operations that move between them. `PointerFamily` bundles
+/// those together so that `ListNode` and `GenericLinkedList` can be written
+/// once and instantiated for either single-threaded or thread-safe pointers.
+///
+/// RcFamily - `Rc<RefCell<T>>` forwards and `rc::Weak<RefCell<T>>` backwards.
+/// ArcFamily - `Arc<RwLock<T>>` forwards and `sync::Weak<RwLock<T>>` backwards.
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The set of pointer and cell types used to link nodes together.
+pub trait PointerFamily: Sized {
+    /// The interior-mutability wrapper around a node.
+    type Cell<T>;
+    /// An owning, reference-counted pointer to a cell.
+    type Strong<T>: Clone + Deref<Target = Self::Cell<T>>;
+    /// A non-owning pointer that can be upgraded back into a `Strong`.
+    type Weak<T>: Clone;
+    /// A shared borrow of the value inside a cell.
+    type Ref<'a, T: 'a>: Deref<Target = T>;
+    /// An exclusive borrow of the value inside a cell.
+    type RefMut<'a, T: 'a>: DerefMut<Target = T>;
+
+    /// Moves `value` into a new cell and returns a strong pointer to it.
+    fn new<T>(value: T) -> Self::Strong<T>;
+
+    /// Creates a weak pointer to the same cell as `ptr`.
+    fn downgrade<T>(ptr: &Self::Strong<T>) -> Self::Weak<T>;
+
+    /// Attempts to promote a weak pointer, returning `None` if the cell has
+    /// already been freed.
+    fn upgrade<T>(ptr: &Self::Weak<T>) -> Option<Self::Strong<T>>;
+
+    /// Immutably borrows the value behind `ptr`.
+    fn borrow<T>(ptr: &Self::Strong<T>) -> Self::Ref<'_, T>;
+
+    /// Mutably borrows the value behind `ptr`.
+    fn borrow_mut<T>(ptr: &Self::Strong<T>) -> Self::RefMut<'_, T>;
+
+    /// Returns `true` if both pointers point to the same cell.
+    fn ptr_eq<T>(a: &Self::Strong<T>, b: &Self::Strong<T>) -> bool;
+
+    /// Returns the inner value if `ptr` is the only strong pointer to it.
+    fn try_unwrap<T>(ptr: Self::Strong<T>) -> Result<T, Self::Strong<T>>;
+
+    /// Returns `true` if `ptr` is the only pointer, strong or weak, to its
+    /// cell.
+    fn is_unique<T>(ptr: &Self::Strong<T>) -> bool;
+}
+
+/// Single-threaded pointers: `Rc<RefCell<T>>` and `rc::Weak<RefCell<T>>`.
+pub struct RcFamily;
+
+impl PointerFamily for RcFamily {
+    type Cell<T> = RefCell<T>;
+    type Strong<T> = std::rc::Rc<RefCell<T>>;
+    type Weak<T> = std::rc::Weak<RefCell<T>>;
+    type Ref<'a, T: 'a> = Ref<'a, T>;
+    type RefMut<'a, T: 'a> = RefMut<'a, T>;
+
+    fn new<T>(value: T) -> Self::Strong<T> {
+        Rc::new(RefCell::new(value))
+    }
+
+    fn downgrade<T>(ptr: &Self::Strong<T>) -> Self::Weak<T> {
+        Rc::downgrade(ptr)
+    }
+
+    fn upgrade<T>(ptr: &Self::Weak<T>) -> Option<Self::Strong<T>> {
+        ptr.upgrade()
+    }
+
+    fn borrow<T>(ptr: &Self::Strong<T>) -> Self::Ref<'_, T> {
+        ptr.borrow()
+    }
+
+    fn borrow_mut<T>(ptr: &Self::Strong<T>) -> Self::RefMut<'_, T> {
+        ptr.borrow_mut()
+    }
+
+    fn ptr_eq<T>(a: &Self::Strong<T>, b: &Self::Strong<T>) -> bool {
+        Rc::ptr_eq(a, b)
+    }
+
+    fn try_unwrap<T>(ptr: Self::Strong<T>) -> Result<T, Self::Strong<T>> {
+        Rc::try_unwrap(ptr).map(RefCell::into_inner)
+    }
+
+    fn is_unique<T>(ptr: &Self::Strong<T>) -> bool {
+        Rc::strong_count(ptr) == 1 && Rc::weak_count(ptr) == 0
+    }
+}
+
+/// Thread-safe pointers: `Arc<RwLock<T>>` and `sync::Weak<RwLock<T>>`.
+///
+/// A poisoned lock only means another thread panicked while holding it. The
+/// list never leaves its links half-updated across a call into user code, so
+/// borrows simply recover the guard and carry on.
+pub struct ArcFamily;
+
+impl PointerFamily for ArcFamily {
+    type Cell<T> = RwLock<T>;
+    type Strong<T> = Arc<RwLock<T>>;
+    type Weak<T> = std::sync::Weak<RwLock<T>>;
+    type Ref<'a, T: 'a> = RwLockReadGuard<'a, T>;
+    type RefMut<'a, T: 'a> = RwLockWriteGuard<'a, T>;
+
+    fn new<T>(value: T) -> Self::Strong<T> {
+        Arc::new(RwLock::new(value))
+    }
+
+    fn downgrade<T>(ptr: &Self::Strong<T>) -> Self::Weak<T> {
+        Arc::downgrade(ptr)
+    }
+
+    fn upgrade<T>(ptr: &Self::Weak<T>) -> Option<Self::Strong<T>> {
+        ptr.upgrade()
+    }
+
+    fn borrow<T>(ptr: &Self::Strong<T>) -> Self::Ref<'_, T> {
+        ptr.read().unwrap_or_else(|poison| poison.into_inner())
+    }
+
+    fn borrow_mut<T>(ptr: &Self::Strong<T>) -> Self::RefMut<'_, T> {
+        ptr.write().unwrap_or_else(|poison| poison.into_inner())
+    }
+
+    fn ptr_eq<T>(a: &Self::Strong<T>, b: &Self::Strong<T>) -> bool {
+        Arc::ptr_eq(a, b)
+    }
+
+    fn try_unwrap<T>(ptr: Self::Strong<T>) -> Result<T, Self::Strong<T>> {
+        Arc::try_unwrap(ptr).map(|lock| lock.into_inner().unwrap_or_else(|p| p.into_inner()))
+    }
+
+    fn is_unique<T>(ptr: &Self::Strong<T>) -> bool {
+        Arc::strong_count(ptr) == 1 && Arc::weak_count(ptr) == 0
+    }
+}
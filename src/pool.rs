@@ -0,0 +1,109 @@
+/// A free list of node allocations for `GenericLinkedList`.
+///
+/// Every push allocates a reference-counted cell and every pop frees one. A
+/// list created with `with_node_pool(capacity)` instead keeps up to
+/// `capacity` popped nodes around and hands them back out to later pushes,
+/// which takes the allocator out of the loop for queue-like workloads with
+/// steady churn.
+///
+/// A node is only recycled when the list holds the last pointer to it, so
+/// nodes that are still shared (with an iterator, or through a clone of the
+/// public `head`) are freed as usual once their last owner lets go.
+use crate::list_node::ListNode;
+use crate::pointer::PointerFamily;
+use std::fmt::Display;
+
+/// Counters describing how a list's node pool has been used.
+///
+/// # Example
+///
+/// ```
+/// # use dll::LinkedList;
+/// let mut list: LinkedList<u32> = LinkedList::with_node_pool(2);
+/// list.push_back(1);
+/// list.pop_front();
+/// list.push_back(2);
+///
+/// let stats = list.pool_stats();
+/// assert_eq!(stats.capacity, 2);
+/// assert_eq!(stats.idle, 0);
+/// assert_eq!(stats.allocated, 1);
+/// assert_eq!(stats.recycled, 1);
+/// assert_eq!(stats.reused, 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The most nodes the pool will hold on to.
+    pub capacity: usize,
+    /// How many nodes are in the pool right now.
+    pub idle: usize,
+    /// How many pushes had to allocate a new node.
+    pub allocated: usize,
+    /// How many pushes reused a node from the pool.
+    pub reused: usize,
+    /// How many removed nodes went back into the pool.
+    pub recycled: usize,
+    /// How many removed nodes were freed because the pool was full.
+    pub discarded: usize,
+}
+
+/// The pool itself. Idle nodes are fully unlinked, but still hold the value
+/// they were last popped with until they are reused or the pool is shrunk.
+pub(crate) struct NodePool<T: Clone + Display, P: PointerFamily> {
+    nodes: Vec<P::Strong<ListNode<T, P>>>,
+    stats: PoolStats,
+}
+
+impl<T: Clone + Display, P: PointerFamily> NodePool<T, P> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        NodePool {
+            nodes: Vec::new(),
+            stats: PoolStats {
+                capacity,
+                ..PoolStats::default()
+            },
+        }
+    }
+
+    /// Returns a node holding `value`, reusing an idle one if there is one.
+    pub(crate) fn take(&mut self, value: T) -> P::Strong<ListNode<T, P>> {
+        match self.nodes.pop() {
+            Some(node) => {
+                P::borrow_mut(&node).data = value;
+                self.stats.reused += 1;
+                node
+            }
+            None => {
+                self.stats.allocated += 1;
+                P::new(ListNode::from(value))
+            }
+        }
+    }
+
+    /// Takes back a node that has been removed from the list.
+    pub(crate) fn give(&mut self, node: P::Strong<ListNode<T, P>>) {
+        if self.nodes.len() < self.stats.capacity && P::is_unique(&node) {
+            {
+                let mut node = P::borrow_mut(&node);
+                node.next = None;
+                node.prev = None;
+            }
+            self.nodes.push(node);
+            self.stats.recycled += 1;
+        } else if self.stats.capacity > 0 {
+            self.stats.discarded += 1;
+        }
+    }
+
+    /// Frees every idle node.
+    pub(crate) fn shrink(&mut self) {
+        self.nodes = Vec::new();
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            idle: self.nodes.len(),
+            ..self.stats
+        }
+    }
+}
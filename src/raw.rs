@@ -0,0 +1,381 @@
+/// A raw-pointer doubly-linked list.
+///
+/// `RawLinkedList` has the same public API as `LinkedList`, but its links are
+/// plain `NonNull<Node<T>>` pointers in both directions instead of
+/// `Rc<RefCell<_>>` going forwards and `Weak<RefCell<_>>` going backwards.
+/// There are no reference counts to bump, no weak pointers to upgrade and no
+/// borrow flags to check, which is exactly what the safe design pays for its
+/// safety. It exists to put a number on that cost, and for hot paths that
+/// would rather not pay it.
+///
+/// It is only compiled with the `unsafe-backend` feature. All of its unsafety
+/// lives in this file, and the shared behavioural suite in `tests/raw.rs` is
+/// meant to be run under Miri:
+///
+/// ```text
+/// cargo +nightly miri test --features unsafe-backend --test raw
+/// ```
+///
+/// Every node is owned by the list (it was leaked from a `Box` when pushed
+/// and is turned back into one when popped), and a node is only ever
+/// reachable through the list's own links.
+use core::fmt;
+use core::marker::PhantomData;
+use std::clone::Clone;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ptr::NonNull;
+
+struct Node<T> {
+    data: T,
+    next: Option<NonNull<Node<T>>>,
+    prev: Option<NonNull<Node<T>>>,
+}
+
+/// A doubly-linked list built on raw pointers.
+///
+/// This `RawLinkedList` allows pushing and popping elements at either end.
+pub struct RawLinkedList<T: Clone + Display> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    num_elements: usize,
+    marker: PhantomData<Box<Node<T>>>, // we own the nodes
+}
+
+// The list owns its nodes outright, just like a `Box` would.
+unsafe impl<T: Clone + Display + Send> Send for RawLinkedList<T> {}
+unsafe impl<T: Clone + Display + Sync> Sync for RawLinkedList<T> {}
+
+impl<T: Clone + Display> RawLinkedList<T> {
+    /// Creates an empty `RawLinkedList`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let list: RawLinkedList<u32> = RawLinkedList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        RawLinkedList {
+            head: None,
+            tail: None,
+            num_elements: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Adds an element to the head of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_front(1);
+    /// list.push_front(2);
+    /// assert_eq!(list.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        let node = Self::alloc(value, self.head, None);
+        match self.head {
+            Some(old_head) => unsafe { (*old_head.as_ptr()).prev = Some(node) },
+            None => self.tail = Some(node),
+        }
+        self.head = Some(node);
+        self.num_elements += 1;
+    }
+
+    /// Removes an element from the head of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// assert_eq!(list.pop_front(), Some(2));
+    /// assert_eq!(list.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|head| unsafe { self.unlink(head) })
+    }
+
+    /// Adds an element to the tail of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.to_string(), "1 -> 2");
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        let node = Self::alloc(value, None, self.tail);
+        match self.tail {
+            Some(old_tail) => unsafe { (*old_tail.as_ptr()).next = Some(node) },
+            None => self.head = Some(node),
+        }
+        self.tail = Some(node);
+        self.num_elements += 1;
+    }
+
+    /// Removes an element from the tail of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// assert_eq!(list.pop_back(), Some(1));
+    /// assert_eq!(list.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.map(|tail| unsafe { self.unlink(tail) })
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// list.retain(|x| x % 2 == 0);
+    /// assert_eq!(list.to_string(), "2");
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let mut current = self.head;
+        while let Some(node) = current {
+            unsafe {
+                current = (*node.as_ptr()).next;
+                if !f(&(*node.as_ptr()).data) {
+                    self.unlink(node);
+                }
+            }
+        }
+    }
+
+    /// Clears the list, removing all values.
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    /// Returns the element at the given index, or `None` if the index is
+    /// out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.get(1), Some(2));
+    /// assert_eq!(list.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.iter().nth(index)
+    }
+
+    /// Returns an iterator over the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> RawListIter<'_, T> {
+        RawListIter {
+            current: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a mutable iterator over the list.
+    ///
+    /// Like `LinkedList::iter_mut` this takes `&mut self`. Here it is what
+    /// keeps the iterator sound: without a `RefCell` in the way, handing out
+    /// `&mut T` from a shared borrow would let two iterators alias the same
+    /// element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    /// assert_eq!(list.to_string(), "10 -> 11");
+    /// ```
+    pub fn iter_mut(&mut self) -> RawListIterMut<'_, T> {
+        RawListIterMut {
+            current: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// The sort is stable: the nodes are ordered by their data and relinked,
+    /// so no element is moved or cloned. The comparator must define a total
+    /// order, see `LinkedList::sort`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::RawLinkedList;
+    /// let mut list: RawLinkedList<u32> = RawLinkedList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.push_back(2);
+    /// list.sort(|a, b| a.cmp(b));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn sort(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let mut nodes = Vec::with_capacity(self.num_elements);
+        let mut current = self.head;
+        while let Some(node) = current {
+            nodes.push(node);
+            current = unsafe { (*node.as_ptr()).next };
+        }
+        nodes.sort_by(|a, b| unsafe { compare(&(*a.as_ptr()).data, &(*b.as_ptr()).data) });
+
+        let mut prev = None;
+        for &node in &nodes {
+            unsafe {
+                (*node.as_ptr()).prev = prev;
+                if let Some(prev) = prev {
+                    (*prev.as_ptr()).next = Some(node);
+                }
+            }
+            prev = Some(node);
+        }
+        if let Some(last) = prev {
+            unsafe { (*last.as_ptr()).next = None };
+        }
+        self.head = nodes.first().copied();
+        self.tail = prev;
+    }
+
+    fn alloc(
+        data: T,
+        next: Option<NonNull<Node<T>>>,
+        prev: Option<NonNull<Node<T>>>,
+    ) -> NonNull<Node<T>> {
+        NonNull::from(Box::leak(Box::new(Node { data, next, prev })))
+    }
+
+    /// Unlinks `node` from its neighbours and frees it, returning its data.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be a node currently linked into this list.
+    unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+        let node = Box::from_raw(node.as_ptr());
+        match node.prev {
+            Some(prev) => (*prev.as_ptr()).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(next) => (*next.as_ptr()).prev = node.prev,
+            None => self.tail = node.prev,
+        }
+        self.num_elements -= 1;
+        node.data
+    }
+}
+
+impl<T: Clone + Display> Default for RawLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display> Drop for RawLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone + Display> Display for RawLinkedList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An immutable iterator over the elements of a `RawLinkedList`.
+pub struct RawListIter<'a, T: Clone + Display> {
+    current: Option<NonNull<Node<T>>>,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+impl<T: Clone + Display> Iterator for RawListIter<'_, T> {
+    type Item = T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current.map(|node| unsafe {
+            let node = &*node.as_ptr();
+            self.current = node.next;
+            node.data.clone()
+        })
+    }
+}
+
+/// A mutable iterator over the elements of a `RawLinkedList`.
+pub struct RawListIterMut<'a, T: Clone + Display> {
+    current: Option<NonNull<Node<T>>>,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T: Clone + Display> Iterator for RawListIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.current.map(|node| unsafe {
+            let node = &mut *node.as_ptr();
+            self.current = node.next;
+            &mut node.data
+        })
+    }
+}
@@ -0,0 +1,774 @@
+/// A text buffer built from a list of chunks.
+///
+/// A `Rope` keeps its text in a `LinkedList` of `String` chunks, each at most
+/// `chunk_size` bytes long. Every chunk caches how many chars and line
+/// breaks it holds, so finding a char offset or a line skips whole chunks,
+/// and an edit only rewrites the one or two chunks it lands in rather than
+/// the whole buffer.
+///
+/// ```text
+///  "fn main() {\n    println!(\"hi\");\n}\n"
+///
+///  head ["fn main() {\n  "] <-> ["  println!(\"hi\""] <-> [");\n}\n"] tail
+///        14 chars, 1 line        15 chars, 0 lines         5 chars, 2 lines
+/// ```
+///
+/// A chunk that grows past `chunk_size` is split in place, and after a
+/// delete the chunks around the gap are merged back together if they fit in
+/// one. Chunks are never empty.
+///
+/// All offsets are in chars, and lines are separated by `'\n'`, so a rope
+/// with `n` line breaks has `n + 1` lines.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::Range;
+
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+/// The chunk size used by `Rope::new`, in bytes.
+pub const DEFAULT_CHUNK_SIZE: usize = 512;
+
+/// A piece of the text with its cached counts.
+#[derive(Clone)]
+struct Chunk {
+    text: String,
+    chars: usize,
+    lines: usize,
+}
+
+impl Chunk {
+    fn new(text: String) -> Self {
+        let mut chunk = Chunk {
+            text,
+            chars: 0,
+            lines: 0,
+        };
+        chunk.recount();
+        chunk
+    }
+
+    fn recount(&mut self) {
+        self.chars = self.text.chars().count();
+        self.lines = self.text.matches('\n').count();
+    }
+}
+
+impl Display for Chunk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+type ChunkNode = StrongPointer<ListNode<Chunk>>;
+
+/// Returns the byte offset of the char at `index` in `text`, or the length of
+/// `text` if `index` is past its end.
+fn byte_offset(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .nth(index)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// Cuts `text` into pieces of at most `max` bytes, on char boundaries.
+fn split_text(text: &str, max: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while rest.len() > max {
+        let mut end = max;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        pieces.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// A text buffer made of a doubly-linked list of bounded-size chunks.
+pub struct Rope {
+    list: LinkedList<Chunk>,
+    chars: usize,
+    lines: usize,
+    chunk_size: usize,
+}
+
+impl Rope {
+    /// Creates an empty rope.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let rope = Rope::new();
+    /// assert_eq!(rope.len_chars(), 0);
+    /// assert_eq!(rope.len_lines(), 1);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates an empty rope whose chunks hold at most `chunk_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is less than 4, the longest UTF-8 char.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(chunk_size >= 4, "chunk size must fit any char");
+        Rope {
+            list: LinkedList::new(),
+            chars: 0,
+            lines: 0,
+            chunk_size,
+        }
+    }
+
+    /// Returns the number of chars in the rope.
+    pub fn len_chars(&self) -> usize {
+        self.chars
+    }
+
+    /// Returns the number of lines in the rope, which is one more than the
+    /// number of line breaks.
+    pub fn len_lines(&self) -> usize {
+        self.lines + 1
+    }
+
+    /// Returns `true` if the rope holds no text.
+    pub fn is_empty(&self) -> bool {
+        self.chars == 0
+    }
+
+    /// Returns the number of chunks the text is stored in.
+    pub fn chunk_count(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns the maximum size of a chunk, in bytes.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Inserts `text` at char offset `at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len_chars`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let mut rope = Rope::from("hello world");
+    /// rope.insert(5, ",");
+    /// rope.insert(12, "!\n");
+    /// assert_eq!(rope.to_string(), "hello, world!\n");
+    /// assert_eq!(rope.len_lines(), 2);
+    /// ```
+    pub fn insert(&mut self, at: usize, text: &str) {
+        assert!(at <= self.chars, "char index out of bounds");
+        if text.is_empty() {
+            return;
+        }
+        let inserted = Chunk::new(text.to_string());
+        self.chars += inserted.chars;
+        self.lines += inserted.lines;
+
+        let Some((node, start)) = self.locate(at) else {
+            for piece in split_text(text, self.chunk_size) {
+                self.list.push_back(Chunk::new(piece));
+            }
+            return;
+        };
+        let overflow = {
+            let chunk = &mut node.borrow_mut().data;
+            let offset = byte_offset(&chunk.text, at - start);
+            chunk.text.insert_str(offset, text);
+            chunk.recount();
+            chunk.text.len() > self.chunk_size
+        };
+        if overflow {
+            self.split(node);
+        }
+    }
+
+    /// Deletes the chars in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is past its end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let mut rope = Rope::from("hello, world");
+    /// rope.delete(5..7);
+    /// assert_eq!(rope.to_string(), "helloworld");
+    /// ```
+    pub fn delete(&mut self, range: Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.chars,
+            "char range out of bounds"
+        );
+        if range.is_empty() {
+            return;
+        }
+        let (first, start) = self.locate_char(range.start);
+        // The last chunk in front of the gap, which may need merging with
+        // the first one after it.
+        let mut before = first.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+        let mut current = Some(first);
+        let mut offset = range.start - start;
+        let mut remaining = range.len();
+        while remaining > 0 {
+            let node = current.unwrap();
+            let (emptied, next) = {
+                let mut node = node.borrow_mut();
+                let chunk = &mut node.data;
+                let end = (offset + remaining).min(chunk.chars);
+                let from = byte_offset(&chunk.text, offset);
+                let to = byte_offset(&chunk.text, end);
+                let lines = chunk.text[from..to].matches('\n').count();
+                chunk.text.replace_range(from..to, "");
+                chunk.chars -= end - offset;
+                chunk.lines -= lines;
+                self.chars -= end - offset;
+                self.lines -= lines;
+                remaining -= end - offset;
+                (chunk.chars == 0, node.next.clone())
+            };
+            if emptied {
+                self.list.unlink(node);
+            } else if offset > 0 {
+                before = Some(node);
+            }
+            offset = 0;
+            current = next;
+        }
+        self.merge_around(before);
+    }
+
+    /// Returns the char at offset `index`, or `None` if it is out of bounds.
+    pub fn char(&self, index: usize) -> Option<char> {
+        if index >= self.chars {
+            return None;
+        }
+        let (node, start) = self.locate_char(index);
+        let chunk = &node.borrow().data;
+        chunk.text.chars().nth(index - start)
+    }
+
+    /// Returns the text in the char range `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or its start is past its end.
+    pub fn slice(&self, range: Range<usize>) -> String {
+        assert!(
+            range.start <= range.end && range.end <= self.chars,
+            "char range out of bounds"
+        );
+        let mut text = String::new();
+        if range.is_empty() {
+            return text;
+        }
+        let (node, start) = self.locate_char(range.start);
+        let mut current = node;
+        let mut offset = range.start - start;
+        let mut remaining = range.len();
+        loop {
+            let next = {
+                let node = current.borrow();
+                let taken = (node.data.chars - offset).min(remaining);
+                text.extend(node.data.text.chars().skip(offset).take(taken));
+                remaining -= taken;
+                node.next.clone()
+            };
+            if remaining == 0 {
+                return text;
+            }
+            current = next.unwrap();
+            offset = 0;
+        }
+    }
+
+    /// Returns the char offset at which line `line` starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `line >= len_lines`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let rope = Rope::from("one\ntwo\nthree");
+    /// assert_eq!(rope.line_to_char(0), 0);
+    /// assert_eq!(rope.line_to_char(2), 8);
+    /// ```
+    pub fn line_to_char(&self, line: usize) -> usize {
+        assert!(line <= self.lines, "line index out of bounds");
+        if line == 0 {
+            return 0;
+        }
+        // Skip the chunks that end before the `line`-th line break.
+        let mut current = self.list.head.clone();
+        let mut start = 0;
+        let mut lines = 0;
+        while let Some(node) = current {
+            let node = node.borrow();
+            let chunk = &node.data;
+            if lines + chunk.lines >= line {
+                let (index, _) = chunk
+                    .text
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '\n')
+                    .nth(line - lines - 1)
+                    .unwrap();
+                return start + index + 1;
+            }
+            start += chunk.chars;
+            lines += chunk.lines;
+            current = node.next.clone();
+        }
+        unreachable!("line counts out of sync")
+    }
+
+    /// Returns the line and column of the char offset `index`. The offset
+    /// right after the text is valid too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len_chars`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let rope = Rope::from("one\ntwo\nthree");
+    /// assert_eq!(rope.char_to_line(0), (0, 0));
+    /// assert_eq!(rope.char_to_line(5), (1, 1));
+    /// assert_eq!(rope.char_to_line(13), (2, 5));
+    /// ```
+    pub fn char_to_line(&self, index: usize) -> (usize, usize) {
+        assert!(index <= self.chars, "char index out of bounds");
+        let mut current = self.list.head.clone();
+        let mut start = 0;
+        let mut line = 0;
+        while let Some(node) = current {
+            let node = node.borrow();
+            let chunk = &node.data;
+            if start + chunk.chars > index {
+                line += chunk
+                    .text
+                    .chars()
+                    .take(index - start)
+                    .filter(|c| *c == '\n')
+                    .count();
+                break;
+            }
+            start += chunk.chars;
+            line += chunk.lines;
+            current = node.next.clone();
+        }
+        (line, index - self.line_to_char(line))
+    }
+
+    /// Returns line `line` without its line break, or `None` if it is out of
+    /// bounds.
+    pub fn line(&self, line: usize) -> Option<String> {
+        if line > self.lines {
+            return None;
+        }
+        let start = self.line_to_char(line);
+        let end = if line < self.lines {
+            self.line_to_char(line + 1) - 1
+        } else {
+            self.chars
+        };
+        Some(self.slice(start..end))
+    }
+
+    /// Returns an iterator over the chars of the rope.
+    pub fn chars(&self) -> Chars {
+        Chars {
+            current: self.list.head.clone(),
+            offset: 0,
+        }
+    }
+
+    /// Returns an iterator over the lines of the rope, without their line
+    /// breaks. It yields `len_lines` lines, so a trailing line break is
+    /// followed by an empty line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::Rope;
+    /// let rope = Rope::from("one\ntwo\n");
+    /// let lines: Vec<String> = rope.lines().collect();
+    /// assert_eq!(lines, ["one", "two", ""]);
+    /// ```
+    pub fn lines(&self) -> Lines {
+        Lines {
+            chars: self.chars(),
+            done: false,
+        }
+    }
+
+    /// Returns a cursor at char offset `at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len_chars`.
+    pub fn cursor(&self, at: usize) -> Cursor<'_> {
+        let mut cursor = Cursor {
+            rope: self,
+            node: None,
+            offset: 0,
+            position: 0,
+        };
+        cursor.move_to(at);
+        cursor
+    }
+
+    /// Removes all text.
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.chars = 0;
+        self.lines = 0;
+    }
+
+    /// Returns the chunk that holds the char offset `at`, or whose end is at
+    /// `at`, along with the offset of its first char. Returns `None` if the
+    /// rope is empty.
+    fn locate(&self, at: usize) -> Option<(ChunkNode, usize)> {
+        let mut current = self.list.head.clone();
+        let mut start = 0;
+        while let Some(node) = current {
+            let (chars, next) = {
+                let node = node.borrow();
+                (node.data.chars, node.next.clone())
+            };
+            if at <= start + chars || next.is_none() {
+                return Some((node, start));
+            }
+            start += chars;
+            current = next;
+        }
+        None
+    }
+
+    /// Like `locate`, but for an offset that is in bounds, so the chunk
+    /// actually holds it.
+    fn locate_char(&self, index: usize) -> (ChunkNode, usize) {
+        let (node, start) = self.locate(index).unwrap();
+        let chars = node.borrow().data.chars;
+        if index - start < chars {
+            return (node, start);
+        }
+        let next = node.borrow().next.clone().unwrap();
+        (next, start + chars)
+    }
+
+    /// Splits the oversized chunk in `node` into chunks that fit.
+    fn split(&mut self, node: ChunkNode) {
+        let pieces = split_text(&node.borrow().data.text, self.chunk_size);
+        let mut pieces = pieces.into_iter();
+        node.borrow_mut().data = Chunk::new(pieces.next().unwrap());
+        let mut prev = node;
+        for piece in pieces {
+            prev = self.list.insert_after(Some(prev), Chunk::new(piece));
+        }
+    }
+
+    /// Merges the chunks on either side of the gap after `node`, or at the
+    /// front if `node` is `None`, with each other and with `node`'s
+    /// predecessor, wherever they fit in one chunk.
+    fn merge_around(&mut self, node: Option<ChunkNode>) {
+        let Some(node) = node.or_else(|| self.list.head.clone()) else {
+            return;
+        };
+        let prev = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+        let node = match prev {
+            Some(prev) if self.merge_with_next(&prev) => prev,
+            _ => node,
+        };
+        self.merge_with_next(&node);
+    }
+
+    /// Appends the chunk after `node` to it if the two fit in one chunk.
+    /// Returns `true` if they were merged.
+    fn merge_with_next(&mut self, node: &ChunkNode) -> bool {
+        let Some(next) = node.borrow().next.clone() else {
+            return false;
+        };
+        let fits = node.borrow().data.text.len() + next.borrow().data.text.len() <= self.chunk_size;
+        if fits {
+            {
+                let mut node = node.borrow_mut();
+                let next = next.borrow();
+                node.data.text.push_str(&next.data.text);
+                node.data.chars += next.data.chars;
+                node.data.lines += next.data.lines;
+            }
+            self.list.unlink(next);
+        }
+        fits
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut rope = Rope::new();
+        rope.insert(0, text);
+        rope
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut current = self.list.head.clone();
+        while let Some(node) = current {
+            let node = node.borrow();
+            write!(f, "{}", node.data)?;
+            current = node.next.clone();
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the chars of a `Rope`.
+pub struct Chars {
+    current: Option<ChunkNode>,
+    // The byte offset of the next char in the current chunk.
+    offset: usize,
+}
+
+impl Iterator for Chars {
+    type Item = char;
+
+    /// Returns the next char of the rope.
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.current.clone()?;
+        let node = node.borrow();
+        let c = node.data.text[self.offset..].chars().next().unwrap();
+        self.offset += c.len_utf8();
+        if self.offset == node.data.text.len() {
+            self.current = node.next.clone();
+            self.offset = 0;
+        }
+        Some(c)
+    }
+}
+
+/// An iterator over the lines of a `Rope`.
+pub struct Lines {
+    chars: Chars,
+    done: bool,
+}
+
+impl Iterator for Lines {
+    type Item = String;
+
+    /// Returns the next line of the rope.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut line = String::new();
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                return Some(line);
+            }
+            line.push(c);
+        }
+        self.done = true;
+        Some(line)
+    }
+}
+
+/// A position in a `Rope` that moves by char, word and line.
+///
+/// The cursor keeps a pointer to the chunk it is in, so stepping by a char
+/// only crosses into a neighbouring chunk at the chunk's edge.
+pub struct Cursor<'a> {
+    rope: &'a Rope,
+    // The chunk holding the char after the cursor, or the last chunk at the
+    // end of the text.
+    node: Option<ChunkNode>,
+    // The char offset of the cursor within `node`.
+    offset: usize,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    /// Returns the char offset of the cursor.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the line and column of the cursor.
+    pub fn line_col(&self) -> (usize, usize) {
+        self.rope.char_to_line(self.position)
+    }
+
+    /// Returns the char after the cursor.
+    pub fn peek_next(&self) -> Option<char> {
+        let node = self.node.as_ref()?;
+        let node = node.borrow();
+        node.data.text.chars().nth(self.offset)
+    }
+
+    /// Returns the char before the cursor.
+    pub fn peek_prev(&self) -> Option<char> {
+        if self.position == 0 {
+            return None;
+        }
+        let node = self.node.as_ref()?;
+        if self.offset > 0 {
+            return node.borrow().data.text.chars().nth(self.offset - 1);
+        }
+        let prev = node
+            .borrow()
+            .prev
+            .as_ref()
+            .and_then(|prev| prev.upgrade())?;
+        let c = prev.borrow().data.text.chars().next_back();
+        c
+    }
+
+    /// Moves the cursor to char offset `at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len_chars`.
+    pub fn move_to(&mut self, at: usize) {
+        assert!(at <= self.rope.chars, "char index out of bounds");
+        self.position = at;
+        self.node = None;
+        self.offset = 0;
+        if let Some((node, start)) = self.rope.locate(at) {
+            self.offset = at - start;
+            self.node = Some(node);
+            self.normalize();
+        }
+    }
+
+    /// Moves forward one char. Returns `false` at the end of the text.
+    pub fn next_char(&mut self) -> bool {
+        if self.position == self.rope.chars {
+            return false;
+        }
+        self.position += 1;
+        self.offset += 1;
+        self.normalize();
+        true
+    }
+
+    /// Moves back one char. Returns `false` at the start of the text.
+    pub fn prev_char(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        if self.offset == 0 {
+            let node = self.node.take().unwrap();
+            let prev = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+            let prev = prev.unwrap();
+            self.offset = prev.borrow().data.chars;
+            self.node = Some(prev);
+        }
+        self.position -= 1;
+        self.offset -= 1;
+        true
+    }
+
+    /// Moves to the start of the next word, or to the end of the text.
+    /// Words are runs of alphanumeric chars and underscores. Returns `false`
+    /// if the cursor didn't move.
+    pub fn next_word(&mut self) -> bool {
+        let start = self.position;
+        while self.peek_next().is_some_and(is_word_char) {
+            self.next_char();
+        }
+        while self.peek_next().is_some_and(|c| !is_word_char(c)) {
+            self.next_char();
+        }
+        self.position != start
+    }
+
+    /// Moves to the start of the current word, or of the previous one if
+    /// the cursor is already at the start of a word. Returns `false` if the
+    /// cursor didn't move.
+    pub fn prev_word(&mut self) -> bool {
+        let start = self.position;
+        while self.peek_prev().is_some_and(|c| !is_word_char(c)) {
+            self.prev_char();
+        }
+        while self.peek_prev().is_some_and(is_word_char) {
+            self.prev_char();
+        }
+        self.position != start
+    }
+
+    /// Moves to the same column on the next line, or to the end of that line
+    /// if it is shorter. Returns `false` on the last line.
+    pub fn next_line(&mut self) -> bool {
+        let (line, column) = self.line_col();
+        if line == self.rope.lines {
+            return false;
+        }
+        self.move_to_line(line + 1, column);
+        true
+    }
+
+    /// Moves to the same column on the previous line, or to the end of that
+    /// line if it is shorter. Returns `false` on the first line.
+    pub fn prev_line(&mut self) -> bool {
+        let (line, column) = self.line_col();
+        if line == 0 {
+            return false;
+        }
+        self.move_to_line(line - 1, column);
+        true
+    }
+
+    fn move_to_line(&mut self, line: usize, column: usize) {
+        let start = self.rope.line_to_char(line);
+        let end = if line < self.rope.lines {
+            self.rope.line_to_char(line + 1) - 1
+        } else {
+            self.rope.chars
+        };
+        self.move_to((start + column).min(end));
+    }
+
+    /// Steps into the next chunk when the cursor sits at the end of one that
+    /// isn't the last.
+    fn normalize(&mut self) {
+        let next = match self.node.as_ref() {
+            Some(node) if self.offset == node.borrow().data.chars => node.borrow().next.clone(),
+            _ => None,
+        };
+        if let Some(next) = next {
+            self.node = Some(next);
+            self.offset = 0;
+        }
+    }
+}
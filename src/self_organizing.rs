@@ -0,0 +1,274 @@
+/// A self-organizing list.
+///
+/// A `SelfOrganizingList` is searched front to back like any list, but every
+/// successful lookup reorders it a little so that the values that are looked
+/// up often drift towards the front, where they are cheap to find. How they
+/// drift is decided by the `Policy`:
+///
+/// ```text
+///  find(c) in  [a] <-> [b] <-> [c] <-> [d]
+///
+///  MoveToFront [c] <-> [a] <-> [b] <-> [d]
+///  Transpose   [a] <-> [c] <-> [b] <-> [d]
+///  Frequency   [c] <-> [a] <-> [b] <-> [d]   if c's count now beats a's
+/// ```
+///
+/// Whatever the policy, the reorder is a single unlink and re-link of the
+/// found node. For `FrequencyCount` the list is kept sorted by lookup count,
+/// and the new spot, in front of the other values with the node's old count,
+/// is noted during the search itself.
+///
+/// The list keeps `AccessStats` on its lookups, so that policies can be
+/// compared by replaying the same trace against each of them.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+use super::list_iter::ListIter;
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+/// How a `SelfOrganizingList` reorders itself after a lookup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// Move the found value to the front.
+    MoveToFront,
+    /// Swap the found value with its predecessor.
+    Transpose,
+    /// Count lookups per value and keep the list sorted by count, most
+    /// looked up first. Ties keep their order.
+    FrequencyCount,
+}
+
+/// Counters describing the lookups made on a `SelfOrganizingList`.
+///
+/// # Example
+///
+/// ```
+/// # use dll::{Policy, SelfOrganizingList};
+/// let mut list = SelfOrganizingList::new(Policy::MoveToFront);
+/// for i in 1..=4 {
+///     list.insert(i);
+/// }
+/// list.access(&4);
+/// list.access(&4);
+/// list.access(&9);
+///
+/// let stats = list.stats();
+/// assert_eq!(stats.lookups, 3);
+/// assert_eq!(stats.hits, 2);
+/// assert_eq!(stats.comparisons, 4 + 1 + 4);
+/// assert_eq!(stats.average_cost(), 3.0);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccessStats {
+    /// How many lookups were made.
+    pub lookups: u64,
+    /// How many lookups found a value.
+    pub hits: u64,
+    /// How many values the lookups compared against, in total.
+    pub comparisons: u64,
+    /// How many lookups moved the found value.
+    pub relinks: u64,
+}
+
+impl AccessStats {
+    /// Returns the average number of comparisons per lookup.
+    pub fn average_cost(&self) -> f64 {
+        if self.lookups == 0 {
+            return 0.0;
+        }
+        self.comparisons as f64 / self.lookups as f64
+    }
+}
+
+/// A value with its lookup count.
+#[derive(Clone)]
+struct Item<T> {
+    value: T,
+    count: u64,
+}
+
+impl<T: Display> Display for Item<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+type ItemNode<T> = StrongPointer<ListNode<Item<T>>>;
+
+/// A list that moves the values it finds towards the front.
+pub struct SelfOrganizingList<T: Clone + Display> {
+    list: LinkedList<Item<T>>,
+    policy: Policy,
+    stats: AccessStats,
+}
+
+impl<T: Clone + Display> SelfOrganizingList<T> {
+    /// Creates an empty list that reorders itself by `policy`.
+    pub fn new(policy: Policy) -> Self {
+        SelfOrganizingList {
+            list: LinkedList::new(),
+            policy,
+            stats: AccessStats::default(),
+        }
+    }
+
+    /// Returns the reordering policy.
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Adds `value` at the back of the list, where a value that has never
+    /// been looked up belongs under every policy.
+    pub fn insert(&mut self, value: T) {
+        self.list.push_back(Item { value, count: 0 });
+    }
+
+    /// Returns the first value matching `predicate`, and moves it forward
+    /// according to the policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::{Policy, SelfOrganizingList};
+    /// let mut list = SelfOrganizingList::new(Policy::Transpose);
+    /// for word in ["alpha", "beta", "gamma"] {
+    ///     list.insert(word);
+    /// }
+    /// assert_eq!(list.find(|word| word.starts_with('g')), Some("gamma"));
+    /// assert_eq!(list.to_string(), "alpha -> gamma -> beta");
+    /// ```
+    pub fn find(&mut self, mut predicate: impl FnMut(&T) -> bool) -> Option<T> {
+        self.stats.lookups += 1;
+        // Under `FrequencyCount`, the first node with the same count as the
+        // current one, which is where the found node moves to.
+        let mut run_start: Option<ItemNode<T>> = None;
+        let mut current = self.list.head.clone();
+        while let Some(node) = current {
+            self.stats.comparisons += 1;
+            let (found, count, next) = {
+                let node = node.borrow();
+                let found = predicate(&node.data.value);
+                (found, node.data.count, node.next.clone())
+            };
+            let run_count = run_start.as_ref().map(|start| start.borrow().data.count);
+            if run_count != Some(count) {
+                run_start = Some(node.clone());
+            }
+            if found {
+                self.stats.hits += 1;
+                let value = node.borrow().data.value.clone();
+                self.reorganize(node, run_start.unwrap());
+                return Some(value);
+            }
+            current = next;
+        }
+        None
+    }
+
+    /// Looks `value` up, moving it forward if it is found. Returns `true` if
+    /// it was found.
+    pub fn access(&mut self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.find(|candidate| candidate == value).is_some()
+    }
+
+    /// Removes the first value equal to `value` and returns it. This isn't
+    /// counted as a lookup.
+    pub fn remove(&mut self, value: &T) -> Option<T>
+    where
+        T: PartialEq,
+    {
+        let mut current = self.list.head.clone();
+        while let Some(node) = current {
+            if node.borrow().data.value == *value {
+                return Some(self.list.unlink(node).value);
+            }
+            current = node.borrow().next.clone();
+        }
+        None
+    }
+
+    /// Returns the lookup statistics.
+    pub fn stats(&self) -> AccessStats {
+        self.stats
+    }
+
+    /// Resets the lookup statistics, leaving the order and the counts of the
+    /// values alone.
+    pub fn reset_stats(&mut self) {
+        self.stats = AccessStats::default();
+    }
+
+    /// Returns an iterator over the values, front to back.
+    pub fn iter(&self) -> Iter<T> {
+        Iter {
+            inner: self.list.iter(),
+        }
+    }
+
+    /// Removes every value.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Moves the found `node` forward. `run_start` is the first node with
+    /// the same count as `node`.
+    fn reorganize(&mut self, node: ItemNode<T>, run_start: ItemNode<T>) {
+        node.borrow_mut().data.count += 1;
+        let prev = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+        let Some(prev) = prev else {
+            return;
+        };
+        let after = match self.policy {
+            Policy::MoveToFront => None,
+            Policy::Transpose => prev.borrow().prev.as_ref().and_then(|prev| prev.upgrade()),
+            Policy::FrequencyCount => {
+                if Rc::ptr_eq(&run_start, &node) {
+                    return;
+                }
+                run_start
+                    .borrow()
+                    .prev
+                    .as_ref()
+                    .and_then(|prev| prev.upgrade())
+            }
+        };
+        self.list.move_after(after, node);
+        self.stats.relinks += 1;
+    }
+}
+
+impl<T: Clone + Display> Display for SelfOrganizingList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
+
+/// An iterator over the values of a `SelfOrganizingList`.
+pub struct Iter<T: Clone + Display> {
+    inner: ListIter<Item<T>>,
+}
+
+impl<T: Clone + Display> Iterator for Iter<T> {
+    type Item = T;
+
+    /// Returns the next value of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| item.value)
+    }
+}
@@ -0,0 +1,504 @@
+/// An indexable skip list.
+///
+/// A `SkipList<K, V>` keeps its entries sorted by key in a stack of linked
+/// lists. Level 0 links every node; each level above it links a random subset
+/// of the level below, about half as many nodes each time, so a search can
+/// skip ahead on the upper levels and only walk a few nodes on each.
+///
+/// Every level follows the same ownership rule as `LinkedList`: `next` links
+/// are strong `Rc`s and `prev` links are weak. A node is owned by each of its
+/// predecessors, but nothing points back at it strongly, so there are no
+/// cycles. Each `next` link also records its width, the number of level-0
+/// steps it spans, which is what makes `rank` and `select` logarithmic.
+///
+/// ```text
+///  level 2   list ----------------4---------------> [d] ------1------> end
+///  level 1   list ------2------> [b] ------2------> [d] --1--> [e] --> end
+///  level 0   list --> [a] --> [b] --> [c] --> [d] --> [e] --> end
+/// ```
+///
+/// As in `LinkedList`, the list itself stands in for the header node: `None`
+/// in a predecessor position means "the list", whose `next` links and widths
+/// are `head` and `head_width`.
+use core::fmt;
+use core::marker::PhantomData;
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::{Bound, RangeBounds};
+use std::rc::{Rc, Weak};
+
+/// No node is ever taller than this, which is plenty for `2^32` entries.
+const MAX_HEIGHT: usize = 32;
+
+type Link<K, V> = Rc<RefCell<SkipNode<K, V>>>;
+type WeakLink<K, V> = Weak<RefCell<SkipNode<K, V>>>;
+
+struct SkipNode<K, V> {
+    key: K,
+    value: V,
+    next: Vec<Option<Link<K, V>>>,
+    prev: Vec<Option<WeakLink<K, V>>>,
+    // The number of level-0 steps each `next` link spans. A link to the end
+    // of the list spans one past the last node.
+    width: Vec<usize>,
+}
+
+/// An ordered map stored in a skip list.
+pub struct SkipList<K: Ord + Clone, V: Clone> {
+    head: Vec<Option<Link<K, V>>>,
+    head_width: Vec<usize>,
+    num_elements: usize,
+    rng: u64,
+}
+
+impl<K: Ord + Clone, V: Clone> SkipList<K, V> {
+    /// Creates an empty `SkipList` whose node heights are drawn from a
+    /// randomly seeded generator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let list: SkipList<u32, &str> = SkipList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_seed(RandomState::new().build_hasher().finish())
+    }
+
+    /// Creates an empty `SkipList` whose node heights are drawn from a
+    /// generator seeded with `seed`. Two lists with the same seed that see
+    /// the same inserts have the same shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut a: SkipList<u32, ()> = SkipList::with_seed(7);
+    /// let mut b: SkipList<u32, ()> = SkipList::with_seed(7);
+    /// for i in 0..100 {
+    ///     a.insert(i, ());
+    ///     b.insert(i, ());
+    /// }
+    /// assert_eq!(a.height(), b.height());
+    /// ```
+    pub fn with_seed(seed: u64) -> Self {
+        SkipList {
+            head: vec![None],
+            head_width: vec![1],
+            num_elements: 0,
+            rng: seed,
+        }
+    }
+
+    /// Returns the number of entries in the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the number of levels currently in use. Level 0 is always
+    /// there, even in an empty list.
+    pub fn height(&self) -> usize {
+        self.head.len()
+    }
+
+    /// Inserts a key-value pair. If the key was already present, its value
+    /// is replaced and the old value is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, &str> = SkipList::new();
+    /// assert_eq!(list.insert(2, "b"), None);
+    /// assert_eq!(list.insert(1, "a"), None);
+    /// assert_eq!(list.insert(2, "B"), Some("b"));
+    /// assert_eq!(list.to_string(), "1: a -> 2: B");
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (mut update, mut ranks) = self.predecessors(&key);
+        if let Some(node) = self.next_of(update[0].as_ref(), 0) {
+            if node.borrow().key == key {
+                return Some(std::mem::replace(&mut node.borrow_mut().value, value));
+            }
+        }
+
+        let height = self.random_height();
+        while self.head.len() < height {
+            self.head.push(None);
+            self.head_width.push(self.num_elements + 1);
+            update.push(None);
+            ranks.push(0);
+        }
+        let rank = ranks[0] + 1;
+        let node = Rc::new(RefCell::new(SkipNode {
+            key,
+            value,
+            next: Vec::with_capacity(height),
+            prev: Vec::with_capacity(height),
+            width: Vec::with_capacity(height),
+        }));
+        for (level, (prev, prev_rank)) in update.iter().zip(&ranks).enumerate() {
+            let prev = prev.as_ref();
+            let width = self.width_of(prev, level);
+            if level < height {
+                let next = self.next_of(prev, level);
+                if let Some(next) = next.as_ref() {
+                    next.borrow_mut().prev[level] = Some(Rc::downgrade(&node));
+                }
+                {
+                    let mut new_node = node.borrow_mut();
+                    new_node.next.push(next);
+                    new_node.prev.push(prev.map(Rc::downgrade));
+                    new_node.width.push(width + 1 - (rank - prev_rank));
+                }
+                self.set_next(prev, level, Some(node.clone()));
+                self.set_width(prev, level, rank - prev_rank);
+            } else {
+                self.set_width(prev, level, width + 1);
+            }
+        }
+        self.num_elements += 1;
+        None
+    }
+
+    /// Removes a key from the list and returns its value, or `None` if the
+    /// key wasn't present.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, &str> = SkipList::new();
+    /// list.insert(1, "a");
+    /// list.insert(2, "b");
+    /// assert_eq!(list.remove(&1), Some("a"));
+    /// assert_eq!(list.remove(&1), None);
+    /// assert_eq!(list.len(), 1);
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (update, _) = self.predecessors(key);
+        let node = self.next_of(update[0].as_ref(), 0)?;
+        if node.borrow().key != *key {
+            return None;
+        }
+
+        let height = node.borrow().next.len();
+        for (level, prev) in update.iter().enumerate() {
+            let prev = prev.as_ref();
+            let width = self.width_of(prev, level);
+            if level < height {
+                let (next, back, span) = {
+                    let mut old = node.borrow_mut();
+                    (
+                        old.next[level].take(),
+                        old.prev[level].take(),
+                        old.width[level],
+                    )
+                };
+                if let Some(next) = next.as_ref() {
+                    next.borrow_mut().prev[level] = back;
+                }
+                self.set_next(prev, level, next);
+                self.set_width(prev, level, width + span - 1);
+            } else {
+                self.set_width(prev, level, width - 1);
+            }
+        }
+        while self.head.len() > 1 && matches!(self.head.last(), Some(None)) {
+            self.head.pop();
+            self.head_width.pop();
+        }
+        self.num_elements -= 1;
+        let value = node.borrow().value.clone();
+        Some(value)
+    }
+
+    /// Returns a copy of the value stored under `key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, &str> = SkipList::new();
+    /// list.insert(1, "a");
+    /// assert_eq!(list.get(&1), Some("a"));
+    /// assert_eq!(list.get(&2), None);
+    /// ```
+    pub fn get(&self, key: &K) -> Option<V> {
+        let (prev, _) = self.descend(|k| k < key);
+        self.next_of(prev.as_ref(), 0)
+            .filter(|node| node.borrow().key == *key)
+            .map(|node| node.borrow().value.clone())
+    }
+
+    /// Returns `true` if the list contains `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of keys in the list that are less than `key`. That
+    /// is the index `key` has, or would have if it were inserted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, ()> = SkipList::new();
+    /// for key in [10, 20, 30] {
+    ///     list.insert(key, ());
+    /// }
+    /// assert_eq!(list.rank(&20), 1);
+    /// assert_eq!(list.rank(&25), 2);
+    /// assert_eq!(list.rank(&5), 0);
+    /// ```
+    pub fn rank(&self, key: &K) -> usize {
+        self.descend(|k| k < key).1
+    }
+
+    /// Returns the entry at position `index` in key order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, &str> = SkipList::new();
+    /// list.insert(20, "b");
+    /// list.insert(10, "a");
+    /// assert_eq!(list.select(1), Some((20, "b")));
+    /// assert_eq!(list.select(2), None);
+    /// ```
+    pub fn select(&self, index: usize) -> Option<(K, V)> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let mut prev: Option<Link<K, V>> = None;
+        let mut rank = 0;
+        for level in (0..self.head.len()).rev() {
+            loop {
+                let width = self.width_of(prev.as_ref(), level);
+                if rank + width > index + 1 {
+                    break;
+                }
+                prev = self.next_of(prev.as_ref(), level);
+                rank += width;
+            }
+        }
+        prev.map(|node| {
+            let node = node.borrow();
+            (node.key.clone(), node.value.clone())
+        })
+    }
+
+    /// Returns an iterator over the entries whose keys fall in `range`, in
+    /// key order. The iterator can also be walked from the back.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SkipList;
+    /// let mut list: SkipList<u32, char> = SkipList::new();
+    /// for (key, value) in [(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd')] {
+    ///     list.insert(key, value);
+    /// }
+    /// let middle: Vec<_> = list.range(2..=3).collect();
+    /// assert_eq!(middle, [(2, 'b'), (3, 'c')]);
+    /// let tail: Vec<_> = list.range(2..).rev().map(|(k, _)| k).collect();
+    /// assert_eq!(tail, [4, 3, 2]);
+    /// ```
+    pub fn range(&self, range: impl RangeBounds<K>) -> SkipIter<'_, K, V> {
+        let (before_front, front_rank) = match range.start_bound() {
+            Bound::Included(start) => self.descend(|k| k < start),
+            Bound::Excluded(start) => self.descend(|k| k <= start),
+            Bound::Unbounded => (None, 0),
+        };
+        let (back, back_rank) = match range.end_bound() {
+            Bound::Included(end) => self.descend(|k| k <= end),
+            Bound::Excluded(end) => self.descend(|k| k < end),
+            Bound::Unbounded => self.descend(|_| true),
+        };
+        SkipIter {
+            front: self.next_of(before_front.as_ref(), 0),
+            back,
+            remaining: back_rank.saturating_sub(front_rank),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over all entries in key order.
+    pub fn iter(&self) -> SkipIter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Clears the list, removing all entries.
+    pub fn clear(&mut self) {
+        // Upper levels only link nodes that level 0 also holds, so drop those
+        // links first and then unlink level 0 one node at a time, so that a
+        // long list doesn't drop recursively.
+        let mut current = self.head.first_mut().and_then(Option::take);
+        self.head = vec![None];
+        self.head_width = vec![1];
+        while let Some(node) = current {
+            let next = std::mem::take(&mut node.borrow_mut().next);
+            current = next.into_iter().next().flatten();
+        }
+        self.num_elements = 0;
+    }
+
+    /// Walks down from the top level, moving right past every node whose key
+    /// satisfies `before`. Returns the last node passed on level 0 (`None` if
+    /// that is the list itself) together with its 1-based position.
+    fn descend(&self, before: impl Fn(&K) -> bool) -> (Option<Link<K, V>>, usize) {
+        let mut prev: Option<Link<K, V>> = None;
+        let mut rank = 0;
+        for level in (0..self.head.len()).rev() {
+            while let Some(next) = self.next_of(prev.as_ref(), level) {
+                if !before(&next.borrow().key) {
+                    break;
+                }
+                rank += self.width_of(prev.as_ref(), level);
+                prev = Some(next);
+            }
+        }
+        (prev, rank)
+    }
+
+    /// Like `descend` for keys less than `key`, but records the predecessor
+    /// and its position on every level.
+    fn predecessors(&self, key: &K) -> (Vec<Option<Link<K, V>>>, Vec<usize>) {
+        let mut update = vec![None; self.head.len()];
+        let mut ranks = vec![0; self.head.len()];
+        let mut prev: Option<Link<K, V>> = None;
+        let mut rank = 0;
+        for level in (0..self.head.len()).rev() {
+            while let Some(next) = self.next_of(prev.as_ref(), level) {
+                if next.borrow().key >= *key {
+                    break;
+                }
+                rank += self.width_of(prev.as_ref(), level);
+                prev = Some(next);
+            }
+            update[level] = prev.clone();
+            ranks[level] = rank;
+        }
+        (update, ranks)
+    }
+
+    /// Draws a node height: 1 with probability 1/2, 2 with 1/4, and so on.
+    fn random_height(&mut self) -> usize {
+        // splitmix64, which is fine with any seed, zero included.
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z.trailing_ones() as usize + 1).min(MAX_HEIGHT)
+    }
+
+    fn next_of(&self, node: Option<&Link<K, V>>, level: usize) -> Option<Link<K, V>> {
+        match node {
+            Some(node) => node.borrow().next[level].clone(),
+            None => self.head[level].clone(),
+        }
+    }
+
+    fn width_of(&self, node: Option<&Link<K, V>>, level: usize) -> usize {
+        match node {
+            Some(node) => node.borrow().width[level],
+            None => self.head_width[level],
+        }
+    }
+
+    fn set_next(&mut self, node: Option<&Link<K, V>>, level: usize, next: Option<Link<K, V>>) {
+        match node {
+            Some(node) => node.borrow_mut().next[level] = next,
+            None => self.head[level] = next,
+        }
+    }
+
+    fn set_width(&mut self, node: Option<&Link<K, V>>, level: usize, width: usize) {
+        match node {
+            Some(node) => node.borrow_mut().width[level] = width,
+            None => self.head_width[level] = width,
+        }
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Drop for SkipList<K, V> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K: Ord + Clone + Display, V: Clone + Display> Display for SkipList<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some((key, value)) = iter.next() {
+            write!(f, "{}: {}", key, value)?;
+            for (key, value) in iter {
+                write!(f, " -> {}: {}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over a range of a `SkipList`.
+pub struct SkipIter<'a, K, V> {
+    front: Option<Link<K, V>>,
+    back: Option<Link<K, V>>,
+    remaining: usize,
+    marker: PhantomData<&'a SkipNode<K, V>>,
+}
+
+impl<K: Clone, V: Clone> Iterator for SkipIter<'_, K, V> {
+    type Item = (K, V);
+
+    /// Returns the next entry of the range.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.front.take().map(|node| {
+            let node = node.borrow();
+            self.front = node.next[0].clone();
+            (node.key.clone(), node.value.clone())
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K: Clone, V: Clone> DoubleEndedIterator for SkipIter<'_, K, V> {
+    /// Returns the last entry of the range that hasn't been returned yet.
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.back.take().map(|node| {
+            let node = node.borrow();
+            self.back = node.prev[0].as_ref().and_then(Weak::upgrade);
+            (node.key.clone(), node.value.clone())
+        })
+    }
+}
+
+impl<K: Clone, V: Clone> ExactSizeIterator for SkipIter<'_, K, V> {}
@@ -0,0 +1,206 @@
+/// Copy-on-write snapshots of a `GenericLinkedList`.
+///
+/// `snapshot` hands out a read-only view of the list in O(1): the view just
+/// holds on to the first node and remembers the length. The nodes themselves
+/// stay shared between the view and the live list, and the live list only
+/// copies them when it is about to change something a view could see.
+///
+/// A view walks `next` links and reads `data`, and nothing else. So while
+/// snapshots are alive, the live list thinks of itself as three runs of
+/// nodes:
+///
+/// ```text
+///   front (private)     shared with snapshots      back (private)
+///  [x] <-> [y] <-> [a] <-> [b] <-> [c] <-> [d] <-> [z]
+/// ```
+///
+/// Pushing and popping at the front only moves the boundary of the shared
+/// run, and pushing and popping at the back only touches the last shared
+/// node's `next`, which is past the end of every view that reaches it. The
+/// one end operation that needs a copy is a `pop_back` that reaches into the
+/// shared run, since the node in front of it has to end the live list while
+/// still leading on to the popped node in the views; that copies the shared
+/// run once, after which the list is private again. `retain`, `sort` and
+/// `iter_mut` can touch any node, so they copy the shared run up front.
+use core::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use super::list_node::ListNode;
+use super::pointer::PointerFamily;
+
+/// A read-only view of a list as it was when the snapshot was taken.
+pub struct Snapshot<T: Clone + Display, P: PointerFamily> {
+    head: Option<P::Strong<ListNode<T, P>>>,
+    num_elements: usize,
+    _token: P::Strong<()>,
+}
+
+impl<T: Clone + Display, P: PointerFamily> Snapshot<T, P> {
+    pub(crate) fn new(
+        head: Option<P::Strong<ListNode<T, P>>>,
+        num_elements: usize,
+        token: P::Strong<()>,
+    ) -> Self {
+        Snapshot {
+            head,
+            num_elements,
+            _token: token,
+        }
+    }
+
+    /// Returns the length of the list when the snapshot was taken.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list was empty when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns an iterator over the snapshot.
+    pub fn iter(&self) -> SnapshotIter<T, P> {
+        SnapshotIter {
+            current: self.head.clone(),
+            remaining: self.num_elements,
+        }
+    }
+}
+
+impl<T: Clone + Display, P: PointerFamily> Drop for Snapshot<T, P> {
+    // The same iterative unlinking as the list's own `Drop`, stopping at the
+    // first node the list or another snapshot still holds.
+    fn drop(&mut self) {
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = P::try_unwrap(node).ok().and_then(|node| node.next);
+        }
+    }
+}
+
+impl<T: Clone + Display, P: PointerFamily> Display for Snapshot<T, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the elements of a `Snapshot`.
+///
+/// It stops after the snapshot's length rather than at the end of the
+/// chain, since the live list may have linked more nodes on after it.
+pub struct SnapshotIter<T: Clone + Display, P: PointerFamily> {
+    current: Option<P::Strong<ListNode<T, P>>>,
+    remaining: usize,
+}
+
+impl<T: Clone + Display, P: PointerFamily> Iterator for SnapshotIter<T, P> {
+    type Item = T;
+
+    /// Returns the next element of the snapshot.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.current.take().map(|node| {
+            let node = P::borrow(&node);
+            if self.remaining > 0 {
+                self.current = node.next.clone();
+            }
+            node.data.clone()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: Clone + Display, P: PointerFamily> ExactSizeIterator for SnapshotIter<T, P> {}
+
+/// Tracks which run of a list's nodes is shared with live snapshots.
+pub(crate) struct Sharing<P: PointerFamily> {
+    // Every snapshot holds a clone of the token, so the list can tell when
+    // the last one is gone.
+    token: Option<P::Strong<()>>,
+    // The number of private nodes in front of the shared run.
+    front: usize,
+    // The length of the shared run.
+    shared: usize,
+}
+
+impl<P: PointerFamily> Sharing<P> {
+    pub(crate) fn new() -> Self {
+        Sharing {
+            token: None,
+            front: 0,
+            shared: 0,
+        }
+    }
+
+    /// Marks all `len` nodes as shared and returns a token for a new
+    /// snapshot.
+    pub(crate) fn share_all(&mut self, len: usize) -> P::Strong<()> {
+        self.front = 0;
+        self.shared = len;
+        self.token.get_or_insert_with(|| P::new(())).clone()
+    }
+
+    /// Returns the number of private nodes in front of the shared run and
+    /// the length of the run. Once the last snapshot is dropped nothing is
+    /// shared anymore.
+    pub(crate) fn span(&mut self) -> (usize, usize) {
+        let alive = self
+            .token
+            .as_ref()
+            .is_some_and(|token| !P::is_unique(token));
+        if !alive {
+            self.forget();
+        }
+        (self.front, self.shared)
+    }
+
+    /// Forgets the shared run, after it was copied or the list was cleared.
+    pub(crate) fn forget(&mut self) {
+        self.front = 0;
+        self.shared = 0;
+    }
+
+    /// Records a node pushed onto the front of the list.
+    pub(crate) fn push_front(&mut self) {
+        if self.span().1 > 0 {
+            self.front += 1;
+        }
+    }
+
+    /// Records that the front node is about to be popped. Returns `true` if
+    /// it is shared, in which case its links must be left alone.
+    pub(crate) fn pop_front(&mut self) -> bool {
+        match self.span() {
+            (_, 0) => false,
+            (0, _) => {
+                self.shared -= 1;
+                true
+            }
+            _ => {
+                self.front -= 1;
+                false
+            }
+        }
+    }
+
+    /// Returns `true` if popping the back node of a list of `len` nodes
+    /// would change a shared node, so the shared run has to be copied first.
+    pub(crate) fn pop_back_needs_copy(&mut self, len: usize) -> bool {
+        let (front, shared) = self.span();
+        shared > 0 && front + shared == len
+    }
+}
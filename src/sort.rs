@@ -1,26 +1,46 @@
-use std::{cell::RefCell, fmt::Display, rc::Rc, cmp::Ordering};
+use std::{cell::RefCell, cmp::Ordering, fmt::Display, rc::Rc};
 
-use super::{LinkedList, ListNode};
+use super::journal::Op;
+use super::observer::{ListEvent, ObserverFamily};
+use super::{GenericLinkedList, LinkedList, ListNode};
 
 /// A really slow sort algorithm.
-pub fn bubble_sort<T: Clone + Display>(
-    list: &mut LinkedList<T>,
+///
+/// Adjacent out-of-order nodes trade their data, so the links themselves
+/// never change while sorting. Each trade is reported to the list's
+/// observers as the second value moving in front of the first, and the
+/// sort as a whole is journaled as the permutation it made.
+pub fn bubble_sort<T: Clone + Display, P: ObserverFamily>(
+    list: &mut GenericLinkedList<T, P>,
     mut compare: impl FnMut(&T, &T) -> Ordering,
 ) {
     let len = list.len();
+    // Where each value started out, followed through the trades.
+    let mut order: Vec<usize> = if list.journal.is_recording() {
+        (0..len).collect()
+    } else {
+        Vec::new()
+    };
     for i in 0..len {
         for j in 0..len - i - 1 {
             let first = list.get_ptr(j).unwrap();
             let second = list.get_ptr(j + 1).unwrap();
-            if compare(&first.borrow().data, &second.borrow().data) == Ordering::Greater {
-                second.borrow_mut().prev = first.borrow().prev.clone();
-                first.borrow_mut().prev = Some(Rc::downgrade(&first.clone()));
-                first.borrow_mut().next = second.borrow().next.clone();
-                second.borrow_mut().next = Some(second.clone());
-                first.swap(&second);
+            let mut first = P::borrow_mut(&first);
+            let mut second = P::borrow_mut(&second);
+            if compare(&first.data, &second.data) == Ordering::Greater {
+                std::mem::swap(&mut first.data, &mut second.data);
+                if !order.is_empty() {
+                    order.swap(j, j + 1);
+                }
+                list.observers.notify(|| ListEvent::Move {
+                    from: j + 1,
+                    to: j,
+                    value: first.data.clone(),
+                });
             }
         }
     }
+    list.journal.record(|| Op::Sort { order });
 }
 
 // print with a cycle cap
@@ -40,7 +60,7 @@ fn _print<T: Clone + Display>(list: &mut LinkedList<T>, max_depth: u32) {
 // print with a cycle cap
 fn _print_reverse<T: Clone + Display>(node: Option<&Rc<RefCell<ListNode<T>>>>, max_depth: u32) {
     if let Some(list_node) = node {
-        if let None = list_node.borrow().prev {
+        if list_node.borrow().prev.is_none() {
             print!("{}", list_node.borrow().data);
         } else if max_depth > 0 {
             _print_reverse(
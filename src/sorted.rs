@@ -0,0 +1,277 @@
+/// A linked list that keeps its elements sorted.
+///
+/// `SortedLinkedList` wraps a `LinkedList` and only lets elements in through
+/// `insert`, which puts each one at its sorted position. Elements that
+/// compare equal stay in the order they were inserted, so the list works as
+/// a stable priority list: `pop_min` hands out the oldest of the smallest
+/// elements.
+///
+/// The order comes from a `Comparator`, which is `NaturalOrder` (the type's
+/// own `Ord`) unless the list is built with `with_comparator`. Any
+/// `Fn(&T, &T) -> Ordering` closure is a comparator too.
+use core::fmt;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::ops::{Bound, RangeBounds};
+
+use super::list_iter::ListIter;
+use super::list_node::{ListNode, StrongPointer};
+use super::LinkedList;
+
+/// Defines the order of the elements in a `SortedLinkedList`.
+pub trait Comparator<T> {
+    /// Compares two elements.
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Orders elements by their `Ord` implementation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NaturalOrder;
+
+impl<T: Ord> Comparator<T> for NaturalOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Comparator<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// A doubly-linked list kept in sorted order.
+pub struct SortedLinkedList<T: Clone + Display, C = NaturalOrder> {
+    list: LinkedList<T>,
+    comparator: C,
+}
+
+impl<T: Clone + Display + Ord> SortedLinkedList<T> {
+    /// Creates an empty `SortedLinkedList` in ascending order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let list: SortedLinkedList<u32> = SortedLinkedList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self::with_comparator(NaturalOrder)
+    }
+}
+
+impl<T: Clone + Display, C: Comparator<T>> SortedLinkedList<T, C> {
+    /// Creates an empty `SortedLinkedList` ordered by `comparator`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let mut list = SortedLinkedList::with_comparator(|a: &u32, b: &u32| b.cmp(a));
+    /// list.insert(1);
+    /// list.insert(3);
+    /// list.insert(2);
+    /// assert_eq!(list.to_string(), "3 -> 2 -> 1");
+    /// ```
+    pub fn with_comparator(comparator: C) -> Self {
+        SortedLinkedList {
+            list: LinkedList::new(),
+            comparator,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Inserts an element at its sorted position, after any elements that
+    /// compare equal to it.
+    ///
+    /// The search starts from the back, so inserting elements that are
+    /// already in order takes constant time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    /// list.insert(3);
+    /// list.insert(1);
+    /// list.insert(2);
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn insert(&mut self, value: T) {
+        let mut prev = self.list.tail.clone();
+        while let Some(node) = prev {
+            if self.comparator.compare(&node.borrow().data, &value) != Ordering::Greater {
+                prev = Some(node);
+                break;
+            }
+            prev = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+        }
+        self.list.insert_after(prev, value);
+    }
+
+    /// Returns the smallest element, or `None` if the list is empty.
+    pub fn first(&self) -> Option<T> {
+        self.list
+            .head
+            .as_ref()
+            .map(|node| node.borrow().data.clone())
+    }
+
+    /// Returns the largest element, or `None` if the list is empty.
+    pub fn last(&self) -> Option<T> {
+        self.list
+            .tail
+            .as_ref()
+            .map(|node| node.borrow().data.clone())
+    }
+
+    /// Removes the smallest element and returns it. Of several equal
+    /// elements, the one inserted first goes first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    /// list.insert(2);
+    /// list.insert(1);
+    /// assert_eq!(list.pop_min(), Some(1));
+    /// assert_eq!(list.pop_min(), Some(2));
+    /// assert_eq!(list.pop_min(), None);
+    /// ```
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    /// Removes the largest element and returns it. Of several equal
+    /// elements, the one inserted last goes first.
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    /// Removes the first element that compares equal to `value` and returns
+    /// it, or returns `None` if there is no such element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    /// for i in [1, 2, 2, 3] {
+    ///     list.insert(i);
+    /// }
+    /// assert_eq!(list.remove(&2), Some(2));
+    /// assert_eq!(list.remove(&4), None);
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn remove(&mut self, value: &T) -> Option<T> {
+        let mut current = self.list.head.clone();
+        while let Some(node) = current {
+            let order = self.comparator.compare(&node.borrow().data, value);
+            match order {
+                Ordering::Less => current = node.borrow().next.clone(),
+                Ordering::Equal => return Some(self.list.unlink(node)),
+                Ordering::Greater => return None,
+            }
+        }
+        None
+    }
+
+    /// Returns an iterator over the elements that fall in `range`, in
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::SortedLinkedList;
+    /// let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    /// for i in [5, 1, 4, 2, 3] {
+    ///     list.insert(i);
+    /// }
+    /// assert_eq!(list.range(2..4).collect::<Vec<_>>(), [2, 3]);
+    /// assert_eq!(list.range(4..).collect::<Vec<_>>(), [4, 5]);
+    /// ```
+    pub fn range(&self, range: impl RangeBounds<T>) -> SortedRange<'_, T, C> {
+        let mut current = self.list.head.clone();
+        while let Some(node) = current.as_ref() {
+            let order = match range.start_bound() {
+                Bound::Included(start) => self.comparator.compare(&node.borrow().data, start),
+                Bound::Excluded(start) => self
+                    .comparator
+                    .compare(&node.borrow().data, start)
+                    .then(Ordering::Less),
+                Bound::Unbounded => Ordering::Greater,
+            };
+            if order != Ordering::Less {
+                break;
+            }
+            let next = node.borrow().next.clone();
+            current = next;
+        }
+        SortedRange {
+            current,
+            end: range.end_bound().cloned(),
+            comparator: &self.comparator,
+        }
+    }
+
+    /// Returns an iterator over the list in sorted order.
+    pub fn iter(&self) -> ListIter<T> {
+        self.list.iter()
+    }
+
+    /// Clears the list, removing all values.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+}
+
+impl<T: Clone + Display + Ord> Default for SortedLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Display, C> Display for SortedLinkedList<T, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.list)
+    }
+}
+
+/// An iterator over a range of a `SortedLinkedList`.
+pub struct SortedRange<'a, T: Clone + Display, C> {
+    current: Option<StrongPointer<ListNode<T>>>,
+    end: Bound<T>,
+    comparator: &'a C,
+}
+
+impl<T: Clone + Display, C: Comparator<T>> Iterator for SortedRange<'_, T, C> {
+    type Item = T;
+
+    /// Returns the next element of the range.
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.current.take()?;
+        let node = node.borrow();
+        let in_range = match &self.end {
+            Bound::Included(end) => self.comparator.compare(&node.data, end) != Ordering::Greater,
+            Bound::Excluded(end) => self.comparator.compare(&node.data, end) == Ordering::Less,
+            Bound::Unbounded => true,
+        };
+        if !in_range {
+            return None;
+        }
+        self.current = node.next.clone();
+        Some(node.data.clone())
+    }
+}
@@ -0,0 +1,770 @@
+/// An unrolled doubly-linked list.
+///
+/// Every node of `UnrolledList<T, N>` holds up to `N` elements in an inline
+/// array, so the links, the node header and the allocation are shared by up
+/// to `N` elements instead of being paid for by each one. For small `Copy`
+/// values that overhead is most of what a `LinkedList` node weighs.
+///
+/// Nodes live in a `Vec` and are linked by index, the same way `ArenaList`
+/// links its slots. A node that fills up is split in half when something is
+/// inserted into it, and a node that drops below half full absorbs its
+/// successor when the two fit into one node.
+///
+/// ```text
+///  N = 4
+///   [a b c _] <-> [d e _ _] <-> [f g h i]
+///   head                          tail
+/// ```
+///
+/// Elements must be `Copy`: node arrays are always fully initialised (a new
+/// node is filled with copies of its first element), which keeps this file
+/// free of `unsafe`.
+use core::fmt;
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+/// Marks the absence of a link.
+const NIL: u32 = u32::MAX;
+
+struct Chunk<T, const N: usize> {
+    items: [T; N],
+    len: usize, // `0` while the chunk is on the free list
+    next: u32,  // next chunk, or next free chunk
+    prev: u32,
+}
+
+/// A doubly-linked list with up to `N` elements per node.
+pub struct UnrolledList<T, const N: usize> {
+    chunks: Vec<Chunk<T, N>>,
+    head: u32,
+    tail: u32,
+    free: u32,
+    num_elements: usize,
+}
+
+impl<T: Copy, const N: usize> UnrolledList<T, N> {
+    /// Creates an empty `UnrolledList`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is less than 2, since a node has to be split in two.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let list: UnrolledList<u32, 16> = UnrolledList::new();
+    /// assert_eq!(list.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        assert!(
+            N >= 2,
+            "UnrolledList needs room for at least two elements per node"
+        );
+        UnrolledList {
+            chunks: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            free: NIL,
+            num_elements: 0,
+        }
+    }
+
+    /// Returns the length of the list.
+    pub fn len(&self) -> usize {
+        self.num_elements
+    }
+
+    /// Returns `true` if the list contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.num_elements == 0
+    }
+
+    /// Returns the number of nodes currently linked into the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// for i in 0..10 {
+    ///     list.push_back(i);
+    /// }
+    /// assert_eq!(list.node_count(), 3);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut current = self.head;
+        while current != NIL {
+            count += 1;
+            current = self.chunks[current as usize].next;
+        }
+        count
+    }
+
+    /// Adds an element to the head of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_front(1);
+    /// list.push_front(2);
+    /// assert_eq!(list.to_string(), "2 -> 1");
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        let head = self.head;
+        if head != NIL && self.chunks[head as usize].len < N {
+            self.insert_in(head, 0, value);
+        } else {
+            self.alloc(NIL, head, value);
+            self.num_elements += 1;
+        }
+    }
+
+    /// Adds an element to the tail of the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.to_string(), "1 -> 2");
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        let tail = self.tail;
+        if tail != NIL && self.chunks[tail as usize].len < N {
+            let len = self.chunks[tail as usize].len;
+            self.insert_in(tail, len, value);
+        } else {
+            self.alloc(tail, NIL, value);
+            self.num_elements += 1;
+        }
+    }
+
+    /// Removes an element from the head of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_front(), Some(1));
+    /// assert_eq!(list.pop_front(), Some(2));
+    /// assert_eq!(list.pop_front(), None);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<T> {
+        match self.head {
+            NIL => None,
+            head => Some(self.remove_in(head, 0)),
+        }
+    }
+
+    /// Removes an element from the tail of the list and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.pop_back(), Some(2));
+    /// assert_eq!(list.pop_back(), Some(1));
+    /// assert_eq!(list.pop_back(), None);
+    /// ```
+    pub fn pop_back(&mut self) -> Option<T> {
+        match self.tail {
+            NIL => None,
+            tail => {
+                let len = self.chunks[tail as usize].len;
+                Some(self.remove_in(tail, len - 1))
+            }
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting everything after it
+    /// towards the tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.insert(1, 2);
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.num_elements, "insertion index out of bounds");
+        if index == self.num_elements {
+            self.push_back(value);
+            return;
+        }
+        let (chunk, offset) = self.locate(index);
+        let prev = self.chunks[chunk as usize].prev;
+        if offset == 0 && prev != NIL && self.chunks[prev as usize].len < N {
+            // Append to the previous node rather than shifting this one.
+            let len = self.chunks[prev as usize].len;
+            self.insert_in(prev, len, value);
+        } else {
+            self.insert_in(chunk, offset, value);
+        }
+    }
+
+    /// Removes and returns the element at position `index`, or `None` if the
+    /// index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// list.push_back(3);
+    /// assert_eq!(list.remove(1), Some(2));
+    /// assert_eq!(list.remove(5), None);
+    /// assert_eq!(list.to_string(), "1 -> 3");
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let (chunk, offset) = self.locate(index);
+        Some(self.remove_in(chunk, offset))
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// for i in 0..10 {
+    ///     list.push_back(i);
+    /// }
+    /// list.retain(|x| x % 3 == 0);
+    /// assert_eq!(list.to_string(), "0 -> 3 -> 6 -> 9");
+    /// assert_eq!(list.node_count(), 1); // 0 3 | 6 | 9 merged into one node
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let mut current = self.head;
+        while current != NIL {
+            let chunk = &mut self.chunks[current as usize];
+            let next = chunk.next;
+            let mut kept = 0;
+            for i in 0..chunk.len {
+                let item = chunk.items[i];
+                if f(&item) {
+                    chunk.items[kept] = item;
+                    kept += 1;
+                }
+            }
+            self.num_elements -= chunk.len - kept;
+            chunk.len = kept;
+            if kept == 0 {
+                self.release(current);
+            }
+            current = next;
+        }
+
+        // Filtering can leave lots of sparse nodes behind; merge them.
+        let mut current = self.head;
+        while current != NIL {
+            while self.merge_next(current) {}
+            current = self.chunks[current as usize].next;
+        }
+    }
+
+    /// Clears the list, removing all values and releasing the nodes.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.free = NIL;
+        self.num_elements = 0;
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// the index is out of bounds.
+    ///
+    /// Indexing skips over whole nodes, so it walks about `index / N` links.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// assert_eq!(list.get(1), Some(&2));
+    /// assert_eq!(list.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let (chunk, offset) = self.locate(index);
+        Some(&self.chunks[chunk as usize].items[offset])
+    }
+
+    /// Returns a mutable reference to the element at the given index, or
+    /// `None` if the index is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.num_elements {
+            return None;
+        }
+        let (chunk, offset) = self.locate(index);
+        Some(&mut self.chunks[chunk as usize].items[offset])
+    }
+
+    /// Returns an iterator over the list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(2);
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(&1));
+    /// assert_eq!(iter.next(), Some(&2));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> UnrolledIter<'_, T, N> {
+        UnrolledIter {
+            chunks: &self.chunks,
+            current: self.head,
+            offset: 0,
+        }
+    }
+
+    /// Returns a mutable iterator over the list.
+    ///
+    /// The nodes are borrowed through `Vec::iter_mut` and put in link order
+    /// up front, which keeps this free of `unsafe` at the cost of a buffer
+    /// of one slice per node.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(0);
+    /// list.push_back(1);
+    /// for element in list.iter_mut() {
+    ///     *element += 10;
+    /// }
+    /// assert_eq!(list.to_string(), "10 -> 11");
+    /// ```
+    pub fn iter_mut(&mut self) -> UnrolledIterMut<'_, T, N> {
+        // Number the chunks in use in link order.
+        let mut position = vec![None; self.chunks.len()];
+        let mut count = 0;
+        let mut current = self.head;
+        while current != NIL {
+            position[current as usize] = Some(count);
+            count += 1;
+            current = self.chunks[current as usize].next;
+        }
+        let mut chunks: Vec<&mut [T]> = (0..count).map(|_| Default::default()).collect();
+        for (chunk, i) in self.chunks.iter_mut().zip(position) {
+            if let Some(i) = i {
+                chunks[i] = &mut chunk.items[..chunk.len];
+            }
+        }
+        UnrolledIterMut {
+            chunks: chunks.into_iter(),
+            items: Default::default(),
+        }
+    }
+
+    /// Sorts the list with a comparator function.
+    ///
+    /// The sort is stable. The values are copied out, sorted and written back
+    /// into the same nodes, so the shape of the list does not change.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// list.push_back(2);
+    /// list.sort(|a, b| a.cmp(b));
+    /// assert_eq!(list.to_string(), "1 -> 2 -> 3");
+    /// ```
+    pub fn sort(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+        let mut values: Vec<T> = self.iter().copied().collect();
+        values.sort_by(|a, b| compare(a, b));
+        let mut values = values.into_iter();
+        let mut current = self.head;
+        while current != NIL {
+            let chunk = &mut self.chunks[current as usize];
+            let len = chunk.len;
+            for item in &mut chunk.items[..len] {
+                *item = values.next().unwrap();
+            }
+            current = chunk.next;
+        }
+    }
+
+    /// Returns a cursor that starts before the first element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dll::UnrolledList;
+    /// let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    /// list.push_back(1);
+    /// list.push_back(3);
+    /// {
+    ///     let mut cursor = list.cursor();
+    ///     assert_eq!(cursor.current(), None);
+    ///     assert!(cursor.move_next());
+    ///     assert_eq!(cursor.current(), Some(&1));
+    ///     cursor.insert_after(2);
+    ///     *cursor.current_mut().unwrap() = 0;
+    /// }
+    /// assert_eq!(list.to_string(), "0 -> 2 -> 3");
+    /// ```
+    pub fn cursor(&mut self) -> UnrolledCursor<'_, T, N> {
+        UnrolledCursor {
+            list: self,
+            chunk: NIL,
+            offset: 0,
+        }
+    }
+
+    /// Returns the node and offset of the element at `index`, walking from
+    /// whichever end is closer. `index` must be in bounds.
+    fn locate(&self, index: usize) -> (u32, usize) {
+        if index < self.num_elements / 2 {
+            let mut current = self.head;
+            let mut index = index;
+            loop {
+                let chunk = &self.chunks[current as usize];
+                if index < chunk.len {
+                    return (current, index);
+                }
+                index -= chunk.len;
+                current = chunk.next;
+            }
+        } else {
+            let mut current = self.tail;
+            let mut from_back = self.num_elements - index;
+            loop {
+                let chunk = &self.chunks[current as usize];
+                if from_back <= chunk.len {
+                    return (current, chunk.len - from_back);
+                }
+                from_back -= chunk.len;
+                current = chunk.prev;
+            }
+        }
+    }
+
+    /// Inserts `value` at `offset` within `chunk`, splitting the node first
+    /// if it is full. Returns where the value ended up.
+    fn insert_in(&mut self, chunk: u32, offset: usize, value: T) -> (u32, usize) {
+        let (mut chunk, mut offset) = (chunk, offset);
+        if self.chunks[chunk as usize].len == N {
+            let upper = self.split(chunk);
+            if offset > N / 2 {
+                chunk = upper;
+                offset -= N / 2;
+            }
+        }
+        let node = &mut self.chunks[chunk as usize];
+        let len = node.len;
+        node.items.copy_within(offset..len, offset + 1);
+        node.items[offset] = value;
+        node.len += 1;
+        self.num_elements += 1;
+        (chunk, offset)
+    }
+
+    /// Removes the element at `offset` within `chunk`. An emptied node is
+    /// released; a sparse one absorbs its successor if they fit together.
+    /// Neither moves any element that comes before the removed one.
+    fn remove_in(&mut self, chunk: u32, offset: usize) -> T {
+        let node = &mut self.chunks[chunk as usize];
+        let value = node.items[offset];
+        let len = node.len;
+        node.items.copy_within(offset + 1..len, offset);
+        node.len -= 1;
+        self.num_elements -= 1;
+        if node.len == 0 {
+            self.release(chunk);
+        } else {
+            self.absorb_next(chunk);
+        }
+        value
+    }
+
+    /// Moves the upper half of a full node into a new node right after it
+    /// and returns the new node.
+    fn split(&mut self, chunk: u32) -> u32 {
+        let node = &self.chunks[chunk as usize];
+        let (items, len, next) = (node.items, node.len, node.next);
+        let upper = self.alloc(chunk, next, items[N / 2]);
+        let upper_node = &mut self.chunks[upper as usize];
+        upper_node.items[..len - N / 2].copy_from_slice(&items[N / 2..len]);
+        upper_node.len = len - N / 2;
+        self.chunks[chunk as usize].len = N / 2;
+        upper
+    }
+
+    /// Merges the successor of `chunk` into it if `chunk` is less than half
+    /// full and both fit into one node.
+    fn absorb_next(&mut self, chunk: u32) {
+        if self.chunks[chunk as usize].len < N / 2 {
+            self.merge_next(chunk);
+        }
+    }
+
+    /// Merges the successor of `chunk` into it if both fit into one node.
+    /// Returns `true` if it merged.
+    fn merge_next(&mut self, chunk: u32) -> bool {
+        let node = &self.chunks[chunk as usize];
+        let (len, next) = (node.len, node.next);
+        if next == NIL || len + self.chunks[next as usize].len > N {
+            return false;
+        }
+        let next_node = &self.chunks[next as usize];
+        let (items, next_len) = (next_node.items, next_node.len);
+        let node = &mut self.chunks[chunk as usize];
+        node.items[len..len + next_len].copy_from_slice(&items[..next_len]);
+        node.len += next_len;
+        self.release(next);
+        true
+    }
+
+    /// Stores a node holding just `first` in a free slot and links it
+    /// between `prev` and `next`, either of which may be `NIL`.
+    fn alloc(&mut self, prev: u32, next: u32, first: T) -> u32 {
+        let chunk = Chunk {
+            items: [first; N],
+            len: 1,
+            next,
+            prev,
+        };
+        let index = match self.free {
+            NIL => {
+                let index = u32::try_from(self.chunks.len())
+                    .ok()
+                    .filter(|&index| index != NIL)
+                    .expect("UnrolledList is full");
+                self.chunks.push(chunk);
+                index
+            }
+            index => {
+                self.free = self.chunks[index as usize].next;
+                self.chunks[index as usize] = chunk;
+                index
+            }
+        };
+
+        match prev {
+            NIL => self.head = index,
+            prev => self.chunks[prev as usize].next = index,
+        }
+        match next {
+            NIL => self.tail = index,
+            next => self.chunks[next as usize].prev = index,
+        }
+        index
+    }
+
+    /// Unlinks the node in slot `chunk` and puts the slot on the free list.
+    fn release(&mut self, chunk: u32) {
+        let node = &mut self.chunks[chunk as usize];
+        let (prev, next) = (node.prev, node.next);
+        node.len = 0;
+        node.next = self.free;
+        node.prev = NIL;
+        self.free = chunk;
+
+        match prev {
+            NIL => self.head = next,
+            prev => self.chunks[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.chunks[next as usize].prev = prev,
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> Default for UnrolledList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Display, const N: usize> Display for UnrolledList<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut iter = self.iter();
+        if let Some(first) = iter.next() {
+            write!(f, "{}", first)?;
+            for next in iter {
+                write!(f, " -> {}", next)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An immutable iterator over the elements of an `UnrolledList`.
+pub struct UnrolledIter<'a, T, const N: usize> {
+    chunks: &'a [Chunk<T, N>],
+    current: u32,
+    offset: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for UnrolledIter<'a, T, N> {
+    type Item = &'a T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == NIL {
+            return None;
+        }
+        let chunk = &self.chunks[self.current as usize];
+        let item = &chunk.items[self.offset];
+        self.offset += 1;
+        if self.offset == chunk.len {
+            self.current = chunk.next;
+            self.offset = 0;
+        }
+        Some(item)
+    }
+}
+
+/// A mutable iterator over the elements of an `UnrolledList`.
+pub struct UnrolledIterMut<'a, T, const N: usize> {
+    chunks: std::vec::IntoIter<&'a mut [T]>,
+    items: std::slice::IterMut<'a, T>,
+}
+
+impl<'a, T, const N: usize> Iterator for UnrolledIterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    /// Returns the next element of the list.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(item);
+            }
+            self.items = self.chunks.next()?.iter_mut();
+        }
+    }
+}
+
+/// A cursor over an `UnrolledList` that can edit the list around it.
+pub struct UnrolledCursor<'a, T, const N: usize> {
+    list: &'a mut UnrolledList<T, N>,
+    chunk: u32, // `NIL` before the first element
+    offset: usize,
+}
+
+impl<T: Copy, const N: usize> UnrolledCursor<'_, T, N> {
+    /// Moves to the next element. Returns `false` (and stays put) at the end
+    /// of the list.
+    pub fn move_next(&mut self) -> bool {
+        let (chunk, offset) = match self.chunk {
+            NIL => (self.list.head, 0),
+            chunk if self.offset + 1 < self.list.chunks[chunk as usize].len => {
+                (chunk, self.offset + 1)
+            }
+            chunk => (self.list.chunks[chunk as usize].next, 0),
+        };
+        if chunk == NIL {
+            return false;
+        }
+        self.chunk = chunk;
+        self.offset = offset;
+        true
+    }
+
+    /// Moves to the previous element. Returns `false` (and stays put) at the
+    /// first element or before it.
+    pub fn move_prev(&mut self) -> bool {
+        if self.chunk == NIL {
+            return false;
+        }
+        if self.offset > 0 {
+            self.offset -= 1;
+            return true;
+        }
+        match self.list.chunks[self.chunk as usize].prev {
+            NIL => false,
+            prev => {
+                self.chunk = prev;
+                self.offset = self.list.chunks[prev as usize].len - 1;
+                true
+            }
+        }
+    }
+
+    /// Returns the current element, or `None` before the first element.
+    pub fn current(&self) -> Option<&T> {
+        match self.chunk {
+            NIL => None,
+            chunk => Some(&self.list.chunks[chunk as usize].items[self.offset]),
+        }
+    }
+
+    /// Returns the current element mutably, or `None` before the first
+    /// element.
+    pub fn current_mut(&mut self) -> Option<&mut T> {
+        match self.chunk {
+            NIL => None,
+            chunk => Some(&mut self.list.chunks[chunk as usize].items[self.offset]),
+        }
+    }
+
+    /// Inserts `value` right after the cursor.
+    pub fn insert_after(&mut self, value: T) {
+        if self.chunk == NIL {
+            self.list.push_front(value);
+            return;
+        }
+        // A split may move the current element into the new node, but it
+        // always stays right in front of the inserted one.
+        let (chunk, offset) = self.list.insert_in(self.chunk, self.offset + 1, value);
+        self.chunk = chunk;
+        self.offset = offset - 1;
+    }
+
+    /// Removes and returns the element right after the cursor, if any.
+    pub fn remove_next(&mut self) -> Option<T> {
+        if self.chunk == NIL {
+            return self.list.pop_front();
+        }
+        let chunk = &self.list.chunks[self.chunk as usize];
+        if self.offset + 1 < chunk.len {
+            Some(self.list.remove_in(self.chunk, self.offset + 1))
+        } else {
+            match chunk.next {
+                NIL => None,
+                next => Some(self.list.remove_in(next, 0)),
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use dll::ArenaList;
+
+#[test]
+fn create_empty_list() {
+    let list: ArenaList<u32> = ArenaList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.capacity(), 0);
+}
+
+#[test]
+fn push_and_pop_front_and_back() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    list.push_front(1);
+    list.push_back(2);
+    list.push_front(3);
+    list.push_back(4);
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.pop_front(), Some(3));
+    assert_eq!(list.pop_back(), Some(4));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn reuses_vacated_slots() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    for i in 0..8 {
+        list.push_back(i);
+    }
+    for i in 8..1000 {
+        list.pop_front();
+        list.push_back(i);
+    }
+    assert_eq!(list.capacity(), 8);
+    assert_eq!(
+        list.to_string(),
+        "992 -> 993 -> 994 -> 995 -> 996 -> 997 -> 998 -> 999"
+    );
+}
+
+#[test]
+fn insert_and_remove_by_index() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    list.insert(0, 2);
+    list.insert(0, 0);
+    list.insert(1, 1);
+    list.insert(3, 4);
+    list.insert(3, 3);
+    assert_eq!(list.to_string(), "0 -> 1 -> 2 -> 3 -> 4");
+    assert_eq!(list.get(3), Some(&3));
+
+    assert_eq!(list.remove(4), Some(4));
+    assert_eq!(list.remove(0), Some(0));
+    assert_eq!(list.remove(1), Some(2));
+    assert_eq!(list.remove(2), None);
+    assert_eq!(list.to_string(), "1 -> 3");
+    assert_eq!(list.pop_back(), Some(3));
+    assert_eq!(list.pop_back(), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "insertion index out of bounds")]
+fn insert_past_the_end_panics() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    list.insert(1, 0);
+}
+
+#[test]
+fn retains_odd_values() {
+    let mut list: ArenaList<u64> = ArenaList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 2 != 0);
+    assert_eq!(list.len(), 500);
+    for _ in 0..list.len() {
+        assert!(!list.pop_front().unwrap().is_multiple_of(2));
+    }
+    assert_eq!(list.len(), 0);
+}
+
+#[test]
+fn sort_is_stable() {
+    let mut list: ArenaList<(u32, char)> = ArenaList::new();
+    for pair in [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')] {
+        list.push_back(pair);
+    }
+    list.sort(|a, b| a.0.cmp(&b.0));
+    let sorted: Vec<_> = list.iter().copied().collect();
+    assert_eq!(sorted, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    assert_eq!(list.pop_back(), Some((2, 'c')));
+    assert_eq!(list.pop_front(), Some((0, 'e')));
+}
+
+#[test]
+fn compact_preserves_order_and_links() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    for i in 0..100 {
+        if i % 2 == 0 {
+            list.push_back(i);
+        } else {
+            list.push_front(i);
+        }
+    }
+    list.retain(|x| x % 3 != 0);
+    let before: Vec<u32> = list.iter().copied().collect();
+
+    list.compact();
+    assert_eq!(list.capacity(), list.len());
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), before);
+
+    // Links still work in both directions after compaction.
+    assert_eq!(list.pop_back(), before.last().copied());
+    assert_eq!(list.pop_front(), before.first().copied());
+    list.push_back(1000);
+    list.insert(1, 2000);
+    assert_eq!(list.get(1), Some(&2000));
+    assert_eq!(list.get(list.len() - 1), Some(&1000));
+}
+
+#[test]
+fn mutable_iterator() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    for i in 0..5 {
+        list.push_front(i);
+    }
+    for element in list.iter_mut() {
+        *element *= 10;
+    }
+    assert_eq!(list.to_string(), "40 -> 30 -> 20 -> 10 -> 0");
+    *list.get_mut(2).unwrap() = 7;
+    assert_eq!(list.get(2), Some(&7));
+
+    // Slots out of link order, with a free one in between.
+    list.pop_back();
+    list.pop_front();
+    list.push_front(1);
+    for (i, element) in list.iter_mut().enumerate() {
+        *element = i as u32;
+    }
+    assert_eq!(list.to_string(), "0 -> 1 -> 2 -> 3");
+}
+
+#[test]
+fn clear() {
+    let mut list: ArenaList<u32> = ArenaList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.clear();
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.capacity(), 0);
+    assert_eq!(list.pop_front(), None);
+}
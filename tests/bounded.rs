@@ -0,0 +1,185 @@
+use dll::{BoundedList, OverflowPolicy, Weigh};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A log line whose weight is its length plus a fixed header.
+#[derive(Clone, Debug, PartialEq)]
+struct Line(&'static str);
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Weigh for Line {
+    fn weight(&self) -> usize {
+        self.0.len() + 2
+    }
+}
+
+#[test]
+fn reject_hands_the_value_back() {
+    let mut list = BoundedList::new(2, OverflowPolicy::Reject);
+    assert_eq!(list.push_back(1), Ok(vec![]));
+    assert_eq!(list.push_front(0), Ok(vec![]));
+    assert_eq!(list.push_back(2), Err(2));
+    assert_eq!(list.push_front(-1), Err(-1));
+    assert_eq!(list.to_string(), "0 -> 1");
+    assert_eq!(list.pop_back(), Some(1));
+    assert_eq!(list.push_back(2), Ok(vec![]));
+    assert_eq!(list.to_string(), "0 -> 2");
+}
+
+#[test]
+fn evict_front_keeps_the_tail() {
+    let mut list = BoundedList::new(3, OverflowPolicy::EvictFront);
+    let mut evicted = Vec::new();
+    for i in 0..10 {
+        evicted.extend(list.push_back(i).unwrap());
+    }
+    assert_eq!(evicted, (0..7).collect::<Vec<_>>());
+    assert_eq!(list.to_string(), "7 -> 8 -> 9");
+    assert_eq!(list.len(), list.capacity());
+}
+
+#[test]
+fn evict_back_keeps_the_head() {
+    let mut list = BoundedList::new(3, OverflowPolicy::EvictBack);
+    for i in 0..5 {
+        list.push_front(i).unwrap();
+    }
+    assert_eq!(list.to_string(), "4 -> 3 -> 2");
+    assert_eq!(list.push_front(5), Ok(vec![2]));
+}
+
+#[test]
+fn weight_limit_evicts_until_the_value_fits() {
+    let mut list = BoundedList::new(100, OverflowPolicy::EvictFront);
+    list.set_weight_limit(20);
+    list.push_back(Line("alpha")).unwrap(); // 7
+    list.push_back(Line("beta")).unwrap(); // 6
+    list.push_back(Line("gamma")).unwrap(); // 7
+    assert_eq!(list.weight(), 20);
+    assert_eq!(
+        list.push_back(Line("epsilon")),
+        Ok(vec![Line("alpha"), Line("beta")])
+    );
+    assert_eq!(list.weight(), 16);
+    assert_eq!(list.pop_front(), Some(Line("gamma")));
+    assert_eq!(list.weight(), 9);
+
+    // Too heavy to ever fit: refused even though the policy evicts.
+    let huge = Line("this line is far too long");
+    assert_eq!(list.push_back(huge.clone()), Err(huge));
+    assert_eq!(list.len(), 1);
+}
+
+#[test]
+fn weight_limit_counts_existing_elements() {
+    let mut list = BoundedList::new(10, OverflowPolicy::Reject);
+    list.push_back(String::from("abc")).unwrap();
+    list.push_back(String::from("de")).unwrap();
+    assert_eq!(list.weight(), 0);
+    list.set_weight_limit(6);
+    assert_eq!(list.weight(), 5);
+    assert_eq!(list.push_back(String::from("f")), Ok(vec![]));
+    assert_eq!(list.push_back(String::from("g")), Err(String::from("g")));
+}
+
+#[test]
+fn eviction_callback_receives_evicted_values() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&seen);
+    let mut list = BoundedList::new(2, OverflowPolicy::EvictFront);
+    list.set_eviction_callback(move |value| log.borrow_mut().push(value));
+    for i in 0..5 {
+        assert_eq!(list.push_back(i), Ok(vec![]));
+    }
+    assert_eq!(*seen.borrow(), [0, 1, 2]);
+    // Popping isn't evicting.
+    list.pop_front();
+    assert_eq!(seen.borrow().len(), 3);
+}
+
+#[test]
+fn block_callback_makes_room() {
+    let flushed = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&flushed);
+    let mut list = BoundedList::new(
+        4,
+        OverflowPolicy::Block(Box::new(move |list: &mut BoundedList<u32>| {
+            // Flush half of the buffer somewhere else.
+            for _ in 0..2 {
+                sink.borrow_mut().extend(list.pop_front());
+            }
+            true
+        })),
+    );
+    for i in 0..10 {
+        assert_eq!(list.push_back(i), Ok(vec![]));
+    }
+    assert_eq!(*flushed.borrow(), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(list.to_string(), "6 -> 7 -> 8 -> 9");
+}
+
+#[test]
+fn block_callback_can_refuse() {
+    let calls = Rc::new(RefCell::new(0));
+    let counter = Rc::clone(&calls);
+    let mut list = BoundedList::new(
+        1,
+        OverflowPolicy::Block(Box::new(move |_: &mut BoundedList<u32>| {
+            *counter.borrow_mut() += 1;
+            false
+        })),
+    );
+    list.push_back(1).unwrap();
+    assert_eq!(list.push_back(2), Err(2));
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(list.to_string(), "1");
+}
+
+#[test]
+fn block_callback_that_makes_no_room_is_refused() {
+    let calls = Rc::new(RefCell::new(0));
+    let counter = Rc::clone(&calls);
+    let mut list = BoundedList::new(
+        1,
+        OverflowPolicy::Block(Box::new(move |_: &mut BoundedList<u32>| {
+            *counter.borrow_mut() += 1;
+            true
+        })),
+    );
+    list.push_back(1).unwrap();
+    assert_eq!(list.push_back(2), Err(2));
+    assert_eq!(*calls.borrow(), 1);
+    assert_eq!(list.to_string(), "1");
+}
+
+#[test]
+fn block_callback_can_replace_the_policy() {
+    let mut list = BoundedList::new(
+        2,
+        OverflowPolicy::Block(Box::new(|list: &mut BoundedList<u32>| {
+            list.set_policy(OverflowPolicy::EvictBack);
+            list.pop_front();
+            true
+        })),
+    );
+    list.push_back(1).unwrap();
+    list.push_back(2).unwrap();
+    assert_eq!(list.push_back(3), Ok(vec![]));
+    assert_eq!(list.to_string(), "2 -> 3");
+    // The callback's policy stays in place.
+    assert_eq!(list.push_back(4), Ok(vec![3]));
+    assert_eq!(list.to_string(), "2 -> 4");
+}
+
+#[test]
+fn zero_capacity_refuses_everything() {
+    let mut list = BoundedList::new(0, OverflowPolicy::EvictFront);
+    assert_eq!(list.push_back(1), Err(1));
+    assert!(list.is_empty());
+}
@@ -0,0 +1,182 @@
+use dll::CircularList;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Solves the Josephus problem: `n` people stand in a circle and every
+/// `k`th one is eliminated. Returns the elimination order.
+fn josephus(n: u32, k: usize) -> Vec<u32> {
+    let mut circle: CircularList<u32> = CircularList::new();
+    for i in 1..=n {
+        circle.push_back(i);
+    }
+    let mut order = Vec::new();
+    while !circle.is_empty() {
+        circle.advance(k - 1);
+        order.push(circle.remove_here().unwrap());
+    }
+    order
+}
+
+#[derive(Clone)]
+struct Counted(Rc<()>);
+
+impl std::fmt::Display for Counted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Rc::strong_count(&self.0))
+    }
+}
+
+#[test]
+fn create_empty_list() {
+    let mut list: CircularList<u32> = CircularList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.current(), None);
+    assert_eq!(list.remove_here(), None);
+    list.advance(3);
+    list.retreat(3);
+    assert_eq!(list.cycle().next(), None);
+    assert_eq!(list.to_string(), "");
+}
+
+#[test]
+fn single_element_ring() {
+    let mut list: CircularList<u32> = CircularList::new();
+    list.insert_here(7);
+    list.advance(1);
+    list.retreat(2);
+    assert_eq!(list.current(), Some(7));
+    assert_eq!(list.cycle().take(3).collect::<Vec<_>>(), [7, 7, 7]);
+    assert_eq!(list.remove_here(), Some(7));
+    assert!(list.is_empty());
+}
+
+#[test]
+fn josephus_elimination_order() {
+    assert_eq!(josephus(7, 3), [3, 6, 2, 7, 5, 1, 4]);
+    assert_eq!(josephus(41, 3).last(), Some(&31));
+    assert_eq!(josephus(5, 1), [1, 2, 3, 4, 5]);
+}
+
+#[derive(Clone)]
+struct Task {
+    name: char,
+    ticks: u32,
+}
+
+impl std::fmt::Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.ticks)
+    }
+}
+
+#[test]
+fn round_robin_scheduling() {
+    let mut tasks: CircularList<Task> = CircularList::new();
+    for (name, ticks) in [('a', 2), ('b', 1), ('c', 3)] {
+        tasks.push_back(Task { name, ticks });
+    }
+    assert_eq!(tasks.to_string(), "a:2 -> b:1 -> c:3");
+
+    // Each turn runs the current task for one tick, then moves on; a task
+    // that has no ticks left is dropped from the rotation.
+    let mut schedule = String::new();
+    while let Some(task) = tasks.remove_here() {
+        schedule.push(task.name);
+        if task.ticks > 1 {
+            tasks.insert_here(Task {
+                ticks: task.ticks - 1,
+                ..task
+            });
+            tasks.advance(1);
+        }
+    }
+    assert_eq!(schedule, "abcacc");
+}
+
+#[test]
+fn rotation_matches_vec_deque() {
+    let mut list: CircularList<u32> = CircularList::new();
+    let mut model: VecDeque<u32> = VecDeque::new();
+    for i in 0..10 {
+        list.push_back(i);
+        model.push_back(i);
+    }
+    for step in 0..200usize {
+        match step % 5 {
+            0 | 1 => {
+                let k = step * 7 % 23;
+                list.advance(k);
+                model.rotate_left(k % model.len());
+            }
+            2 => {
+                let k = step * 3 % 17;
+                list.retreat(k);
+                model.rotate_right(k % model.len());
+            }
+            3 => {
+                list.insert_here(100 + step as u32);
+                model.push_front(100 + step as u32);
+            }
+            _ => assert_eq!(list.remove_here(), model.pop_front()),
+        }
+        assert_eq!(list.len(), model.len());
+        assert_eq!(list.iter().collect::<Vec<_>>(), Vec::from(model.clone()));
+        assert_eq!(list.current(), model.front().copied());
+    }
+}
+
+#[test]
+fn cycle_wraps_around() {
+    let mut list: CircularList<u32> = CircularList::new();
+    for i in 1..=3 {
+        list.push_back(i);
+    }
+    list.retreat(1);
+    assert_eq!(
+        list.cycle().take(7).collect::<Vec<_>>(),
+        [3, 1, 2, 3, 1, 2, 3]
+    );
+    assert_eq!(list.to_string(), "3 -> 1 -> 2");
+}
+
+#[test]
+fn dropping_frees_every_node() {
+    let counter = Rc::new(());
+    {
+        let mut list: CircularList<Counted> = CircularList::new();
+        for _ in 0..50 {
+            list.push_back(Counted(Rc::clone(&counter)));
+        }
+        list.advance(17);
+        list.retreat(30);
+        list.insert_here(Counted(Rc::clone(&counter)));
+        list.remove_here();
+        list.advance(1);
+        assert_eq!(Rc::strong_count(&counter), 51);
+        let _ = list.cycle().take(120).count();
+    }
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn drops_long_list() {
+    let mut list: CircularList<u32> = CircularList::new();
+    for i in 0..200_000 {
+        list.push_back(i);
+    }
+    list.advance(100_000);
+    drop(list);
+}
+
+#[test]
+fn clear() {
+    let counter = Rc::new(());
+    let mut list: CircularList<Counted> = CircularList::new();
+    for _ in 0..10 {
+        list.push_back(Counted(Rc::clone(&counter)));
+    }
+    list.clear();
+    assert!(list.is_empty());
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
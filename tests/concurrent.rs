@@ -0,0 +1,396 @@
+mod common;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+
+use common::xorshift;
+use dll::concurrent::ConcurrentDeque;
+
+const THREADS: usize = 8;
+const OPS_PER_THREAD: usize = 2_000;
+
+#[test]
+fn create_empty_deque() {
+    let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    assert_eq!(deque.len(), 0);
+    assert!(deque.is_empty());
+    assert_eq!(deque.pop_front(), None);
+    assert_eq!(deque.pop_back(), None);
+}
+
+#[test]
+fn push_and_pop_front() {
+    let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    deque.push_front(3);
+    deque.push_front(1);
+    deque.push_front(2);
+    assert_eq!(deque.len(), 3);
+    assert_eq!(deque.pop_front(), Some(2));
+    assert_eq!(deque.pop_front(), Some(1));
+    assert_eq!(deque.pop_front(), Some(3));
+    assert_eq!(deque.len(), 0);
+    assert_eq!(deque.pop_front(), None);
+}
+
+#[test]
+fn push_and_pop_back() {
+    let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    deque.push_back(1);
+    deque.push_back(2);
+    deque.push_back(3);
+    assert_eq!(deque.len(), 3);
+    assert_eq!(deque.pop_back(), Some(3));
+    assert_eq!(deque.pop_back(), Some(2));
+    assert_eq!(deque.pop_back(), Some(1));
+    assert_eq!(deque.len(), 0);
+    assert_eq!(deque.pop_back(), None);
+}
+
+#[test]
+fn push_and_pop_front_and_back() {
+    let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    deque.push_front(1);
+    deque.push_back(2);
+    deque.push_front(3);
+    deque.push_back(4);
+    assert_eq!(deque.len(), 4);
+    assert_eq!(deque.pop_front(), Some(3));
+    assert_eq!(deque.pop_back(), Some(4));
+    assert_eq!(deque.pop_front(), Some(1));
+    assert_eq!(deque.pop_back(), Some(2));
+    assert_eq!(deque.len(), 0);
+    assert_eq!(deque.pop_front(), None);
+    assert_eq!(deque.pop_back(), None);
+}
+
+#[test]
+fn iterable() {
+    let deque: ConcurrentDeque<u32> = ConcurrentDeque::new();
+    deque.push_front(1);
+    deque.push_front(2);
+    deque.push_back(0);
+    let mut iter = deque.iter();
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), None);
+    assert_eq!(deque.to_string(), "2 -> 1 -> 0");
+}
+
+#[test]
+fn drops_remaining_elements() {
+    let value = Arc::new(());
+    {
+        let deque = ConcurrentDeque::new();
+        for _ in 0..100 {
+            deque.push_back(Wrapper(Arc::clone(&value)));
+            deque.push_front(Wrapper(Arc::clone(&value)));
+        }
+        for _ in 0..50 {
+            deque.pop_front();
+            deque.pop_back();
+        }
+    }
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[derive(Clone)]
+struct Wrapper(Arc<()>);
+
+impl std::fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Arc::strong_count(&self.0))
+    }
+}
+
+#[test]
+fn concurrent_pushes_and_pops_lose_nothing() {
+    let deque = Arc::new(ConcurrentDeque::new());
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let deque = Arc::clone(&deque);
+            thread::spawn(move || {
+                let mut popped = Vec::new();
+                for i in 0..OPS_PER_THREAD {
+                    let value = t * OPS_PER_THREAD + i;
+                    match i % 4 {
+                        0 => deque.push_front(value),
+                        1 => deque.push_back(value),
+                        2 => {
+                            deque.push_back(value);
+                            popped.extend(deque.pop_front());
+                        }
+                        _ => {
+                            deque.push_front(value);
+                            popped.extend(deque.pop_back());
+                        }
+                    }
+                }
+                popped
+            })
+        })
+        .collect();
+
+    let mut all: Vec<usize> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    let remaining: Vec<usize> = deque.iter().collect();
+    assert_eq!(remaining.len(), deque.len());
+    all.extend(remaining);
+    all.sort();
+    assert_eq!(all, (0..THREADS * OPS_PER_THREAD).collect::<Vec<_>>());
+}
+
+#[test]
+fn concurrent_drain_from_both_ends() {
+    let deque = Arc::new(ConcurrentDeque::new());
+    for i in 0..THREADS * OPS_PER_THREAD {
+        deque.push_back(i);
+    }
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let deque = Arc::clone(&deque);
+            thread::spawn(move || {
+                let mut popped = Vec::new();
+                loop {
+                    let value = if t % 2 == 0 {
+                        deque.pop_front()
+                    } else {
+                        deque.pop_back()
+                    };
+                    match value {
+                        Some(value) => popped.push(value),
+                        None => return popped,
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let mut all: Vec<usize> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect();
+    assert!(deque.is_empty());
+    all.sort();
+    assert_eq!(all, (0..THREADS * OPS_PER_THREAD).collect::<Vec<_>>());
+}
+
+#[test]
+fn snapshots_are_contiguous_under_contention() {
+    // Writers only ever push increasing values to the back and pop from the
+    // front, so every consistent snapshot is a run of consecutive integers.
+    let deque = Arc::new(ConcurrentDeque::new());
+    let next = Arc::new(Mutex::new(0u64));
+    let writers: Vec<_> = (0..2)
+        .map(|_| {
+            let deque = Arc::clone(&deque);
+            let next = Arc::clone(&next);
+            thread::spawn(move || {
+                for _ in 0..OPS_PER_THREAD {
+                    {
+                        let mut next = next.lock().unwrap();
+                        deque.push_back(*next);
+                        *next += 1;
+                    }
+                    if deque.len() > 8 {
+                        deque.pop_front();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for _ in 0..200 {
+        let snapshot: Vec<u64> = deque.iter().collect();
+        for pair in snapshot.windows(2) {
+            assert_eq!(pair[0] + 1, pair[1], "inconsistent snapshot {:?}", snapshot);
+        }
+    }
+    for writer in writers {
+        writer.join().unwrap();
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////
+// Linearizability checking
+/////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    PushFront(u32),
+    PushBack(u32),
+    PopFront,
+    PopBack,
+    Len,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Ret {
+    Unit,
+    Value(Option<u32>),
+    Len(usize),
+}
+
+/// One completed operation together with the logical times at which it was
+/// invoked and at which it returned.
+#[derive(Clone, Copy, Debug)]
+struct Event {
+    op: Op,
+    ret: Ret,
+    invoke: u64,
+    respond: u64,
+}
+
+/// Applies `op` to the sequential model, returning what it would return.
+fn apply(model: &mut VecDeque<u32>, op: Op) -> Ret {
+    match op {
+        Op::PushFront(v) => {
+            model.push_front(v);
+            Ret::Unit
+        }
+        Op::PushBack(v) => {
+            model.push_back(v);
+            Ret::Unit
+        }
+        Op::PopFront => Ret::Value(model.pop_front()),
+        Op::PopBack => Ret::Value(model.pop_back()),
+        Op::Len => Ret::Len(model.len()),
+    }
+}
+
+/// Searches for a sequential order of `history` that respects real time (an
+/// operation that returned before another was invoked comes first) and in
+/// which every result matches the sequential deque (Wing & Gong's algorithm,
+/// with memoization of visited states).
+fn is_linearizable(history: &[Event], initial: VecDeque<u32>) -> bool {
+    fn search(
+        history: &[Event],
+        done: u64,
+        model: &VecDeque<u32>,
+        seen: &mut HashSet<(u64, VecDeque<u32>)>,
+    ) -> bool {
+        if done.count_ones() as usize == history.len() {
+            return true;
+        }
+        if !seen.insert((done, model.clone())) {
+            return false;
+        }
+        let pending = || (0..history.len()).filter(move |i| done & (1 << i) == 0);
+        let first_response = pending().map(|i| history[i].respond).min().unwrap();
+        for i in pending().filter(|&i| history[i].invoke < first_response) {
+            let mut next = model.clone();
+            if apply(&mut next, history[i].op) == history[i].ret
+                && search(history, done | (1 << i), &next, seen)
+            {
+                return true;
+            }
+        }
+        false
+    }
+    search(history, 0, &initial, &mut HashSet::new())
+}
+
+fn run(deque: &ConcurrentDeque<u32>, op: Op) -> Ret {
+    match op {
+        Op::PushFront(v) => {
+            deque.push_front(v);
+            Ret::Unit
+        }
+        Op::PushBack(v) => {
+            deque.push_back(v);
+            Ret::Unit
+        }
+        Op::PopFront => Ret::Value(deque.pop_front()),
+        Op::PopBack => Ret::Value(deque.pop_back()),
+        Op::Len => Ret::Len(deque.len()),
+    }
+}
+
+#[test]
+fn checker_rejects_impossible_history() {
+    // A pop that returned before a push was even invoked can't see its value.
+    let history = [
+        Event {
+            op: Op::PopFront,
+            ret: Ret::Value(Some(7)),
+            invoke: 0,
+            respond: 1,
+        },
+        Event {
+            op: Op::PushBack(7),
+            ret: Ret::Unit,
+            invoke: 2,
+            respond: 3,
+        },
+    ];
+    assert!(!is_linearizable(&history, VecDeque::new()));
+
+    // ...but overlapping with the push, it can.
+    let mut overlapping = history;
+    overlapping[0].respond = 4;
+    assert!(is_linearizable(&overlapping, VecDeque::new()));
+}
+
+#[test]
+fn histories_are_linearizable() {
+    const ROUNDS: u64 = 300;
+    const WORKERS: usize = 3;
+    const OPS: usize = 5;
+
+    for round in 0..ROUNDS {
+        let deque = Arc::new(ConcurrentDeque::new());
+        let initial: VecDeque<u32> = (0..2).collect();
+        for &v in &initial {
+            deque.push_back(v);
+        }
+        let clock = Arc::new(AtomicU64::new(0));
+        let barrier = Arc::new(Barrier::new(WORKERS));
+        let handles: Vec<_> = (0..WORKERS)
+            .map(|w| {
+                let deque = Arc::clone(&deque);
+                let clock = Arc::clone(&clock);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    let mut rng = round * 31 + w as u64 + 1;
+                    let mut events = Vec::new();
+                    barrier.wait();
+                    for i in 0..OPS {
+                        let value = (100 * (w + 1) + i) as u32;
+                        let op = match xorshift(&mut rng) % 5 {
+                            0 => Op::PushFront(value),
+                            1 => Op::PushBack(value),
+                            2 => Op::PopFront,
+                            3 => Op::PopBack,
+                            _ => Op::Len,
+                        };
+                        let invoke = clock.fetch_add(1, Ordering::SeqCst);
+                        let ret = run(&deque, op);
+                        let respond = clock.fetch_add(1, Ordering::SeqCst);
+                        events.push(Event {
+                            op,
+                            ret,
+                            invoke,
+                            respond,
+                        });
+                    }
+                    events
+                })
+            })
+            .collect();
+
+        let history: Vec<Event> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        assert!(
+            is_linearizable(&history, initial),
+            "round {} produced a non-linearizable history: {:#?}",
+            round,
+            history
+        );
+    }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::xorshift;
+use dll::{Deque, LinkedList, LinkedQueue, LinkedStack, Queue, Stack, SyncLinkedList};
+use std::collections::VecDeque;
+
+/// Runs the same random operations against `deque` and a `VecDeque` model.
+fn check_deque<D: Deque<u64>>(mut deque: D) {
+    let mut model = VecDeque::new();
+    let mut state = 0xdeadbeef;
+    for _ in 0..2000 {
+        let value = xorshift(&mut state);
+        match value % 4 {
+            0 => {
+                Deque::push_front(&mut deque, value);
+                model.push_front(value);
+            }
+            1 => {
+                Deque::push_back(&mut deque, value);
+                model.push_back(value);
+            }
+            2 => assert_eq!(Deque::pop_front(&mut deque), model.pop_front()),
+            _ => assert_eq!(Deque::pop_back(&mut deque), model.pop_back()),
+        }
+        assert_eq!(deque.peek_front(), model.front().copied());
+        assert_eq!(deque.peek_back(), model.back().copied());
+        assert_eq!(Deque::len(&deque), model.len());
+        assert_eq!(Deque::is_empty(&deque), model.is_empty());
+    }
+}
+
+/// Pushes 0..10, pops three and checks they come out last-in, first-out.
+fn check_stack<S: Stack<u32>>(mut stack: S) {
+    assert!(stack.is_empty());
+    assert_eq!(stack.peek(), None);
+    for i in 0..10 {
+        stack.push(i);
+    }
+    assert_eq!(stack.peek(), Some(9));
+    assert_eq!(stack.pop(), Some(9));
+    assert_eq!(stack.pop(), Some(8));
+    assert_eq!(stack.pop(), Some(7));
+    assert_eq!(stack.len(), 7);
+    while stack.pop().is_some() {}
+    assert!(stack.is_empty());
+}
+
+/// Enqueues 0..10, dequeues three and checks they come out first-in,
+/// first-out.
+fn check_queue<Q: Queue<u32>>(mut queue: Q) {
+    assert!(queue.is_empty());
+    assert_eq!(queue.front(), None);
+    for i in 0..10 {
+        queue.enqueue(i);
+    }
+    assert_eq!(queue.front(), Some(0));
+    assert_eq!(queue.dequeue(), Some(0));
+    assert_eq!(queue.dequeue(), Some(1));
+    assert_eq!(queue.dequeue(), Some(2));
+    assert_eq!(queue.len(), 7);
+    while queue.dequeue().is_some() {}
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn every_container_is_a_deque() {
+    check_deque(LinkedList::new());
+    check_deque(SyncLinkedList::new());
+    check_deque(VecDeque::new());
+    check_deque(Vec::new());
+}
+
+#[test]
+fn every_container_is_a_stack() {
+    check_stack(LinkedList::new());
+    check_stack(SyncLinkedList::new());
+    check_stack(VecDeque::new());
+    check_stack(Vec::new());
+    check_stack(LinkedStack::new());
+}
+
+#[test]
+fn every_container_is_a_queue() {
+    check_queue(LinkedList::new());
+    check_queue(SyncLinkedList::new());
+    check_queue(VecDeque::new());
+    check_queue(Vec::new());
+    check_queue(LinkedQueue::new());
+}
+
+#[test]
+fn linked_list_stack_and_queue_agree_with_its_ends() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.push_back(2);
+    assert_eq!(Stack::peek(&list), Some(2));
+    assert_eq!(Queue::front(&list), Some(1));
+    Stack::push(&mut list, 3);
+    Queue::enqueue(&mut list, 4);
+    assert_eq!(list.to_string(), "1 -> 2 -> 3 -> 4");
+}
+
+#[test]
+fn wrappers_display_and_iterate() {
+    let mut stack = LinkedStack::new();
+    let mut queue = LinkedQueue::new();
+    for i in 1..=3 {
+        stack.push(i);
+        queue.enqueue(i);
+    }
+    assert_eq!(stack.to_string(), "1 -> 2 -> 3");
+    assert_eq!(queue.to_string(), "1 -> 2 -> 3");
+    assert_eq!(stack.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(queue.iter().collect::<Vec<_>>(), [1, 2, 3]);
+    stack.clear();
+    queue.clear();
+    assert!(stack.is_empty());
+    assert!(queue.is_empty());
+}
@@ -0,0 +1,233 @@
+use dll::{Command, History};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Inserts a string into a text buffer.
+struct Insert {
+    at: usize,
+    text: &'static str,
+}
+
+impl Command<String> for Insert {
+    fn apply(&mut self, buffer: &mut String) {
+        buffer.insert_str(self.at, self.text);
+    }
+
+    fn revert(&mut self, buffer: &mut String) {
+        buffer.replace_range(self.at..self.at + self.text.len(), "");
+    }
+}
+
+/// Deletes a range of a text buffer, remembering what it deleted.
+struct Delete {
+    at: usize,
+    len: usize,
+    deleted: String,
+}
+
+impl Command<String> for Delete {
+    fn apply(&mut self, buffer: &mut String) {
+        self.deleted = buffer.drain(self.at..self.at + self.len).collect();
+    }
+
+    fn revert(&mut self, buffer: &mut String) {
+        buffer.insert_str(self.at, &self.deleted);
+    }
+}
+
+fn insert(at: usize, text: &'static str) -> Insert {
+    Insert { at, text }
+}
+
+fn delete(at: usize, len: usize) -> Delete {
+    Delete {
+        at,
+        len,
+        deleted: String::new(),
+    }
+}
+
+#[test]
+fn undo_and_redo() {
+    let mut history = History::new(String::new(), 10);
+    assert!(!history.undo());
+    assert!(!history.redo());
+
+    history.execute("hello", insert(0, "hello"));
+    history.execute("world", insert(5, " world"));
+    history.execute("delete", delete(0, 6));
+    assert_eq!(history.state(), "world");
+    assert_eq!(history.to_string(), "hello -> world -> delete*");
+
+    assert!(history.undo());
+    assert_eq!(history.state(), "hello world");
+    assert!(history.undo());
+    assert!(history.undo());
+    assert_eq!(history.state(), "");
+    assert!(!history.undo());
+    assert_eq!(history.to_string(), "hello -> world -> delete");
+    assert_eq!(history.redo_label(), Some(String::from("hello")));
+
+    assert!(history.redo());
+    assert!(history.redo());
+    assert_eq!(history.undo_label(), Some(String::from("world")));
+    assert!(history.redo());
+    assert!(!history.redo());
+    assert_eq!(history.state(), "world");
+    assert_eq!(history.len(), 3);
+}
+
+#[test]
+fn new_command_truncates_redo_tail() {
+    let mut history = History::new(String::new(), 10);
+    history.execute("a", insert(0, "a"));
+    history.execute("b", insert(1, "b"));
+    history.execute("c", insert(2, "c"));
+    history.undo();
+    history.undo();
+    history.execute("x", insert(1, "x"));
+    assert_eq!(history.state(), "ax");
+    assert_eq!(history.to_string(), "a -> x*");
+    assert!(!history.can_redo());
+
+    // Branching from the very start drops everything.
+    history.undo();
+    history.undo();
+    history.execute("y", insert(0, "y"));
+    assert_eq!(history.to_string(), "y*");
+    history.undo();
+    assert_eq!(history.state(), "");
+}
+
+#[test]
+fn max_depth_evicts_the_oldest_entries() {
+    let mut history = History::new(0, 3);
+    for i in 1..=5 {
+        history.set(format!("{}", i), i);
+    }
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.to_string(), "3 -> 4 -> 5*");
+    while history.undo() {}
+    assert_eq!(*history.state(), 2);
+    history.redo();
+    assert_eq!(*history.state(), 3);
+}
+
+#[test]
+fn zero_depth_keeps_nothing() {
+    let mut history = History::new(0, 0);
+    history.set("one", 1);
+    assert!(history.is_empty());
+    assert!(!history.can_undo());
+    assert_eq!(*history.state(), 1);
+}
+
+#[test]
+fn transactions_group_commands() {
+    let mut history = History::new(String::from("abc"), 10);
+    history.begin("replace");
+    history.execute("delete", delete(0, 3));
+    history.begin("nested");
+    history.execute("insert", insert(0, "xy"));
+    history.commit();
+    assert!(history.in_transaction());
+    history.execute("insert", insert(2, "z"));
+    history.commit();
+    assert!(!history.in_transaction());
+
+    assert_eq!(history.state(), "xyz");
+    assert_eq!(history.to_string(), "replace*");
+    history.undo();
+    assert_eq!(history.state(), "abc");
+    history.redo();
+    assert_eq!(history.state(), "xyz");
+
+    // An empty transaction records nothing.
+    history.begin("nothing");
+    history.commit();
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn rollback_reverts_the_transaction() {
+    let mut history = History::new(String::from("abc"), 10);
+    history.execute("d", insert(3, "d"));
+    history.begin("scratch");
+    history.execute("delete", delete(1, 2));
+    history.execute("insert", insert(0, "!"));
+    assert_eq!(history.state(), "!ad");
+    history.rollback();
+    assert_eq!(history.state(), "abcd");
+    assert_eq!(history.to_string(), "d*");
+}
+
+#[test]
+#[should_panic(expected = "transaction in progress")]
+fn undo_inside_transaction_panics() {
+    let mut history = History::new(0, 10);
+    history.begin("open");
+    history.undo();
+}
+
+#[test]
+#[should_panic(expected = "no transaction in progress")]
+fn commit_without_begin_panics() {
+    let mut history = History::new(0, 10);
+    history.commit();
+}
+
+/// A command that counts how many of its kind are alive.
+struct Tracked(Rc<Cell<usize>>);
+
+impl Tracked {
+    fn new(alive: &Rc<Cell<usize>>) -> Self {
+        alive.set(alive.get() + 1);
+        Tracked(Rc::clone(alive))
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+impl Command<u64> for Tracked {
+    fn apply(&mut self, state: &mut u64) {
+        *state += 1;
+    }
+
+    fn revert(&mut self, state: &mut u64) {
+        *state -= 1;
+    }
+}
+
+#[test]
+fn dropped_entries_free_their_commands() {
+    let alive = Rc::new(Cell::new(0));
+    let mut history = History::new(0, 4);
+    for _ in 0..10 {
+        history.execute("step", Tracked::new(&alive));
+    }
+    assert_eq!(alive.get(), 4);
+    history.undo();
+    history.undo();
+    history.execute("step", Tracked::new(&alive));
+    assert_eq!(alive.get(), 3);
+    history.clear();
+    assert_eq!(alive.get(), 0);
+    assert_eq!(*history.state(), 9);
+}
+
+#[test]
+fn drop_deep_history() {
+    let mut history = History::new(0, usize::MAX);
+    for i in 0..200_000u64 {
+        history.set("step", i);
+    }
+    for _ in 0..1000 {
+        history.undo();
+    }
+    assert_eq!(*history.state(), 198_999);
+    drop(history);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use common::xorshift;
+use dll::{Adapter, IntrusiveList, Links};
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+/// A job that is always in the list of all jobs, and in the run queue while
+/// it is runnable.
+struct Job {
+    id: u32,
+    all: Links<Job>,
+    runnable: Links<Job>,
+}
+
+struct Runnable;
+
+impl Adapter for Job {
+    fn links(&self) -> &Links<Self> {
+        &self.all
+    }
+}
+
+impl Adapter<Runnable> for Job {
+    fn links(&self) -> &Links<Self> {
+        &self.runnable
+    }
+}
+
+impl fmt::Display for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+fn job(id: u32) -> Rc<Job> {
+    Rc::new(Job {
+        id,
+        all: Links::new(),
+        runnable: Links::new(),
+    })
+}
+
+fn ids<Tag>(list: &IntrusiveList<Job, Tag>) -> Vec<u32>
+where
+    Job: Adapter<Tag>,
+{
+    list.iter().map(|job| job.id).collect()
+}
+
+#[test]
+fn push_pop_at_both_ends() {
+    let mut list: IntrusiveList<Job> = IntrusiveList::new();
+    assert!(list.pop_front().is_none());
+    for i in 0..3 {
+        assert!(list.push_back(job(i)).is_ok());
+    }
+    assert!(list.push_front(job(9)).is_ok());
+    assert_eq!(list.to_string(), "9 -> 0 -> 1 -> 2");
+    assert_eq!(list.front().map(|job| job.id), Some(9));
+    assert_eq!(list.back().map(|job| job.id), Some(2));
+    assert_eq!(list.pop_back().map(|job| job.id), Some(2));
+    assert_eq!(list.pop_front().map(|job| job.id), Some(9));
+    assert_eq!(ids(&list), [0, 1]);
+    assert_eq!(list.len(), 2);
+}
+
+#[test]
+fn remove_by_reference() {
+    let jobs: Vec<Rc<Job>> = (0..5).map(job).collect();
+    let mut list: IntrusiveList<Job> = IntrusiveList::new();
+    for job in &jobs {
+        assert!(list.push_back(Rc::clone(job)).is_ok());
+    }
+    let removed = list.remove(&jobs[2]).unwrap();
+    assert!(Rc::ptr_eq(&removed, &jobs[2]));
+    assert!(!jobs[2].all.is_linked());
+    assert!(list.remove(&jobs[2]).is_none());
+    list.remove(&jobs[0]).unwrap();
+    list.remove(&jobs[4]).unwrap();
+    assert_eq!(ids(&list), [1, 3]);
+    assert_eq!(list.front().map(|job| job.id), Some(1));
+    assert_eq!(list.back().map(|job| job.id), Some(3));
+
+    // Only the lists hold extra references.
+    drop(removed);
+    assert_eq!(Rc::strong_count(&jobs[2]), 1);
+    assert_eq!(Rc::strong_count(&jobs[1]), 2);
+}
+
+#[test]
+fn same_links_cannot_be_linked_twice() {
+    let a = job(1);
+    let mut first: IntrusiveList<Job> = IntrusiveList::new();
+    let mut second: IntrusiveList<Job> = IntrusiveList::new();
+    assert!(first.push_back(Rc::clone(&a)).is_ok());
+    assert!(first.push_back(Rc::clone(&a)).is_err());
+    assert!(second.push_front(Rc::clone(&a)).is_err());
+    assert_eq!(first.len(), 1);
+    assert!(second.is_empty());
+
+    // A list can't remove what another list holds.
+    assert!(second.remove(&a).is_none());
+    assert!(first.contains(&a));
+    assert!(!second.contains(&a));
+
+    first.remove(&a);
+    assert!(second.push_back(Rc::clone(&a)).is_ok());
+}
+
+#[test]
+fn membership_in_several_lists() {
+    let jobs: Vec<Rc<Job>> = (0..4).map(job).collect();
+    let mut all: IntrusiveList<Job> = IntrusiveList::new();
+    let mut queue: IntrusiveList<Job, Runnable> = IntrusiveList::new();
+    for job in &jobs {
+        assert!(all.push_back(Rc::clone(job)).is_ok());
+    }
+    for job in jobs.iter().rev() {
+        assert!(queue.push_back(Rc::clone(job)).is_ok());
+    }
+    queue.remove(&jobs[1]);
+    assert_eq!(ids(&all), [0, 1, 2, 3]);
+    assert_eq!(ids(&queue), [3, 2, 0]);
+
+    all.remove(&jobs[3]);
+    assert_eq!(ids(&all), [0, 1, 2]);
+    assert_eq!(ids(&queue), [3, 2, 0]);
+    assert!(jobs[3].runnable.is_linked());
+}
+
+#[test]
+fn insert_after_anchor() {
+    let jobs: Vec<Rc<Job>> = (0..4).map(job).collect();
+    let mut list: IntrusiveList<Job> = IntrusiveList::new();
+    assert!(list.push_back(Rc::clone(&jobs[0])).is_ok());
+    assert!(list.insert_after(&jobs[0], Rc::clone(&jobs[2])).is_ok());
+    assert!(list.insert_after(&jobs[0], Rc::clone(&jobs[1])).is_ok());
+    assert!(list.insert_after(&jobs[2], Rc::clone(&jobs[3])).is_ok());
+    assert_eq!(ids(&list), [0, 1, 2, 3]);
+    assert_eq!(list.back().map(|job| job.id), Some(3));
+
+    let stranger = job(9);
+    assert!(list.insert_after(&stranger, job(10)).is_err());
+}
+
+#[test]
+fn dropping_the_list_unlinks_its_values() {
+    let a = job(1);
+    {
+        let mut list: IntrusiveList<Job> = IntrusiveList::new();
+        assert!(list.push_back(Rc::clone(&a)).is_ok());
+        assert!(list.push_back(job(2)).is_ok());
+    }
+    assert!(!a.all.is_linked());
+    assert_eq!(Rc::strong_count(&a), 1);
+}
+
+#[test]
+fn random_operations_match_a_model() {
+    let jobs: Vec<Rc<Job>> = (0..32).map(job).collect();
+    let mut list: IntrusiveList<Job> = IntrusiveList::new();
+    let mut model: VecDeque<u32> = VecDeque::new();
+    let mut state = 0xfeed;
+    for _ in 0..5000 {
+        let value = xorshift(&mut state);
+        let job = &jobs[(value >> 8) as usize % jobs.len()];
+        let linked = model.contains(&job.id);
+        match value % 4 {
+            0 => {
+                assert_eq!(list.push_back(Rc::clone(job)).is_ok(), !linked);
+                if !linked {
+                    model.push_back(job.id);
+                }
+            }
+            1 => {
+                assert_eq!(list.push_front(Rc::clone(job)).is_ok(), !linked);
+                if !linked {
+                    model.push_front(job.id);
+                }
+            }
+            2 => {
+                assert_eq!(list.remove(job).is_some(), linked);
+                model.retain(|&id| id != job.id);
+            }
+            _ => assert_eq!(list.pop_front().map(|job| job.id), model.pop_front()),
+        }
+        assert_eq!(list.len(), model.len());
+    }
+    assert!(list.iter().map(|job| job.id).eq(model.iter().copied()));
+}
+
+#[test]
+fn drop_long_list() {
+    let mut list: IntrusiveList<Job> = IntrusiveList::new();
+    for i in 0..200_000 {
+        assert!(list.push_back(job(i)).is_ok());
+    }
+    assert_eq!(list.len(), 200_000);
+    drop(list);
+}
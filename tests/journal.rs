@@ -0,0 +1,315 @@
+mod common;
+
+use common::xorshift;
+use dll::{LinkedList, ListEvent, Op, ParseOpError, SyncLinkedList};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+fn journaled_list() -> LinkedList<u32> {
+    let mut list = LinkedList::new();
+    list.start_journal();
+    list
+}
+
+fn to_lines(list: &mut LinkedList<u32>) -> Vec<String> {
+    list.journal().iter().map(|op| op.to_string()).collect()
+}
+
+#[test]
+fn ops_round_trip_through_text() {
+    let ops: Vec<Op<String>> = vec![
+        Op::PushFront("a b".to_string()),
+        Op::PushBack(String::new()),
+        Op::PopFront,
+        Op::PopBack,
+        Op::Insert {
+            index: 3,
+            value: " x".to_string(),
+        },
+        Op::Remove { index: 0 },
+        Op::Retain {
+            keep: vec![true, false, true],
+        },
+        Op::Retain { keep: vec![] },
+        Op::Sort {
+            order: vec![2, 0, 1],
+        },
+        Op::Sort { order: vec![] },
+        Op::Clear,
+    ];
+    for op in ops {
+        let line = op.to_string();
+        assert_eq!(line.parse::<Op<String>>(), Ok(op), "{:?}", line);
+    }
+    assert_eq!(
+        Op::<u32>::Insert { index: 1, value: 7 }.to_string(),
+        "insert 1 7"
+    );
+    assert_eq!(
+        Op::<u32>::Retain {
+            keep: vec![false, true]
+        }
+        .to_string(),
+        "retain 01"
+    );
+}
+
+#[test]
+fn bad_lines_are_rejected() {
+    for line in [
+        "",
+        "push",
+        "push_back x",
+        "pop_front 1",
+        "insert 1",
+        "insert x 1",
+        "remove",
+        "retain 012",
+        "sort 1 a",
+        "clear now",
+    ] {
+        assert_eq!(line.parse::<Op<u32>>(), Err(ParseOpError(line.to_string())));
+    }
+}
+
+#[test]
+fn mutating_calls_are_journaled() {
+    let mut list = journaled_list();
+    list.push_back(1);
+    list.push_front(0);
+    list.insert(2, 5);
+    list.remove(7);
+    list.pop_back();
+    list.pop_front();
+    list.clear();
+    list.pop_front();
+    assert_eq!(
+        to_lines(&mut list),
+        [
+            "push_back 1",
+            "push_front 0",
+            "insert 2 5",
+            "pop_back",
+            "pop_front",
+            "clear",
+        ]
+    );
+}
+
+#[test]
+fn journaling_starts_from_the_current_contents() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.push_back(2);
+    assert!(!list.is_journaling());
+    assert!(list.journal().is_empty());
+
+    list.start_journal();
+    list.start_journal();
+    list.push_front(0);
+    list.stop_journal();
+    list.push_front(9);
+    assert!(!list.is_journaling());
+    assert_eq!(
+        to_lines(&mut list),
+        ["push_back 1", "push_back 2", "push_front 0"]
+    );
+}
+
+#[test]
+fn restarting_the_journal_keeps_it_replayable() {
+    let mut list = journaled_list();
+    list.push_back(1);
+    list.stop_journal();
+    list.push_back(2);
+    list.start_journal();
+    assert_eq!(
+        to_lines(&mut list),
+        ["push_back 1", "clear", "push_back 1", "push_back 2"]
+    );
+    let replica: LinkedList<u32> = LinkedList::replay(list.journal().drain());
+    assert_eq!(replica.to_string(), "1 -> 2");
+
+    // With the journal drained, a restart needs no `clear`.
+    list.stop_journal();
+    list.start_journal();
+    assert_eq!(to_lines(&mut list), ["push_back 1", "push_back 2"]);
+}
+
+#[test]
+fn retain_and_sort_are_journaled_by_outcome() {
+    let mut list = journaled_list();
+    for value in [4, 1, 3, 2, 5] {
+        list.push_back(value);
+    }
+    list.journal().drain();
+    // A predicate that depends on call order, not on the values.
+    let mut calls = 0;
+    list.retain(|_| {
+        calls += 1;
+        calls != 2
+    });
+    list.sort(|a, b| a.cmp(b));
+    assert_eq!(to_lines(&mut list), ["retain 10111", "sort 2 1 0 3"]);
+    assert_eq!(list.to_string(), "2 -> 3 -> 4 -> 5");
+
+    let replica: LinkedList<u32> = LinkedList::replay(vec![
+        Op::PushBack(4),
+        Op::PushBack(1),
+        Op::PushBack(3),
+        Op::PushBack(2),
+        Op::PushBack(5),
+        Op::Retain {
+            keep: vec![true, false, true, true, true],
+        },
+        Op::Sort {
+            order: vec![2, 1, 0, 3],
+        },
+    ]);
+    assert_eq!(replica.to_string(), "2 -> 3 -> 4 -> 5");
+}
+
+#[test]
+fn draining_keeps_a_replica_in_step() {
+    let mut list = journaled_list();
+    let mut replica: LinkedList<u32> = LinkedList::new();
+    replica.start_journal();
+    let mut state = 0x1234;
+    for round in 0..50 {
+        for _ in 0..20 {
+            let value = xorshift(&mut state);
+            match value % 4 {
+                0 => {
+                    list.pop_front();
+                }
+                1 => list.push_front(value as u32 % 100),
+                _ => list.push_back(value as u32 % 100),
+            }
+        }
+        if round % 10 == 9 {
+            list.sort(|a, b| a.cmp(b));
+        }
+        let shipped: Vec<Op<u32>> = list.journal().drain().collect();
+        for op in shipped.iter().cloned() {
+            replica.apply(op);
+        }
+        assert!(list.journal().is_empty());
+        assert!(list.iter().eq(replica.iter()));
+        // The replica journals the same ops it was given.
+        assert_eq!(replica.journal().drain().collect::<Vec<_>>(), shipped);
+    }
+}
+
+#[test]
+#[should_panic(expected = "journal doesn't match the list")]
+fn replaying_a_mismatched_sort_panics() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.push_back(2);
+    list.apply(Op::Sort { order: vec![0, 0] });
+}
+
+#[test]
+#[should_panic(expected = "journal doesn't match the list")]
+fn replaying_a_mismatched_retain_panics() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.apply(Op::Retain {
+        keep: vec![true, true],
+    });
+}
+
+#[test]
+fn replayed_sort_is_reported_to_observers() {
+    let mut list = journaled_list();
+    for value in [3, 0, 2, 1] {
+        list.push_back(value);
+    }
+    list.sort(|a, b| a.cmp(b));
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut replica: LinkedList<u32> = LinkedList::new();
+    let log = Rc::clone(&events);
+    replica.subscribe(Box::new(move |event: &ListEvent<u32>| {
+        log.borrow_mut().push(event.clone())
+    }));
+    for op in list.journal().drain() {
+        replica.apply(op);
+    }
+    assert_eq!(replica.to_string(), "0 -> 1 -> 2 -> 3");
+
+    // Mirror the events into a vector and compare.
+    let mut model = Vec::new();
+    for event in events.borrow().iter() {
+        match event {
+            ListEvent::PushBack { value } => model.push(*value),
+            ListEvent::Move { from, to, value } => {
+                let moved = model.remove(*from);
+                assert_eq!(moved, *value);
+                model.insert(*to, moved);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+    assert_eq!(model, [0, 1, 2, 3]);
+}
+
+#[test]
+fn random_operations_replay_from_text() {
+    let mut list = journaled_list();
+    let mut state = 0xbeef;
+    for _ in 0..3000 {
+        let value = xorshift(&mut state);
+        let item = (value >> 16) as u32 % 100;
+        let index = (value >> 32) as usize % (list.len() + 1);
+        match value % 9 {
+            0 => list.push_front(item),
+            1 => list.push_back(item),
+            2 => {
+                list.pop_front();
+            }
+            3 => {
+                list.pop_back();
+            }
+            4 => list.insert(index, item),
+            5 => {
+                list.remove(index);
+            }
+            6 => list.retain(|x| x % 5 != item % 5),
+            7 if list.len() < 30 => list.sort(|a, b| b.cmp(a)),
+            _ if value % 64 == 8 => list.clear(),
+            _ => list.push_back(item),
+        }
+    }
+    let text: String = list
+        .journal()
+        .iter()
+        .map(|op| format!("{}\n", op))
+        .collect();
+    let ops = text.lines().map(|line| line.parse::<Op<u32>>().unwrap());
+    let replica: LinkedList<u32> = LinkedList::replay(ops);
+    assert!(list.iter().eq(replica.iter()));
+}
+
+#[test]
+fn sync_list_replicates_across_threads() {
+    let (sender, receiver) = mpsc::channel::<String>();
+    let worker = std::thread::spawn(move || {
+        let mut list: SyncLinkedList<u32> = SyncLinkedList::new();
+        list.start_journal();
+        for i in 0..10 {
+            list.push_back(i);
+        }
+        list.retain(|x| x % 3 == 0);
+        for op in list.journal().drain() {
+            sender.send(op.to_string()).unwrap();
+        }
+        list.to_string()
+    });
+    let expected = worker.join().unwrap();
+    let replica: SyncLinkedList<u32> =
+        SyncLinkedList::replay(receiver.iter().map(|line| line.parse().unwrap()));
+    assert_eq!(replica.to_string(), expected);
+    assert_eq!(expected, "0 -> 3 -> 6 -> 9");
+}
@@ -0,0 +1,157 @@
+use dll::lfu::LfuCache;
+
+#[test]
+fn create_empty_cache() {
+    let mut cache: LfuCache<u32, u32> = LfuCache::new(4);
+    assert_eq!(cache.len(), 0);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.peek(&1), None);
+    assert_eq!(cache.frequency(&1), None);
+    assert_eq!(cache.pop_lfu(), None);
+    assert_eq!(cache.remove(&1), None);
+    assert_eq!(cache.to_string(), "");
+}
+
+#[test]
+fn evicts_least_frequently_used() {
+    let mut cache = LfuCache::new(3);
+    cache.put(1, 10);
+    cache.put(2, 20);
+    cache.put(3, 30);
+    for _ in 0..3 {
+        cache.get(&1);
+    }
+    cache.get(&3);
+    assert_eq!(cache.to_string(), "1: [2] -> 2: [3] -> 4: [1]");
+
+    cache.put(4, 40);
+    assert!(!cache.contains(&2));
+    cache.put(5, 50);
+    // 4 is the only entry used once, so it goes before 3.
+    assert!(!cache.contains(&4));
+    assert_eq!(cache.to_string(), "1: [5] -> 2: [3] -> 4: [1]");
+}
+
+#[test]
+fn ties_go_to_least_recently_used() {
+    let mut cache = LfuCache::new(3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.put("c", 3);
+    cache.get(&"c");
+    cache.get(&"a");
+    cache.get(&"b");
+    assert_eq!(cache.to_string(), "2: [c -> a -> b]");
+    assert_eq!(cache.pop_lfu(), Some(("c", 3)));
+    cache.put("d", 4);
+    cache.put("e", 5);
+    assert_eq!(cache.to_string(), "1: [e] -> 2: [a -> b]");
+}
+
+#[test]
+fn peek_does_not_count_as_a_use() {
+    let mut cache = LfuCache::new(2);
+    cache.put(1, 1);
+    cache.put(2, 2);
+    assert_eq!(cache.peek(&1), Some(1));
+    assert_eq!(cache.frequency(&1), Some(1));
+    cache.get(&2);
+    cache.put(3, 3);
+    assert_eq!(cache.peek(&1), None);
+    assert_eq!(cache.frequency(&2), Some(2));
+}
+
+#[test]
+fn put_on_existing_key_counts_as_a_use() {
+    let mut cache = LfuCache::new(2);
+    cache.put(1, 1);
+    assert_eq!(cache.put(1, 100), Some(1));
+    assert_eq!(cache.frequency(&1), Some(2));
+    assert_eq!(cache.len(), 1);
+    cache.put(2, 2);
+    cache.put(3, 3);
+    assert_eq!(cache.get(&1), Some(100));
+    assert_eq!(cache.get(&2), None);
+}
+
+#[test]
+fn empty_buckets_are_dropped() {
+    let mut cache = LfuCache::new(4);
+    cache.put(1, 1);
+    cache.get(&1);
+    cache.get(&1);
+    assert_eq!(cache.to_string(), "3: [1]");
+    cache.put(2, 2);
+    assert_eq!(cache.remove(&1), Some(1));
+    assert_eq!(cache.to_string(), "1: [2]");
+    cache.get(&2);
+    assert_eq!(cache.to_string(), "2: [2]");
+}
+
+#[test]
+fn zero_capacity_stores_nothing() {
+    let mut cache = LfuCache::new(0);
+    assert_eq!(cache.put(1, 1), None);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&1), None);
+}
+
+#[test]
+fn random_operations_match_model() {
+    // (key, value, frequency, last use) for every cached entry.
+    let mut model: Vec<(u32, u32, usize, usize)> = Vec::new();
+    let mut cache = LfuCache::new(12);
+    let mut state: u64 = 7;
+    for step in 0..5000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let key = (state % 30) as u32;
+        let position = model.iter().position(|entry| entry.0 == key);
+        if state.is_multiple_of(2) {
+            let expected = position.map(|index| {
+                model[index].2 += 1;
+                model[index].3 = step;
+                model[index].1
+            });
+            assert_eq!(cache.get(&key), expected);
+        } else {
+            let old = match position {
+                Some(index) => {
+                    model[index].2 += 1;
+                    model[index].3 = step;
+                    Some(std::mem::replace(&mut model[index].1, step as u32))
+                }
+                None => {
+                    if model.len() == 12 {
+                        let victim = (0..model.len())
+                            .min_by_key(|&i| (model[i].2, model[i].3))
+                            .unwrap();
+                        model.remove(victim);
+                    }
+                    model.push((key, step as u32, 1, step));
+                    None
+                }
+            };
+            assert_eq!(cache.put(key, step as u32), old);
+        }
+        for entry in &model {
+            assert_eq!(cache.frequency(&entry.0), Some(entry.2));
+        }
+    }
+    model.sort_by_key(|entry| (entry.2, entry.3));
+    for (key, value, _, _) in model {
+        assert_eq!(cache.pop_lfu(), Some((key, value)));
+    }
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn drop_large_cache() {
+    let mut cache = LfuCache::new(200_000);
+    for i in 0..200_000 {
+        cache.put(i, i);
+    }
+    drop(cache);
+}
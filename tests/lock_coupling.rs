@@ -0,0 +1,298 @@
+use std::sync::mpsc;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use dll::LockCouplingList;
+
+const THREADS: usize = 16;
+const OPS_PER_THREAD: usize = 300;
+
+/// Runs `f` on its own thread and fails the test if it hasn't finished within
+/// `secs` seconds. Every operation locks nodes strictly front to back, so a
+/// run that stalls here means that ordering was broken somewhere.
+fn with_deadline<F: FnOnce() + Send + 'static>(secs: u64, f: F) {
+    let (done, finished) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        f();
+        done.send(()).unwrap();
+    });
+    match finished.recv_timeout(Duration::from_secs(secs)) {
+        Ok(()) => worker.join().unwrap(),
+        Err(mpsc::RecvTimeoutError::Disconnected) => worker.join().unwrap(),
+        Err(mpsc::RecvTimeoutError::Timeout) => panic!("possible deadlock: still running"),
+    }
+}
+
+/// Checks that the forward and backward links describe the same sequence and
+/// agree with `len()`, draining the list in the process.
+fn assert_invariants_and_drain(list: &LockCouplingList<usize>) -> Vec<usize> {
+    let forward: Vec<usize> = list.iter().collect();
+    assert_eq!(forward.len(), list.len());
+    let mut backward = Vec::new();
+    while let Some(value) = list.pop_back() {
+        backward.push(value);
+    }
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert!(list.is_empty());
+    forward
+}
+
+#[test]
+fn create_empty_list() {
+    let list: LockCouplingList<u32> = LockCouplingList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn push_and_pop_front_and_back() {
+    let list: LockCouplingList<u32> = LockCouplingList::new();
+    list.push_front(1);
+    list.push_back(2);
+    list.push_front(3);
+    list.push_back(4);
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.to_string(), "3 -> 1 -> 2 -> 4");
+    assert_eq!(list.pop_front(), Some(3));
+    assert_eq!(list.pop_back(), Some(4));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn retains_odd_values() {
+    let list: LockCouplingList<usize> = LockCouplingList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 2 != 0);
+    assert_eq!(list.len(), 500);
+    let values = assert_invariants_and_drain(&list);
+    assert!(values.iter().all(|x| x % 2 != 0));
+}
+
+/// Each removal leaves the removed node's lock holding the last pointer to
+/// it, so the node is unlocked and freed in one go. Boxed values give Miri
+/// (see `tests/raw.rs`) something to catch if the guard ever outlived the
+/// node.
+#[test]
+fn removed_nodes_are_unlocked_before_they_are_freed() {
+    let list: LockCouplingList<Box<usize>> = LockCouplingList::new();
+    for i in 0..10 {
+        list.push_back(Box::new(i));
+    }
+    assert_eq!(list.pop_front(), Some(Box::new(0)));
+    assert_eq!(list.pop_back(), Some(Box::new(9)));
+    assert_eq!(list.remove_if(|x| **x == 4), Some(Box::new(4)));
+    list.retain(|x| **x % 3 != 0);
+    {
+        let mut cursor = list.cursor();
+        assert!(cursor.move_next());
+        assert_eq!(cursor.remove_next(), Some(Box::new(2)));
+    }
+    assert_eq!(list.to_string(), "1 -> 5 -> 7 -> 8");
+}
+
+#[test]
+fn cursor_walks_inserts_and_removes() {
+    let list: LockCouplingList<usize> = LockCouplingList::new();
+    for i in [1, 3, 4] {
+        list.push_back(i);
+    }
+    {
+        let mut cursor = list.cursor();
+        assert!(cursor.move_next());
+        cursor.insert_after(2);
+        assert!(cursor.move_next());
+        assert!(cursor.move_next());
+        assert_eq!(cursor.current(), Some(&3));
+        assert_eq!(cursor.remove_next(), Some(4));
+        assert_eq!(cursor.remove_next(), None);
+        assert!(!cursor.move_next());
+    }
+    assert_eq!(assert_invariants_and_drain(&list), vec![1, 2, 3]);
+}
+
+#[test]
+fn parked_iterator_survives_long_runs_of_removals() {
+    let list: LockCouplingList<usize> = LockCouplingList::new();
+    for i in 0..100_000 {
+        list.push_back(i);
+    }
+    // Park one iterator at each end of a long run of nodes, then remove the
+    // whole run one node at a time.
+    let mut dropped = list.iter();
+    assert_eq!(dropped.next(), Some(0));
+    let mut iter = list.iter();
+    assert_eq!(iter.nth(99_997), Some(99_997));
+    for i in 0..99_998 {
+        assert_eq!(list.pop_front(), Some(i));
+    }
+    drop(dropped);
+    list.push_back(100_000);
+    assert_eq!(iter.next(), Some(99_998));
+
+    // Parked in the middle, with nodes on both sides going too.
+    assert_eq!(list.remove_if(|x| *x == 99_998), Some(99_998));
+    assert_eq!(list.pop_front(), Some(99_999));
+    assert_eq!(iter.collect::<Vec<_>>(), [100_000]);
+}
+
+#[test]
+fn disjoint_regions_do_not_block() {
+    let list = Arc::new(LockCouplingList::new());
+    for i in 0..100 {
+        list.push_back(i);
+    }
+
+    // Park a cursor near the front, holding that node's lock, while another
+    // thread works at the back of the list.
+    let mut cursor = list.cursor();
+    cursor.move_next();
+    cursor.move_next();
+    let other = Arc::clone(&list);
+    with_deadline(10, move || {
+        for i in 0..100 {
+            other.push_back(100 + i);
+            other.pop_back();
+        }
+        assert_eq!(other.pop_back(), Some(99));
+    });
+    assert_eq!(cursor.current(), Some(&1));
+
+    // A traversal from the front has to wait for the cursor to move on.
+    drop(cursor);
+    assert_eq!(list.remove_if(|x| *x == 50), Some(50));
+    assert_eq!(list.len(), 98);
+}
+
+#[test]
+fn concurrent_mixed_operations() {
+    let list = Arc::new(LockCouplingList::new());
+    for i in 0..THREADS * OPS_PER_THREAD {
+        list.push_back(i);
+    }
+
+    let shared = Arc::clone(&list);
+    with_deadline(60, move || {
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let list = Arc::clone(&shared);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    let base = 1_000_000 * (t + 1);
+                    for i in 0..OPS_PER_THREAD {
+                        match (t + i) % 8 {
+                            0 => list.push_front(base + i),
+                            1 => list.push_back(base + i),
+                            2 => drop(list.pop_front()),
+                            3 => drop(list.pop_back()),
+                            4 => drop(list.insert_after(|x| x % 97 == t, base + i)),
+                            5 => drop(list.remove_if(|x| x % 89 == t)),
+                            6 => list.retain(|x| x % 1013 != i),
+                            _ => {
+                                let mut cursor = list.cursor();
+                                let mut steps = 0;
+                                while steps < 50 && cursor.move_next() {
+                                    steps += 1;
+                                }
+                                cursor.insert_after(base + i);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+
+    assert_invariants_and_drain(&list);
+}
+
+#[test]
+fn concurrent_pushes_and_pops_lose_nothing() {
+    let list = Arc::new(LockCouplingList::new());
+    let shared = Arc::clone(&list);
+    let popped = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let popped_by_workers = Arc::clone(&popped);
+    with_deadline(60, move || {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let list = Arc::clone(&shared);
+                let popped = Arc::clone(&popped_by_workers);
+                thread::spawn(move || {
+                    let mut mine = Vec::new();
+                    for i in 0..OPS_PER_THREAD {
+                        let value = t * OPS_PER_THREAD + i;
+                        if i % 2 == 0 {
+                            list.push_front(value);
+                            mine.extend(list.pop_back());
+                        } else {
+                            list.push_back(value);
+                            mine.extend(list.pop_front());
+                        }
+                    }
+                    popped.lock().unwrap().extend(mine);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+
+    let mut all = assert_invariants_and_drain(&list);
+    all.extend(popped.lock().unwrap().iter().copied());
+    all.sort();
+    assert_eq!(all, (0..THREADS * OPS_PER_THREAD).collect::<Vec<_>>());
+}
+
+#[test]
+fn iterators_keep_order_while_others_remove() {
+    let list = Arc::new(LockCouplingList::new());
+    for i in 0..THREADS * OPS_PER_THREAD {
+        list.push_back(i);
+    }
+    let shared = Arc::clone(&list);
+    with_deadline(60, move || {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let list = Arc::clone(&shared);
+                thread::spawn(move || {
+                    if t % 2 == 0 {
+                        // Values only ever go in in ascending order, so any
+                        // iterator has to see them that way, without repeats.
+                        for _ in 0..5 {
+                            let seen: Vec<usize> = list.iter().collect();
+                            assert!(seen.windows(2).all(|pair| pair[0] < pair[1]));
+                        }
+                    } else {
+                        for i in 0..OPS_PER_THREAD {
+                            match i % 3 {
+                                0 => drop(list.pop_front()),
+                                1 => drop(list.remove_if(|x| x % 7 == t)),
+                                _ => list.retain(|x| x % 11 != i % 11),
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+
+    assert_invariants_and_drain(&list);
+}
@@ -0,0 +1,151 @@
+use dll::lru::LruCache;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Returns the cache's keys from most to least recently used.
+fn keys(cache: &LruCache<u32, u32>) -> Vec<u32> {
+    cache.iter().map(|(key, _)| key).collect()
+}
+
+/// Attaches a callback that records every eviction.
+fn record_evictions(cache: &mut LruCache<u32, u32>) -> Rc<RefCell<Vec<(u32, u32)>>> {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&evicted);
+    cache.set_eviction_callback(move |key, value| log.borrow_mut().push((key, value)));
+    evicted
+}
+
+#[test]
+fn create_empty_cache() {
+    let mut cache: LruCache<u32, u32> = LruCache::new(4);
+    assert_eq!(cache.len(), 0);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.peek(&1), None);
+    assert_eq!(cache.pop_lru(), None);
+    assert_eq!(cache.remove(&1), None);
+    assert_eq!(keys(&cache), []);
+}
+
+#[test]
+fn evicts_in_insertion_order_without_reads() {
+    let mut cache = LruCache::new(3);
+    let evicted = record_evictions(&mut cache);
+    for i in 0..6 {
+        cache.put(i, i * 10);
+    }
+    assert_eq!(keys(&cache), [5, 4, 3]);
+    assert_eq!(*evicted.borrow(), [(0, 0), (1, 10), (2, 20)]);
+}
+
+#[test]
+fn get_promotes_but_peek_does_not() {
+    let mut cache = LruCache::new(3);
+    let evicted = record_evictions(&mut cache);
+    cache.put(1, 1);
+    cache.put(2, 2);
+    cache.put(3, 3);
+
+    assert_eq!(cache.get(&1), Some(1));
+    assert_eq!(keys(&cache), [1, 3, 2]);
+    assert_eq!(cache.peek(&2), Some(2));
+    assert_eq!(keys(&cache), [1, 3, 2]);
+
+    cache.put(4, 4);
+    cache.put(5, 5);
+    assert_eq!(*evicted.borrow(), [(2, 2), (3, 3)]);
+    assert_eq!(keys(&cache), [5, 4, 1]);
+}
+
+#[test]
+fn put_on_existing_key_replaces_and_promotes() {
+    let mut cache = LruCache::new(2);
+    let evicted = record_evictions(&mut cache);
+    cache.put(1, 1);
+    cache.put(2, 2);
+    assert_eq!(cache.put(1, 100), Some(1));
+    assert_eq!(cache.len(), 2);
+    cache.put(3, 3);
+    assert_eq!(*evicted.borrow(), [(2, 2)]);
+    assert_eq!(cache.peek(&1), Some(100));
+}
+
+#[test]
+fn pop_lru_and_remove_skip_the_callback() {
+    let mut cache = LruCache::new(4);
+    let evicted = record_evictions(&mut cache);
+    for i in 0..4 {
+        cache.put(i, i);
+    }
+    assert_eq!(cache.pop_lru(), Some((0, 0)));
+    assert_eq!(cache.remove(&2), Some(2));
+    assert!(!cache.contains(&2));
+    assert_eq!(keys(&cache), [3, 1]);
+    assert!(evicted.borrow().is_empty());
+}
+
+#[test]
+fn resize_evicts_least_recent() {
+    let mut cache = LruCache::new(5);
+    let evicted = record_evictions(&mut cache);
+    for i in 0..5 {
+        cache.put(i, i);
+    }
+    cache.get(&0);
+    cache.resize(2);
+    assert_eq!(cache.capacity(), 2);
+    assert_eq!(keys(&cache), [0, 4]);
+    assert_eq!(*evicted.borrow(), [(1, 1), (2, 2), (3, 3)]);
+
+    cache.resize(3);
+    cache.put(5, 5);
+    assert_eq!(keys(&cache), [5, 0, 4]);
+    assert_eq!(evicted.borrow().len(), 3);
+}
+
+#[test]
+fn zero_capacity_evicts_everything() {
+    let mut cache = LruCache::new(0);
+    let evicted = record_evictions(&mut cache);
+    cache.put(1, 1);
+    assert!(cache.is_empty());
+    assert_eq!(*evicted.borrow(), [(1, 1)]);
+}
+
+#[test]
+fn random_operations_match_model() {
+    let mut state: u64 = 99;
+    let mut cache = LruCache::new(16);
+    // Most recent first, like the cache.
+    let mut model: Vec<(u32, u32)> = Vec::new();
+    for step in 0..5000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let key = (state % 40) as u32;
+        let position = model.iter().position(|(k, _)| *k == key);
+        if state.is_multiple_of(3) {
+            let expected = position.map(|index| {
+                let entry = model.remove(index);
+                model.insert(0, entry);
+                entry.1
+            });
+            assert_eq!(cache.get(&key), expected);
+        } else {
+            let old = position.map(|index| model.remove(index).1);
+            model.insert(0, (key, step));
+            model.truncate(16);
+            assert_eq!(cache.put(key, step), old);
+        }
+    }
+    assert_eq!(cache.iter().collect::<Vec<_>>(), model);
+}
+
+#[test]
+fn drop_large_cache() {
+    let mut cache = LruCache::new(200_000);
+    for i in 0..200_000 {
+        cache.put(i, i);
+    }
+    drop(cache);
+}
@@ -244,6 +244,7 @@ fn clear() {
 }
 
 #[test]
+#[allow(clippy::manual_is_multiple_of)]
 fn retains_odd_values() {
     let mut list: LinkedList<u64> = LinkedList::new();
     for i in 0..1000 {
@@ -281,3 +282,59 @@ fn immutable_iterator() {
     assert_eq!(format!("{}", list), "1 -> 2 -> 3 -> 4 -> 5");
     assert_eq!(list.len(), 5);
 }
+
+#[test]
+fn retains_head_and_tail() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in 0..10 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 3 == 0);
+    assert_eq!(list.to_string(), "0 -> 3 -> 6 -> 9");
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_back(), Some(9));
+    assert!(list.head.as_ref().unwrap().borrow().prev.is_none());
+    assert!(list.tail.as_ref().unwrap().borrow().next.is_none());
+}
+
+#[test]
+fn drops_long_list() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in 0..200_000 {
+        list.push_back(i);
+    }
+    drop(list);
+}
+
+#[test]
+fn links_stay_consistent() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in 0..50 {
+        match i % 5 {
+            0 | 1 => list.push_back(i),
+            2 => list.push_front(i),
+            3 => drop(list.pop_front()),
+            _ => list.retain(|x| x % 7 != 0),
+        }
+
+        // Walk forwards, then backwards over the weak links, and check both
+        // ends are terminated.
+        let forward: Vec<u32> = list.iter().collect();
+        let mut backward = Vec::new();
+        let mut current = list.tail.clone();
+        while let Some(node) = current {
+            backward.push(node.borrow().data);
+            current = node.borrow().prev.as_ref().and_then(|prev| prev.upgrade());
+        }
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), list.len());
+        if let Some(head) = list.head.as_ref() {
+            assert!(head.borrow().prev.is_none());
+            assert!(list.tail.as_ref().unwrap().borrow().next.is_none());
+        } else {
+            assert!(list.tail.is_none());
+        }
+    }
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use common::xorshift;
+use dll::{LinkedList, ListEvent, SyncLinkedList};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+type Log = Rc<RefCell<Vec<ListEvent<u32>>>>;
+
+fn logged_list() -> (LinkedList<u32>, Log) {
+    let log: Log = Rc::new(RefCell::new(Vec::new()));
+    let mut list = LinkedList::new();
+    let sink = Rc::clone(&log);
+    list.subscribe(Box::new(move |event: &ListEvent<u32>| {
+        sink.borrow_mut().push(event.clone())
+    }));
+    (list, log)
+}
+
+/// Applies `event` to `model`, the way a subscriber mirroring the list would.
+fn apply(model: &mut Vec<u32>, event: &ListEvent<u32>) {
+    match event {
+        ListEvent::PushFront { value } => model.insert(0, *value),
+        ListEvent::PushBack { value } => model.push(*value),
+        ListEvent::PopFront { value } => assert_eq!(model.remove(0), *value),
+        ListEvent::PopBack { value } => assert_eq!(model.pop(), Some(*value)),
+        ListEvent::Insert { index, value } => model.insert(*index, *value),
+        ListEvent::Remove { index, value } => assert_eq!(model.remove(*index), *value),
+        ListEvent::Move { from, to, value } => {
+            let moved = model.remove(*from);
+            assert_eq!(moved, *value);
+            model.insert(*to, moved);
+        }
+        ListEvent::Clear { values } => {
+            assert_eq!(model, values);
+            model.clear();
+        }
+    }
+}
+
+#[test]
+fn pushes_pops_inserts_and_removals() {
+    let (mut list, log) = logged_list();
+    list.push_back(1);
+    list.push_front(0);
+    list.insert(2, 3);
+    list.insert(2, 2);
+    assert_eq!(list.remove(1), Some(1));
+    assert_eq!(list.remove(9), None);
+    assert_eq!(list.pop_back(), Some(3));
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_front(), Some(2));
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(
+        *log.borrow(),
+        [
+            ListEvent::PushBack { value: 1 },
+            ListEvent::PushFront { value: 0 },
+            ListEvent::Insert { index: 2, value: 3 },
+            ListEvent::Insert { index: 2, value: 2 },
+            ListEvent::Remove { index: 1, value: 1 },
+            ListEvent::PopBack { value: 3 },
+            ListEvent::PopFront { value: 0 },
+            ListEvent::PopFront { value: 2 },
+        ]
+    );
+}
+
+#[test]
+fn retain_reports_each_removal_and_clear_reports_once() {
+    let (mut list, log) = logged_list();
+    for i in 0..6 {
+        list.push_back(i);
+    }
+    log.borrow_mut().clear();
+    list.retain(|x| x % 3 != 0);
+    assert_eq!(
+        *log.borrow(),
+        [
+            ListEvent::Remove { index: 0, value: 0 },
+            ListEvent::Remove { index: 2, value: 3 },
+        ]
+    );
+    log.borrow_mut().clear();
+    list.clear();
+    assert_eq!(
+        *log.borrow(),
+        [ListEvent::Clear {
+            values: vec![1, 2, 4, 5]
+        }]
+    );
+}
+
+#[test]
+fn sort_is_reported_as_moves() {
+    let (mut list, log) = logged_list();
+    for value in [3, 1, 2] {
+        list.push_back(value);
+    }
+    let mut model: Vec<u32> = list.iter().collect();
+    log.borrow_mut().clear();
+    list.sort(|a, b| a.cmp(b));
+    assert_eq!(
+        log.borrow()[0],
+        ListEvent::Move {
+            from: 1,
+            to: 0,
+            value: 1
+        }
+    );
+    for event in log.borrow().iter() {
+        apply(&mut model, event);
+    }
+    assert_eq!(model, [1, 2, 3]);
+}
+
+#[test]
+fn unsubscribe_by_token() {
+    let first = Rc::new(RefCell::new(0));
+    let second = Rc::new(RefCell::new(0));
+    let mut list: LinkedList<u32> = LinkedList::new();
+    let counter = Rc::clone(&first);
+    let a = list.subscribe(Box::new(move |_: &ListEvent<u32>| {
+        *counter.borrow_mut() += 1
+    }));
+    let counter = Rc::clone(&second);
+    let b = list.subscribe(Box::new(move |_: &ListEvent<u32>| {
+        *counter.borrow_mut() += 1
+    }));
+    assert_ne!(a, b);
+
+    list.push_back(1);
+    assert!(list.unsubscribe(a));
+    assert!(!list.unsubscribe(a));
+    list.push_back(2);
+    assert!(list.unsubscribe(b));
+    list.push_back(3);
+    assert_eq!(*first.borrow(), 1);
+    assert_eq!(*second.borrow(), 2);
+}
+
+#[test]
+fn events_leave_snapshots_alone() {
+    let (mut list, log) = logged_list();
+    for i in 0..4 {
+        list.push_back(i);
+    }
+    let snapshot = list.snapshot();
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.remove(1), Some(2));
+    assert_eq!(
+        log.borrow().last(),
+        Some(&ListEvent::Remove { index: 1, value: 2 })
+    );
+    assert_eq!(snapshot.to_string(), "0 -> 1 -> 2 -> 3");
+    assert_eq!(list.to_string(), "1 -> 3");
+}
+
+#[test]
+fn sync_list_takes_send_sync_observers() {
+    let count = Arc::new(Mutex::new(0));
+    let mut list: SyncLinkedList<u32> = SyncLinkedList::new();
+    let counter = Arc::clone(&count);
+    list.subscribe(Box::new(move |_: &ListEvent<u32>| {
+        *counter.lock().unwrap() += 1
+    }));
+    let handle = std::thread::spawn(move || {
+        list.push_back(1);
+        list.push_back(2);
+        list.clear();
+        list
+    });
+    assert!(handle.join().unwrap().is_empty());
+    assert_eq!(*count.lock().unwrap(), 3);
+}
+
+#[test]
+fn random_events_rebuild_the_list() {
+    let (mut list, log) = logged_list();
+    let mut state = 0x5eed;
+    for _ in 0..3000 {
+        let value = xorshift(&mut state);
+        let item = (value >> 16) as u32 % 100;
+        let index = (value >> 32) as usize % (list.len() + 1);
+        match value % 9 {
+            0 => list.push_front(item),
+            1 => list.push_back(item),
+            2 => {
+                list.pop_front();
+            }
+            3 => {
+                list.pop_back();
+            }
+            4 => list.insert(index, item),
+            5 => {
+                list.remove(index);
+            }
+            6 => list.retain(|x| x % 7 != item % 7),
+            7 if list.len() < 20 => list.sort(|a, b| a.cmp(b)),
+            _ if value % 50 == 8 => list.clear(),
+            _ => list.push_back(item),
+        }
+    }
+    let mut model = Vec::new();
+    for event in log.borrow().iter() {
+        apply(&mut model, event);
+    }
+    assert!(list.iter().eq(model.iter().copied()));
+}
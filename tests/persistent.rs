@@ -0,0 +1,142 @@
+use dll::PersistentList;
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A value that counts how many times it has been cloned, to check which
+/// nodes an operation copies.
+struct Counted {
+    value: u32,
+    clones: Rc<Cell<usize>>,
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        Counted {
+            value: self.value,
+            clones: Rc::clone(&self.clones),
+        }
+    }
+}
+
+impl fmt::Display for Counted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+fn counted_list(len: u32) -> (PersistentList<Counted>, Rc<Cell<usize>>) {
+    let clones = Rc::new(Cell::new(0));
+    let list = (0..len)
+        .map(|value| Counted {
+            value,
+            clones: Rc::clone(&clones),
+        })
+        .collect();
+    (list, clones)
+}
+
+#[test]
+fn create_empty_list() {
+    let list: PersistentList<u32> = PersistentList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.first(), None);
+    assert_eq!(list.get(0), None);
+    assert!(list.pop_front().is_none());
+    assert_eq!(list.iter().next(), None);
+}
+
+#[test]
+fn old_versions_are_unchanged() {
+    let v0: PersistentList<u32> = (1..=3).collect();
+    let v1 = v0.push_front(0);
+    let v2 = v1.insert(4, 4);
+    let v3 = v2.update(2, 20);
+    let (_, v4) = v3.pop_front().unwrap();
+    let v5 = v4.concat(&v0);
+
+    assert_eq!(v0.to_string(), "1 -> 2 -> 3");
+    assert_eq!(v1.to_string(), "0 -> 1 -> 2 -> 3");
+    assert_eq!(v2.to_string(), "0 -> 1 -> 2 -> 3 -> 4");
+    assert_eq!(v3.to_string(), "0 -> 1 -> 20 -> 3 -> 4");
+    assert_eq!(v4.to_string(), "1 -> 20 -> 3 -> 4");
+    assert_eq!(v5.to_string(), "1 -> 20 -> 3 -> 4 -> 1 -> 2 -> 3");
+    assert_eq!(v5.len(), 7);
+    assert_eq!(v5.get(5), Some(&2));
+}
+
+#[test]
+fn edits_copy_only_the_prefix() {
+    let (list, clones) = counted_list(100);
+
+    let longer = list.push_front(list.get(0).unwrap().clone());
+    assert_eq!(clones.get(), 1);
+    assert_eq!(longer.len(), 101);
+
+    clones.set(0);
+    let _ = list.insert(10, list.get(0).unwrap().clone());
+    assert_eq!(clones.get(), 1 + 10);
+
+    clones.set(0);
+    let _ = list.update(30, list.get(0).unwrap().clone());
+    assert_eq!(clones.get(), 1 + 30);
+
+    clones.set(0);
+    let (short, short_clones) = counted_list(5);
+    let _ = short.concat(&list);
+    assert_eq!(short_clones.get(), 5);
+    assert_eq!(clones.get(), 0);
+
+    clones.set(0);
+    let copy = list.clone();
+    assert_eq!(clones.get(), 0);
+    assert!(copy.ptr_eq(&list));
+}
+
+#[test]
+fn pop_front_shares_the_tail() {
+    let list: PersistentList<u32> = (1..=3).collect();
+    let (_, rest) = list.pop_front().unwrap();
+    let (_, again) = list.pop_front().unwrap();
+    assert!(rest.ptr_eq(&again));
+    assert!(!rest.ptr_eq(&list));
+    assert!(rest == (2..=3).collect());
+}
+
+#[test]
+fn snapshots_of_every_step() {
+    let mut history = vec![PersistentList::new()];
+    for i in 0..100u32 {
+        let last = history.last().unwrap();
+        let next = if i % 3 == 0 && !last.is_empty() {
+            last.update(0, i)
+        } else {
+            last.push_front(i)
+        };
+        history.push(next);
+    }
+    assert_eq!(history[0].len(), 0);
+    assert_eq!(history[1].to_string(), "0");
+    assert_eq!(history[3].to_string(), "2 -> 1 -> 0");
+    assert_eq!(history[4].to_string(), "3 -> 1 -> 0");
+    assert_eq!(history[100].len(), 67);
+}
+
+#[test]
+#[should_panic(expected = "insertion index out of bounds")]
+fn insert_past_the_end_panics() {
+    let list: PersistentList<u32> = (1..=3).collect();
+    let _ = list.insert(4, 0);
+}
+
+#[test]
+fn dropping_a_version_keeps_shared_nodes() {
+    let base: PersistentList<u32> = (0..200_000).collect();
+    let branch = base.push_front(7);
+    drop(base);
+    assert_eq!(branch.len(), 200_001);
+    assert_eq!(branch.iter().last(), Some(&199_999));
+    drop(branch);
+}
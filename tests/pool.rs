@@ -0,0 +1,125 @@
+use dll::{LinkedList, PoolStats, SyncLinkedList};
+use std::rc::Rc;
+
+#[test]
+fn new_list_has_no_pool() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.pop_back();
+    assert_eq!(
+        list.pool_stats(),
+        PoolStats {
+            allocated: 1,
+            ..PoolStats::default()
+        }
+    );
+}
+
+#[test]
+fn queue_churn_reuses_nodes() {
+    let mut list: LinkedList<u32> = LinkedList::with_node_pool(8);
+    for i in 0..1000 {
+        list.push_back(i);
+        if list.len() > 4 {
+            assert_eq!(list.pop_front(), Some(i - 4));
+        }
+    }
+    assert_eq!(list.to_string(), "996 -> 997 -> 998 -> 999");
+
+    let stats = list.pool_stats();
+    assert_eq!(stats.allocated, 5);
+    assert_eq!(stats.reused, 995);
+    assert_eq!(stats.recycled, 996);
+    assert_eq!(stats.idle, 1);
+    assert_eq!(stats.discarded, 0);
+}
+
+#[test]
+fn reused_nodes_are_relinked_correctly() {
+    let mut list: LinkedList<u32> = LinkedList::with_node_pool(4);
+    for i in 0..4 {
+        list.push_back(i);
+    }
+    for _ in 0..4 {
+        list.pop_back();
+    }
+    list.push_front(10);
+    list.push_back(20);
+    list.push_front(30);
+    assert_eq!(list.pool_stats().reused, 3);
+    assert_eq!(list.to_string(), "30 -> 10 -> 20");
+    assert!(list.head.as_ref().unwrap().borrow().prev.is_none());
+    assert!(list.tail.as_ref().unwrap().borrow().next.is_none());
+    assert_eq!(list.pop_back(), Some(20));
+    assert_eq!(list.pop_back(), Some(10));
+    assert_eq!(list.pop_back(), Some(30));
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn pool_capacity_is_respected() {
+    let mut list: LinkedList<u32> = LinkedList::with_node_pool(2);
+    for i in 0..5 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 2 == 0);
+    while list.pop_front().is_some() {}
+    let stats = list.pool_stats();
+    assert_eq!(stats.idle, 2);
+    assert_eq!(stats.recycled, 2);
+    assert_eq!(stats.discarded, 3);
+}
+
+#[test]
+fn shared_nodes_are_not_recycled() {
+    let mut list: LinkedList<u32> = LinkedList::with_node_pool(4);
+    list.push_back(1);
+    list.push_back(2);
+    let held = list.head.clone().unwrap();
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pool_stats().idle, 0);
+    assert_eq!(list.pool_stats().discarded, 1);
+
+    // The node we hold on to is untouched by later pushes.
+    list.push_back(3);
+    assert_eq!(held.borrow().data, 1);
+    assert_eq!(list.to_string(), "2 -> 3");
+}
+
+#[derive(Clone)]
+struct Counted(Rc<()>);
+
+impl std::fmt::Display for Counted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Rc::strong_count(&self.0))
+    }
+}
+
+#[test]
+fn shrink_pool_releases_stale_values() {
+    let counter = Rc::new(());
+    let mut list: LinkedList<Counted> = LinkedList::with_node_pool(8);
+    for _ in 0..4 {
+        list.push_back(Counted(Rc::clone(&counter)));
+    }
+    while list.pop_front().is_some() {}
+    assert_eq!(list.pool_stats().idle, 4);
+    assert_eq!(Rc::strong_count(&counter), 5);
+
+    list.shrink_pool();
+    assert_eq!(list.pool_stats().idle, 0);
+    assert_eq!(list.pool_stats().capacity, 8);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn sync_list_pools_nodes() {
+    let mut list: SyncLinkedList<u32> = SyncLinkedList::with_node_pool(4);
+    for i in 0..100 {
+        list.push_front(i);
+        list.pop_back();
+    }
+    assert_eq!(list.pool_stats().allocated, 1);
+    assert_eq!(list.pool_stats().reused, 99);
+    assert!(list.is_empty());
+}
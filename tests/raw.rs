@@ -0,0 +1,194 @@
+#![cfg(feature = "unsafe-backend")]
+//! The behavioural suite shared by `LinkedList` and `RawLinkedList`.
+//!
+//! Run it under Miri to check the raw backend for undefined behaviour:
+//!
+//! ```text
+//! cargo +nightly miri test --features unsafe-backend --test raw
+//! ```
+
+/// Keeps the long-running tests tolerable under Miri.
+#[cfg(not(miri))]
+const LONG: u32 = 200_000;
+#[cfg(miri)]
+const LONG: u32 = 200;
+
+macro_rules! behavioural_suite {
+    ($list:ident) => {
+        use super::LONG;
+
+        #[test]
+        fn create_empty_list() {
+            let list: $list<u32> = $list::new();
+            assert_eq!(list.len(), 0);
+            assert!(list.is_empty());
+            assert_eq!(list.get(0), None);
+            assert_eq!(list.to_string(), "");
+        }
+
+        #[test]
+        fn push_and_pop_front_and_back() {
+            let mut list: $list<u32> = $list::new();
+            list.push_front(1);
+            list.push_back(2);
+            list.push_front(3);
+            list.push_back(4);
+            assert_eq!(list.len(), 4);
+            assert_eq!(list.pop_front(), Some(3));
+            assert_eq!(list.pop_back(), Some(4));
+            assert_eq!(list.pop_front(), Some(1));
+            assert_eq!(list.pop_back(), Some(2));
+            assert_eq!(list.len(), 0);
+            assert_eq!(list.pop_front(), None);
+            assert_eq!(list.pop_back(), None);
+
+            // The list is still usable once it has been emptied.
+            list.push_back(5);
+            assert_eq!(list.pop_front(), Some(5));
+        }
+
+        #[test]
+        fn iterable() {
+            let mut list: $list<u32> = $list::new();
+            for i in 1..=4 {
+                list.push_front(i);
+            }
+            assert_eq!(list.iter().collect::<Vec<_>>(), [4, 3, 2, 1]);
+            assert_eq!(list.get(0), Some(4));
+            assert_eq!(list.get(3), Some(1));
+            assert_eq!(list.get(4), None);
+        }
+
+        #[test]
+        fn mutable_iterator() {
+            let mut list: $list<u32> = $list::new();
+            for i in 0..5 {
+                list.push_back(i);
+            }
+            for element in list.iter_mut() {
+                *element *= 10;
+            }
+            assert_eq!(list.to_string(), "0 -> 10 -> 20 -> 30 -> 40");
+        }
+
+        #[test]
+        fn sort_and_relink() {
+            let mut list: $list<u32> = $list::new();
+            for i in [5, 1, 4, 2, 3] {
+                list.push_back(i);
+            }
+            list.sort(|a, b| a.cmp(b));
+            assert_eq!(list.to_string(), "1 -> 2 -> 3 -> 4 -> 5");
+            assert_eq!(list.pop_back(), Some(5));
+            assert_eq!(list.pop_front(), Some(1));
+            list.push_front(0);
+            list.push_back(9);
+            assert_eq!(list.to_string(), "0 -> 2 -> 3 -> 4 -> 9");
+
+            let mut empty: $list<u32> = $list::new();
+            empty.sort(|a, b| a.cmp(b));
+            assert!(empty.is_empty());
+        }
+
+        #[test]
+        fn retains_odd_values() {
+            let mut list: $list<u64> = $list::new();
+            for i in 0..1000 {
+                list.push_back(i);
+            }
+            list.retain(|x| x % 2 != 0);
+            assert_eq!(list.len(), 500);
+            for _ in 0..list.len() {
+                assert!(list.pop_front().unwrap() % 2 != 0);
+            }
+            assert_eq!(list.len(), 0);
+        }
+
+        #[test]
+        fn retains_head_and_tail() {
+            let mut list: $list<u32> = $list::new();
+            for i in 0..10 {
+                list.push_back(i);
+            }
+            list.retain(|x| x % 3 == 0);
+            assert_eq!(list.to_string(), "0 -> 3 -> 6 -> 9");
+            assert_eq!(list.len(), 4);
+            assert_eq!(list.pop_front(), Some(0));
+            assert_eq!(list.pop_back(), Some(9));
+            assert_eq!(list.to_string(), "3 -> 6");
+
+            list.retain(|_| false);
+            assert!(list.is_empty());
+            assert_eq!(list.pop_back(), None);
+        }
+
+        #[test]
+        fn clear() {
+            let mut list: $list<String> = $list::new();
+            for i in 0..1000 {
+                list.push_back(i.to_string());
+            }
+            list.clear();
+            assert_eq!(list.len(), 0);
+            assert_eq!(list.pop_front(), None);
+        }
+
+        #[test]
+        fn drops_long_list() {
+            let mut list: $list<u32> = $list::new();
+            for i in 0..LONG {
+                list.push_back(i);
+            }
+            drop(list);
+        }
+
+        #[test]
+        fn drops_every_element_once() {
+            use std::rc::Rc;
+
+            let counter = Rc::new(());
+            let mut list: $list<Counted> = $list::new();
+            for _ in 0..10 {
+                list.push_back(Counted(Rc::clone(&counter)));
+            }
+            drop(list.pop_front());
+            list.retain(|_| false);
+            for _ in 0..10 {
+                list.push_front(Counted(Rc::clone(&counter)));
+            }
+            drop(list);
+            assert_eq!(Rc::strong_count(&counter), 1);
+        }
+    };
+}
+
+/// Holds a reference count so tests can see whether every element was
+/// dropped.
+#[derive(Clone)]
+struct Counted(std::rc::Rc<()>);
+
+impl std::fmt::Display for Counted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", std::rc::Rc::strong_count(&self.0))
+    }
+}
+
+mod linked_list {
+    use super::Counted;
+    use dll::LinkedList;
+
+    behavioural_suite!(LinkedList);
+}
+
+mod raw_linked_list {
+    use super::Counted;
+    use dll::RawLinkedList;
+
+    behavioural_suite!(RawLinkedList);
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RawLinkedList<u32>>();
+    }
+}
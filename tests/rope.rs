@@ -0,0 +1,187 @@
+mod common;
+
+use common::xorshift;
+use dll::Rope;
+
+/// Returns the byte range of the chars `start..end` of `text`.
+fn byte_range(text: &str, start: usize, end: usize) -> std::ops::Range<usize> {
+    let offset = |index| {
+        text.char_indices()
+            .nth(index)
+            .map_or(text.len(), |(offset, _)| offset)
+    };
+    offset(start)..offset(end)
+}
+
+#[test]
+fn insert_and_delete_across_chunks() {
+    let mut rope = Rope::with_chunk_size(8);
+    rope.insert(0, "the quick brown fox");
+    assert!(rope.chunk_count() >= 3);
+    rope.insert(4, "very ");
+    rope.insert(rope.len_chars(), " jumps");
+    assert_eq!(rope.to_string(), "the very quick brown fox jumps");
+
+    rope.delete(4..15);
+    assert_eq!(rope.to_string(), "the brown fox jumps");
+    rope.delete(0..rope.len_chars());
+    assert!(rope.is_empty());
+    assert_eq!(rope.chunk_count(), 0);
+}
+
+#[test]
+fn random_edits_match_a_string() {
+    let words = [
+        "a",
+        "héllo",
+        "\n",
+        "wörld ",
+        "🦀🦀",
+        "x\ny\n",
+        "longer piece of text",
+    ];
+    let mut state = 0x1234_5678;
+    let mut rope = Rope::with_chunk_size(16);
+    let mut model = String::new();
+    for _ in 0..3000 {
+        let value = xorshift(&mut state);
+        let len = model.chars().count();
+        if value.is_multiple_of(3) && len > 0 {
+            let start = (value >> 8) as usize % len;
+            let end = start + ((value >> 32) as usize % (len - start + 1)).min(24);
+            rope.delete(start..end);
+            model.replace_range(byte_range(&model, start, end), "");
+        } else {
+            let at = (value >> 8) as usize % (len + 1);
+            let word = words[(value >> 40) as usize % words.len()];
+            rope.insert(at, word);
+            let offset = byte_range(&model, at, at).start;
+            model.insert_str(offset, word);
+        }
+        assert_eq!(rope.len_chars(), model.chars().count());
+        assert_eq!(rope.len_lines(), model.matches('\n').count() + 1);
+    }
+    assert_eq!(rope.to_string(), model);
+    assert!(rope.chars().eq(model.chars()));
+    // Merging keeps the chunks from getting fragmented.
+    assert!(rope.chunk_count() <= 2 * model.len() / rope.chunk_size() + 2);
+}
+
+#[test]
+fn deleting_merges_neighbouring_chunks() {
+    let mut rope = Rope::with_chunk_size(8);
+    rope.insert(0, "aaaaaaaabbbbbbbbcccccccc");
+    assert_eq!(rope.chunk_count(), 3);
+    rope.delete(4..20);
+    assert_eq!(rope.to_string(), "aaaacccc");
+    assert_eq!(rope.chunk_count(), 1);
+}
+
+#[test]
+fn line_indexing() {
+    let mut rope = Rope::with_chunk_size(5);
+    rope.insert(0, "first\nsecond line\n\nfourth");
+    assert_eq!(rope.len_lines(), 4);
+    assert_eq!(rope.line(0).as_deref(), Some("first"));
+    assert_eq!(rope.line(1).as_deref(), Some("second line"));
+    assert_eq!(rope.line(2).as_deref(), Some(""));
+    assert_eq!(rope.line(3).as_deref(), Some("fourth"));
+    assert_eq!(rope.line(4), None);
+
+    assert_eq!(rope.line_to_char(1), 6);
+    assert_eq!(rope.line_to_char(3), 19);
+    assert_eq!(rope.char_to_line(5), (0, 5));
+    assert_eq!(rope.char_to_line(6), (1, 0));
+    assert_eq!(rope.char_to_line(13), (1, 7));
+    assert_eq!(rope.char_to_line(18), (2, 0));
+    assert_eq!(rope.char_to_line(25), (3, 6));
+    assert_eq!(rope.char(6), Some('s'));
+    assert_eq!(rope.char(25), None);
+    assert_eq!(rope.slice(3..9), "st\nsec");
+
+    let lines: Vec<String> = rope.lines().collect();
+    assert_eq!(lines, ["first", "second line", "", "fourth"]);
+    assert_eq!(Rope::new().lines().collect::<Vec<_>>(), [""]);
+}
+
+#[test]
+fn cursor_moves_by_char() {
+    let mut rope = Rope::with_chunk_size(4);
+    rope.insert(0, "añb🦀c");
+    let mut cursor = rope.cursor(0);
+    let mut seen = String::new();
+    while let Some(c) = cursor.peek_next() {
+        seen.push(c);
+        assert!(cursor.next_char());
+    }
+    assert_eq!(seen, "añb🦀c");
+    assert_eq!(cursor.position(), 5);
+    assert!(!cursor.next_char());
+
+    let mut back = String::new();
+    while let Some(c) = cursor.peek_prev() {
+        back.insert(0, c);
+        assert!(cursor.prev_char());
+    }
+    assert_eq!(back, "añb🦀c");
+    assert!(!cursor.prev_char());
+}
+
+#[test]
+fn cursor_moves_by_word() {
+    let rope = Rope::from("let x_1 = foo(bar);");
+    let mut cursor = rope.cursor(0);
+    let mut stops = vec![cursor.position()];
+    while cursor.next_word() {
+        stops.push(cursor.position());
+    }
+    assert_eq!(stops, [0, 4, 10, 14, 19]);
+
+    let mut stops = vec![cursor.position()];
+    while cursor.prev_word() {
+        stops.push(cursor.position());
+    }
+    assert_eq!(stops, [19, 14, 10, 4, 0]);
+
+    let mut cursor = rope.cursor(11);
+    assert!(cursor.prev_word());
+    assert_eq!(cursor.position(), 10);
+}
+
+#[test]
+fn cursor_moves_by_line_keeping_the_column() {
+    let mut rope = Rope::with_chunk_size(6);
+    rope.insert(0, "a long line\nshort\n\nanother long line");
+    let mut cursor = rope.cursor(8);
+    assert_eq!(cursor.line_col(), (0, 8));
+    assert!(!cursor.prev_line());
+    assert!(cursor.next_line());
+    assert_eq!(cursor.line_col(), (1, 5));
+    assert!(cursor.next_line());
+    assert_eq!(cursor.line_col(), (2, 0));
+    assert!(cursor.next_line());
+    assert_eq!(cursor.line_col(), (3, 0));
+    cursor.move_to(rope.len_chars());
+    assert_eq!(cursor.line_col(), (3, 17));
+    assert!(!cursor.next_line());
+    assert!(cursor.prev_line());
+    assert_eq!(cursor.line_col(), (2, 0));
+}
+
+#[test]
+#[should_panic(expected = "char index out of bounds")]
+fn insert_past_the_end_panics() {
+    let mut rope = Rope::from("abc");
+    rope.insert(4, "d");
+}
+
+#[test]
+fn drop_large_rope() {
+    let mut rope = Rope::with_chunk_size(4);
+    let line = "0123456789\n".repeat(20_000);
+    rope.insert(0, &line);
+    assert!(rope.chunk_count() >= 50_000);
+    assert_eq!(rope.len_lines(), 20_001);
+    assert_eq!(rope.line(19_999).as_deref(), Some("0123456789"));
+    drop(rope);
+}
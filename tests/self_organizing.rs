@@ -0,0 +1,128 @@
+mod common;
+
+use common::xorshift;
+use dll::{Policy, SelfOrganizingList};
+
+fn list_of(policy: Policy, len: u32) -> SelfOrganizingList<u32> {
+    let mut list = SelfOrganizingList::new(policy);
+    for i in 0..len {
+        list.insert(i);
+    }
+    list
+}
+
+#[test]
+fn move_to_front() {
+    let mut list = list_of(Policy::MoveToFront, 5);
+    assert!(list.access(&3));
+    assert_eq!(list.to_string(), "3 -> 0 -> 1 -> 2 -> 4");
+    assert!(list.access(&4));
+    assert!(list.access(&4));
+    assert_eq!(list.to_string(), "4 -> 3 -> 0 -> 1 -> 2");
+    assert_eq!(list.stats().relinks, 2);
+}
+
+#[test]
+fn transpose() {
+    let mut list = list_of(Policy::Transpose, 5);
+    assert!(list.access(&3));
+    assert_eq!(list.to_string(), "0 -> 1 -> 3 -> 2 -> 4");
+    assert!(list.access(&3));
+    assert!(list.access(&3));
+    assert_eq!(list.to_string(), "3 -> 0 -> 1 -> 2 -> 4");
+    assert!(list.access(&3));
+    assert_eq!(list.to_string(), "3 -> 0 -> 1 -> 2 -> 4");
+    assert_eq!(list.stats().relinks, 3);
+}
+
+#[test]
+fn frequency_count_keeps_the_list_sorted_by_count() {
+    let mut list = list_of(Policy::FrequencyCount, 5);
+    assert!(list.access(&2));
+    assert_eq!(list.to_string(), "2 -> 0 -> 1 -> 3 -> 4");
+    assert!(list.access(&4));
+    assert_eq!(list.to_string(), "2 -> 4 -> 0 -> 1 -> 3");
+    // 4 ties with 2, so it stays behind it.
+    assert!(list.access(&4));
+    assert_eq!(list.to_string(), "4 -> 2 -> 0 -> 1 -> 3");
+    assert!(list.access(&1));
+    assert!(list.access(&1));
+    assert_eq!(list.to_string(), "4 -> 1 -> 2 -> 0 -> 3");
+    // Already first in its run: only the count changes.
+    assert!(list.access(&4));
+    assert_eq!(list.to_string(), "4 -> 1 -> 2 -> 0 -> 3");
+}
+
+#[test]
+fn frequency_count_matches_counted_sort() {
+    let mut list = list_of(Policy::FrequencyCount, 20);
+    let mut counts = [0u32; 20];
+    let mut state = 0xabc;
+    for _ in 0..2000 {
+        // Skewed towards small values.
+        let value = (xorshift(&mut state) % 20) as u32;
+        let value = value * value / 20;
+        assert!(list.access(&value));
+        counts[value as usize] += 1;
+    }
+    let order: Vec<u32> = list.iter().collect();
+    for pair in order.windows(2) {
+        assert!(counts[pair[0] as usize] >= counts[pair[1] as usize]);
+    }
+}
+
+#[test]
+fn misses_and_find_by_predicate() {
+    let mut list = list_of(Policy::MoveToFront, 4);
+    assert!(!list.access(&7));
+    assert_eq!(list.find(|x| x % 2 == 1), Some(1));
+    assert_eq!(list.find(|x| *x > 10), None);
+    assert_eq!(list.to_string(), "1 -> 0 -> 2 -> 3");
+
+    let stats = list.stats();
+    assert_eq!(stats.lookups, 3);
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.comparisons, 4 + 2 + 4);
+    list.reset_stats();
+    assert_eq!(list.stats().lookups, 0);
+    assert_eq!(list.stats().average_cost(), 0.0);
+}
+
+#[test]
+fn remove_is_not_a_lookup() {
+    let mut list = list_of(Policy::Transpose, 3);
+    assert_eq!(list.remove(&1), Some(1));
+    assert_eq!(list.remove(&1), None);
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.stats().lookups, 0);
+    list.clear();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn skewed_trace_beats_a_static_list() {
+    // Values 90..100 make up most of the lookups but start at the back.
+    let mut trace = Vec::new();
+    let mut state = 0x77;
+    for _ in 0..5000 {
+        let value = xorshift(&mut state);
+        trace.push(match value % 10 {
+            0 => (value >> 8) as u32 % 100,
+            _ => 90 + (value >> 8) as u32 % 10,
+        });
+    }
+    let static_cost: u64 = trace.iter().map(|&value| value as u64 + 1).sum();
+    for policy in [
+        Policy::MoveToFront,
+        Policy::Transpose,
+        Policy::FrequencyCount,
+    ] {
+        let mut list = list_of(policy, 100);
+        for value in &trace {
+            assert!(list.access(value));
+        }
+        let stats = list.stats();
+        assert_eq!(stats.hits, trace.len() as u64);
+        assert!(stats.comparisons < static_cost / 2, "{:?}", policy);
+    }
+}
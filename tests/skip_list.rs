@@ -0,0 +1,150 @@
+mod common;
+
+use common::xorshift;
+use dll::SkipList;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// Checks the list against a `BTreeMap` holding the same entries, through
+/// iteration in both directions, `get`, `rank` and `select`.
+fn assert_matches(list: &SkipList<u32, u32>, model: &BTreeMap<u32, u32>) {
+    let entries: Vec<(u32, u32)> = model.iter().map(|(k, v)| (*k, *v)).collect();
+    assert_eq!(list.len(), model.len());
+    assert_eq!(list.iter().collect::<Vec<_>>(), entries);
+    assert_eq!(
+        list.iter().rev().collect::<Vec<_>>(),
+        entries.iter().rev().copied().collect::<Vec<_>>()
+    );
+    for (index, (key, value)) in entries.iter().enumerate() {
+        assert_eq!(list.get(key), Some(*value));
+        assert_eq!(list.rank(key), index);
+        assert_eq!(list.select(index), Some((*key, *value)));
+    }
+    assert_eq!(list.select(entries.len()), None);
+}
+
+#[test]
+fn create_empty_list() {
+    let list: SkipList<u32, u32> = SkipList::with_seed(1);
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.height(), 1);
+    assert_eq!(list.get(&0), None);
+    assert_eq!(list.rank(&0), 0);
+    assert_eq!(list.select(0), None);
+    assert_eq!(list.iter().next(), None);
+    assert_eq!(list.iter().next_back(), None);
+}
+
+#[test]
+fn insert_replaces_existing_values() {
+    let mut list: SkipList<&str, u32> = SkipList::with_seed(2);
+    assert_eq!(list.insert("b", 1), None);
+    assert_eq!(list.insert("a", 2), None);
+    assert_eq!(list.insert("b", 3), Some(1));
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.to_string(), "a: 2 -> b: 3");
+    assert!(list.contains_key(&"a"));
+    assert!(!list.contains_key(&"c"));
+}
+
+#[test]
+fn removing_everything_shrinks_the_list() {
+    let mut list: SkipList<u32, u32> = SkipList::with_seed(3);
+    for i in 0..1000 {
+        list.insert(i, i * 2);
+    }
+    assert!(list.height() > 1);
+    for i in (0..1000).rev() {
+        assert_eq!(list.remove(&i), Some(i * 2));
+        assert_eq!(list.remove(&i), None);
+    }
+    assert!(list.is_empty());
+    assert_eq!(list.height(), 1);
+    list.insert(5, 5);
+    assert_eq!(list.to_string(), "5: 5");
+}
+
+#[test]
+fn ranges_respect_bounds() {
+    let mut list: SkipList<u32, char> = SkipList::with_seed(4);
+    for (i, c) in ('a'..='j').enumerate() {
+        list.insert(i as u32 * 10, c);
+    }
+    let keys = |iter: dll::skip_list::SkipIter<'_, u32, char>| -> Vec<u32> {
+        iter.map(|(k, _)| k).collect()
+    };
+    assert_eq!(keys(list.range(20..50)), [20, 30, 40]);
+    assert_eq!(keys(list.range(15..=50)), [20, 30, 40, 50]);
+    assert_eq!(keys(list.range(..25)), [0, 10, 20]);
+    assert_eq!(keys(list.range(85..)), [90]);
+    assert_eq!(keys(list.range(95..)), []);
+    assert_eq!(keys(list.range(40..40)), []);
+    assert_eq!(
+        keys(list.range((Bound::Excluded(30), Bound::Excluded(60)))),
+        [40, 50]
+    );
+    assert_eq!(list.range(10..=80).len(), 8);
+
+    // Both ends meet in the middle without overlapping.
+    let mut iter = list.range(30..=60);
+    assert_eq!(iter.next(), Some((30, 'd')));
+    assert_eq!(iter.next_back(), Some((60, 'g')));
+    assert_eq!(iter.next_back(), Some((50, 'f')));
+    assert_eq!(iter.next(), Some((40, 'e')));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}
+
+#[test]
+fn same_seed_builds_same_shape() {
+    let shape = |seed| {
+        let mut list: SkipList<u32, ()> = SkipList::with_seed(seed);
+        (0..500)
+            .map(|i| {
+                list.insert(i, ());
+                list.height()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(shape(9), shape(9));
+    assert_ne!(shape(9), shape(10));
+}
+
+#[test]
+fn random_operations_match_btree_map() {
+    for seed in 1..4 {
+        let mut state = seed | 1;
+        let mut list: SkipList<u32, u32> = SkipList::with_seed(seed);
+        let mut model: BTreeMap<u32, u32> = BTreeMap::new();
+        for step in 0..4000 {
+            let key = (xorshift(&mut state) % 500) as u32;
+            match xorshift(&mut state) % 3 {
+                0 | 1 => assert_eq!(list.insert(key, step), model.insert(key, step)),
+                _ => assert_eq!(list.remove(&key), model.remove(&key)),
+            }
+            if step % 499 == 0 {
+                assert_matches(&list, &model);
+                let (low, high) = (key.min(250), key.max(250));
+                assert_eq!(
+                    list.range(low..high).collect::<Vec<_>>(),
+                    model
+                        .range(low..high)
+                        .map(|(k, v)| (*k, *v))
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+        assert_matches(&list, &model);
+    }
+}
+
+#[test]
+fn drop_long_list() {
+    let mut list: SkipList<u32, u32> = SkipList::new();
+    for i in 0..200_000 {
+        list.insert(i, i);
+    }
+    assert_eq!(list.select(123_456), Some((123_456, 123_456)));
+    drop(list);
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use common::xorshift;
+use dll::{LinkedList, SyncLinkedList};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+/// A value that counts how many times it has been cloned, to check which
+/// nodes an operation copies.
+struct Counted {
+    value: u32,
+    clones: Rc<Cell<usize>>,
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        Counted {
+            value: self.value,
+            clones: Rc::clone(&self.clones),
+        }
+    }
+}
+
+impl fmt::Display for Counted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[test]
+fn snapshot_of_empty_list() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    let snapshot = list.snapshot();
+    list.push_back(1);
+    list.push_front(0);
+    assert!(snapshot.is_empty());
+    assert_eq!(snapshot.iter().next(), None);
+    assert_eq!(list.to_string(), "0 -> 1");
+}
+
+#[test]
+fn snapshot_survives_end_operations() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in 1..=4 {
+        list.push_back(i);
+    }
+    let snapshot = list.snapshot();
+
+    list.push_back(5);
+    list.push_front(0);
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(5));
+    assert_eq!(list.pop_back(), Some(4));
+    list.push_back(6);
+
+    assert_eq!(list.to_string(), "2 -> 3 -> 6");
+    assert_eq!(snapshot.to_string(), "1 -> 2 -> 3 -> 4");
+    assert_eq!(snapshot.len(), 4);
+    assert_eq!(snapshot.iter().len(), 4);
+}
+
+#[test]
+fn snapshot_survives_retain_sort_and_iter_mut() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in [3, 1, 4, 1, 5, 9, 2, 6] {
+        list.push_back(i);
+    }
+    let before = list.snapshot();
+    list.sort(|a, b| a.cmp(b));
+    let sorted = list.snapshot();
+    list.retain(|x| x % 2 == 1);
+    let odd = list.snapshot();
+    for x in list.iter_mut() {
+        *x *= 10;
+    }
+
+    assert_eq!(before.to_string(), "3 -> 1 -> 4 -> 1 -> 5 -> 9 -> 2 -> 6");
+    assert_eq!(sorted.to_string(), "1 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 9");
+    assert_eq!(odd.to_string(), "1 -> 1 -> 3 -> 5 -> 9");
+    assert_eq!(list.to_string(), "10 -> 10 -> 30 -> 50 -> 90");
+}
+
+#[test]
+fn end_operations_copy_nothing_until_pop_back_reaches_shared_nodes() {
+    let clones = Rc::new(Cell::new(0));
+    let counted = |value| Counted {
+        value,
+        clones: Rc::clone(&clones),
+    };
+    let mut list: LinkedList<Counted> = LinkedList::new();
+    for i in 0..100 {
+        list.push_back(counted(i));
+    }
+    let snapshot = list.snapshot();
+    let allocated = list.pool_stats().allocated;
+
+    list.push_front(counted(100));
+    list.push_back(counted(101));
+    list.pop_front();
+    list.pop_front();
+    list.pop_back();
+    clones.set(0);
+    assert_eq!(list.pool_stats().allocated, allocated + 2);
+
+    // The next pop_back would change the last node the snapshot can see, so
+    // the 99 shared nodes are copied once.
+    list.pop_back();
+    assert_eq!(clones.get(), 99 + 1);
+    clones.set(0);
+    list.pop_back();
+    assert_eq!(clones.get(), 1);
+
+    assert_eq!(list.len(), 97);
+    assert_eq!(snapshot.len(), 100);
+    assert_eq!(snapshot.iter().last().map(|c| c.value), Some(99));
+}
+
+#[test]
+fn dropping_the_snapshot_stops_sharing() {
+    let mut list: LinkedList<u32> = LinkedList::with_node_pool(8);
+    for i in 0..4 {
+        list.push_back(i);
+    }
+    drop(list.snapshot());
+    assert_eq!(list.pop_back(), Some(3));
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pool_stats().idle, 2);
+    list.sort(|a, b| b.cmp(a));
+    assert_eq!(list.pool_stats().allocated, 4);
+    assert_eq!(list.to_string(), "2 -> 1");
+}
+
+#[test]
+fn random_operations_keep_every_snapshot() {
+    let mut state = 0x5eed;
+    let mut list: LinkedList<u64> = LinkedList::with_node_pool(16);
+    let mut model = VecDeque::new();
+    let mut snapshots = Vec::new();
+    for step in 0..2000 {
+        let value = xorshift(&mut state);
+        match value % 10 {
+            0 | 1 => {
+                list.push_back(step);
+                model.push_back(step);
+            }
+            2 | 3 => {
+                list.push_front(step);
+                model.push_front(step);
+            }
+            4 => assert_eq!(list.pop_front(), model.pop_front()),
+            5 => assert_eq!(list.pop_back(), model.pop_back()),
+            6 if value.is_multiple_of(7) => {
+                list.retain(|x| !x.is_multiple_of(3));
+                model.retain(|x| !x.is_multiple_of(3));
+            }
+            8 => {
+                let index = (value >> 8) as usize % (model.len() + 1);
+                list.insert(index, step);
+                model.insert(index, step);
+            }
+            9 => {
+                let index = (value >> 8) as usize % (model.len() + 1);
+                assert_eq!(list.remove(index), model.remove(index));
+            }
+            _ => {
+                let expected: Vec<u64> = model.iter().copied().collect();
+                snapshots.push((list.snapshot(), expected));
+                if snapshots.len() > 20 {
+                    snapshots.remove(value as usize % 20);
+                }
+            }
+        }
+        assert_eq!(list.len(), model.len());
+    }
+    assert!(list.iter().eq(model.iter().copied()));
+    for (snapshot, expected) in &snapshots {
+        assert!(snapshot.iter().eq(expected.iter().copied()));
+    }
+}
+
+#[test]
+fn sync_list_snapshots() {
+    let mut list: SyncLinkedList<u32> = SyncLinkedList::new();
+    list.push_back(1);
+    list.push_back(2);
+    let snapshot = list.snapshot();
+    list.pop_back();
+    list.push_back(3);
+    assert_eq!(snapshot.to_string(), "1 -> 2");
+    assert_eq!(list.to_string(), "1 -> 3");
+}
+
+#[test]
+fn drop_long_snapshot() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for i in 0..200_000 {
+        list.push_back(i);
+    }
+    let snapshot = list.snapshot();
+    drop(list);
+    assert_eq!(snapshot.iter().last(), Some(199_999));
+    drop(snapshot);
+}
+
+#[test]
+fn insert_copies_the_shared_nodes() {
+    let mut list: LinkedList<u32> = LinkedList::new();
+    list.push_back(1);
+    list.push_back(2);
+    let snapshot = list.snapshot();
+    list.insert(1, 9);
+    assert_eq!(list.to_string(), "1 -> 9 -> 2");
+    assert_eq!(list.len(), 3);
+    assert_eq!(snapshot.to_string(), "1 -> 2");
+}
@@ -0,0 +1,133 @@
+use dll::SortedLinkedList;
+use std::fmt;
+use std::ops::Bound;
+
+/// A job with a priority, compared by priority only, so that the tests can
+/// tell equal-priority jobs apart.
+#[derive(Clone, Debug, PartialEq)]
+struct Job {
+    priority: u32,
+    name: &'static str,
+}
+
+impl fmt::Display for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.name, self.priority)
+    }
+}
+
+fn by_priority(a: &Job, b: &Job) -> std::cmp::Ordering {
+    a.priority.cmp(&b.priority)
+}
+
+fn job(priority: u32, name: &'static str) -> Job {
+    Job { priority, name }
+}
+
+#[test]
+fn create_empty_list() {
+    let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.first(), None);
+    assert_eq!(list.last(), None);
+    assert_eq!(list.pop_min(), None);
+    assert_eq!(list.pop_max(), None);
+    assert_eq!(list.remove(&1), None);
+    assert_eq!(list.range(..).next(), None);
+}
+
+#[test]
+fn inserts_stay_sorted() {
+    let mut list: SortedLinkedList<i32> = SortedLinkedList::new();
+    for value in [5, -3, 9, 0, 5, 12, -7, 3] {
+        list.insert(value);
+    }
+    assert_eq!(list.len(), 8);
+    assert_eq!(list.to_string(), "-7 -> -3 -> 0 -> 3 -> 5 -> 5 -> 9 -> 12");
+    assert_eq!(list.first(), Some(-7));
+    assert_eq!(list.last(), Some(12));
+    assert_eq!(list.pop_max(), Some(12));
+    assert_eq!(list.pop_min(), Some(-7));
+    assert_eq!(list.iter().collect::<Vec<_>>(), [-3, 0, 3, 5, 5, 9]);
+}
+
+#[test]
+fn equal_elements_keep_insertion_order() {
+    let mut list = SortedLinkedList::with_comparator(by_priority);
+    list.insert(job(2, "a"));
+    list.insert(job(1, "b"));
+    list.insert(job(2, "c"));
+    list.insert(job(1, "d"));
+    list.insert(job(3, "e"));
+    list.insert(job(2, "f"));
+    assert_eq!(list.to_string(), "b1 -> d1 -> a2 -> c2 -> f2 -> e3");
+
+    assert_eq!(list.pop_min(), Some(job(1, "b")));
+    assert_eq!(list.pop_max(), Some(job(3, "e")));
+    assert_eq!(list.pop_max(), Some(job(2, "f")));
+    // `remove` takes the oldest of the equal elements.
+    assert_eq!(list.remove(&job(2, "?")), Some(job(2, "a")));
+    assert_eq!(list.to_string(), "d1 -> c2");
+}
+
+#[test]
+fn ranges_respect_bounds() {
+    let mut list: SortedLinkedList<u32> = SortedLinkedList::new();
+    for value in [30, 10, 20, 20, 40, 50] {
+        list.insert(value);
+    }
+    assert_eq!(list.range(20..40).collect::<Vec<_>>(), [20, 20, 30]);
+    assert_eq!(list.range(20..=40).collect::<Vec<_>>(), [20, 20, 30, 40]);
+    assert_eq!(list.range(..=10).collect::<Vec<_>>(), [10]);
+    assert_eq!(list.range(45..).collect::<Vec<_>>(), [50]);
+    assert_eq!(list.range(60..).count(), 0);
+    assert_eq!(list.range(..).count(), 6);
+    let open = (Bound::Excluded(20), Bound::Excluded(50));
+    assert_eq!(list.range(open).collect::<Vec<_>>(), [30, 40]);
+}
+
+#[test]
+fn custom_comparator_orders_descending() {
+    let mut list = SortedLinkedList::with_comparator(|a: &u32, b: &u32| b.cmp(a));
+    for value in 0..10 {
+        list.insert(value);
+    }
+    assert_eq!(list.first(), Some(9));
+    // Bounds follow the comparator, so the range runs from 7 down to 3.
+    let descending = (Bound::Included(7), Bound::Included(3));
+    assert_eq!(list.range(descending).collect::<Vec<_>>(), [7, 6, 5, 4, 3]);
+    assert_eq!(list.remove(&5), Some(5));
+    assert_eq!(list.pop_min(), Some(9));
+    assert_eq!(list.pop_max(), Some(0));
+    assert_eq!(list.to_string(), "8 -> 7 -> 6 -> 4 -> 3 -> 2 -> 1");
+}
+
+#[test]
+fn random_inserts_match_stable_sort() {
+    const NAMES: [&str; 4] = ["w", "x", "y", "z"];
+    let mut state: u64 = 12345;
+    let mut list = SortedLinkedList::with_comparator(by_priority);
+    let mut model: Vec<Job> = Vec::new();
+    for i in 0..2000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let priority = (state % 50) as u32;
+        if state.is_multiple_of(7) {
+            let expected = model
+                .iter()
+                .position(|job| job.priority == priority)
+                .map(|index| model.remove(index));
+            assert_eq!(list.remove(&job(priority, "?")), expected);
+        } else {
+            let new_job = job(priority, NAMES[i % NAMES.len()]);
+            // `sort_by` is stable, just like the list.
+            model.push(new_job.clone());
+            model.sort_by(by_priority);
+            list.insert(new_job);
+        }
+    }
+    assert_eq!(list.len(), model.len());
+    assert_eq!(list.iter().collect::<Vec<_>>(), model);
+}
@@ -0,0 +1,212 @@
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+use dll::{GenericLinkedList, LinkedList, ObserverFamily, SyncLinkedList};
+
+const THREADS: usize = 8;
+const OPS_PER_THREAD: usize = 500;
+
+/// Walks the list in both directions and checks that every link agrees with
+/// its neighbour, that the head and tail are the true ends, and that the
+/// element count matches `len()`.
+fn assert_invariants<T: Clone + std::fmt::Display>(list: &SyncLinkedList<T>) {
+    let mut count = 0;
+    let mut prev = None;
+    let mut current = list.head.clone();
+    while let Some(node) = current {
+        let guard = node.read().unwrap();
+        match (&prev, &guard.prev) {
+            (None, None) => {}
+            (Some(expected), Some(actual)) => {
+                assert!(Arc::ptr_eq(expected, &actual.upgrade().unwrap()))
+            }
+            _ => panic!("prev pointer disagrees with forward traversal"),
+        }
+        count += 1;
+        current = guard.next.clone();
+        drop(guard);
+        prev = Some(node);
+    }
+    assert_eq!(count, list.len());
+    match (prev, list.tail.as_ref()) {
+        (None, None) => {}
+        (Some(last), Some(tail)) => assert!(Arc::ptr_eq(&last, tail)),
+        _ => panic!("tail disagrees with forward traversal"),
+    }
+}
+
+#[test]
+fn is_send_and_sync() {
+    fn assert_send_sync<S: Send + Sync>() {}
+    assert_send_sync::<SyncLinkedList<u32>>();
+    assert_send_sync::<SyncLinkedList<String>>();
+}
+
+#[test]
+fn push_pop_front_and_back() {
+    let mut list: SyncLinkedList<u32> = SyncLinkedList::new();
+    list.push_front(1);
+    list.push_back(2);
+    list.push_front(3);
+    list.push_back(4);
+    assert_invariants(&list);
+    assert_eq!(list.to_string(), "3 -> 1 -> 2 -> 4");
+    assert_eq!(list.pop_front(), Some(3));
+    assert_eq!(list.pop_back(), Some(4));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_invariants(&list);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn retain_keeps_links_consistent() {
+    let mut list: SyncLinkedList<u64> = SyncLinkedList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 3 == 1);
+    assert_invariants(&list);
+    assert_eq!(list.len(), 333);
+    assert!(list.iter().all(|x| x % 3 == 1));
+
+    list.retain(|_| false);
+    assert_invariants(&list);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn sort_and_get() {
+    let mut list: SyncLinkedList<u32> = SyncLinkedList::new();
+    for i in [5, 1, 4, 2, 3] {
+        list.push_back(i);
+    }
+    list.sort(|a, b| a.cmp(b));
+    assert_invariants(&list);
+    assert_eq!(list.to_string(), "1 -> 2 -> 3 -> 4 -> 5");
+    assert_eq!(list.get(0), Some(1));
+    assert_eq!(list.get(4), Some(5));
+    assert_eq!(list.get(5), None);
+}
+
+#[test]
+fn moves_between_threads() {
+    let mut list: SyncLinkedList<String> = SyncLinkedList::new();
+    list.push_back("a".to_string());
+    let mut list = thread::spawn(move || {
+        list.push_back("b".to_string());
+        list
+    })
+    .join()
+    .unwrap();
+    list.push_back("c".to_string());
+    assert_eq!(list.to_string(), "a -> b -> c");
+}
+
+#[test]
+fn concurrent_pushes() {
+    let list = Arc::new(Mutex::new(SyncLinkedList::new()));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let list = Arc::clone(&list);
+            thread::spawn(move || {
+                for i in 0..OPS_PER_THREAD {
+                    let value = t * OPS_PER_THREAD + i;
+                    let mut list = list.lock().unwrap();
+                    if i % 2 == 0 {
+                        list.push_front(value);
+                    } else {
+                        list.push_back(value);
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let list = list.lock().unwrap();
+    assert_invariants(&list);
+    assert_eq!(list.len(), THREADS * OPS_PER_THREAD);
+    let mut seen: Vec<usize> = list.iter().collect();
+    seen.sort();
+    assert_eq!(seen, (0..THREADS * OPS_PER_THREAD).collect::<Vec<_>>());
+}
+
+#[test]
+fn concurrent_pushes_and_pops() {
+    let list = Arc::new(Mutex::new(SyncLinkedList::new()));
+    let popped = Arc::new(Mutex::new(Vec::new()));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let list = Arc::clone(&list);
+            let popped = Arc::clone(&popped);
+            thread::spawn(move || {
+                let mut mine = Vec::new();
+                for i in 0..OPS_PER_THREAD {
+                    let mut list = list.lock().unwrap();
+                    list.push_back(t * OPS_PER_THREAD + i);
+                    if i % 3 == 0 {
+                        mine.extend(list.pop_front());
+                    } else if i % 3 == 1 {
+                        mine.extend(list.pop_back());
+                    }
+                }
+                popped.lock().unwrap().extend(mine);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // Every pushed value is either still in the list or was popped exactly
+    // once, and the surviving structure is intact.
+    let list = list.lock().unwrap();
+    assert_invariants(&list);
+    let mut all: Vec<usize> = list.iter().collect();
+    all.extend(popped.lock().unwrap().iter().copied());
+    all.sort();
+    assert_eq!(all, (0..THREADS * OPS_PER_THREAD).collect::<Vec<_>>());
+}
+
+#[test]
+fn concurrent_readers() {
+    let mut list: SyncLinkedList<u64> = SyncLinkedList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    let list = Arc::new(RwLock::new(list));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let list = Arc::clone(&list);
+            thread::spawn(move || {
+                let list = list.read().unwrap();
+                assert_eq!(list.iter().sum::<u64>(), 999 * 1000 / 2);
+                assert_eq!(list.get(500), Some(500));
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_invariants(&list.read().unwrap());
+}
+
+#[test]
+fn generic_over_pointer_family() {
+    fn fill<P: ObserverFamily>(list: &mut GenericLinkedList<u32, P>) -> String {
+        for i in [3, 1, 2] {
+            list.push_back(i);
+        }
+        list.sort(|a, b| a.cmp(b));
+        list.to_string()
+    }
+
+    let mut rc_list: LinkedList<u32> = LinkedList::new();
+    let mut arc_list: SyncLinkedList<u32> = SyncLinkedList::new();
+    assert_eq!(fill(&mut rc_list), "1 -> 2 -> 3");
+    assert_eq!(fill(&mut arc_list), "1 -> 2 -> 3");
+}
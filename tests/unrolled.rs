@@ -0,0 +1,282 @@
+mod common;
+
+use common::xorshift;
+use dll::UnrolledList;
+
+/// Checks the list against a `Vec` holding the same elements, walking it
+/// forwards, backwards (via `get`) and through a cursor.
+fn assert_matches<const N: usize>(list: &mut UnrolledList<u32, N>, model: &[u32]) {
+    assert_eq!(list.len(), model.len());
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), model);
+    for (i, value) in model.iter().enumerate().rev() {
+        assert_eq!(list.get(i), Some(value));
+    }
+    assert_eq!(list.get(model.len()), None);
+    assert!(list.node_count() * N >= list.len());
+    assert!(list.node_count() <= list.len());
+
+    let mut cursor = list.cursor();
+    for value in model {
+        assert!(cursor.move_next());
+        assert_eq!(cursor.current(), Some(value));
+    }
+    assert!(!cursor.move_next());
+    for value in model.iter().rev().skip(1) {
+        assert!(cursor.move_prev());
+        assert_eq!(cursor.current(), Some(value));
+    }
+}
+
+fn random_operations<const N: usize>(seed: u64) {
+    let mut state = seed | 1;
+    let mut list: UnrolledList<u32, N> = UnrolledList::new();
+    let mut model: Vec<u32> = Vec::new();
+    for step in 0..3000 {
+        let value = step as u32;
+        match xorshift(&mut state) % 8 {
+            0 => {
+                list.push_front(value);
+                model.insert(0, value);
+            }
+            1 => {
+                list.push_back(value);
+                model.push(value);
+            }
+            2 => assert_eq!(
+                list.pop_front(),
+                (!model.is_empty()).then(|| model.remove(0))
+            ),
+            3 => assert_eq!(list.pop_back(), model.pop()),
+            4 | 5 => {
+                let index = xorshift(&mut state) as usize % (model.len() + 1);
+                list.insert(index, value);
+                model.insert(index, value);
+            }
+            6 => {
+                let index = xorshift(&mut state) as usize % (model.len() + 1);
+                let expected = (index < model.len()).then(|| model.remove(index));
+                assert_eq!(list.remove(index), expected);
+            }
+            _ => {
+                let modulus = 2 + xorshift(&mut state) as u32 % 5;
+                list.retain(|x| x % modulus != 0);
+                model.retain(|x| x % modulus != 0);
+            }
+        }
+        if step % 97 == 0 {
+            assert_matches(&mut list, &model);
+        }
+    }
+    assert_matches(&mut list, &model);
+}
+
+#[test]
+fn create_empty_list() {
+    let mut list: UnrolledList<u32, 8> = UnrolledList::new();
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+    assert_eq!(list.node_count(), 0);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+    assert_eq!(list.get(0), None);
+}
+
+#[test]
+#[should_panic(expected = "at least two elements per node")]
+fn single_element_nodes_are_rejected() {
+    let _: UnrolledList<u32, 1> = UnrolledList::new();
+}
+
+#[test]
+fn push_and_pop_front_and_back() {
+    let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    list.push_front(1);
+    list.push_back(2);
+    list.push_front(3);
+    list.push_back(4);
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.pop_front(), Some(3));
+    assert_eq!(list.pop_back(), Some(4));
+    assert_eq!(list.pop_front(), Some(1));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+}
+
+#[test]
+fn nodes_fill_up_before_allocating() {
+    let mut list: UnrolledList<u8, 16> = UnrolledList::new();
+    for i in 0..160 {
+        list.push_back(i);
+    }
+    assert_eq!(list.node_count(), 10);
+    for i in 0..16 {
+        list.push_front(i);
+    }
+    assert_eq!(list.node_count(), 11);
+}
+
+#[test]
+fn inserting_into_full_node_splits_it() {
+    let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    for i in [0, 1, 3, 4] {
+        list.push_back(i);
+    }
+    assert_eq!(list.node_count(), 1);
+    list.insert(2, 2);
+    assert_eq!(list.node_count(), 2);
+    assert_eq!(list.to_string(), "0 -> 1 -> 2 -> 3 -> 4");
+}
+
+#[test]
+fn removing_merges_sparse_nodes() {
+    let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    for i in 0..8 {
+        list.push_back(i);
+    }
+    assert_eq!(list.remove(4), Some(4));
+    assert_eq!(list.remove(0), Some(0));
+    assert_eq!(list.remove(0), Some(1));
+    assert_eq!(list.node_count(), 2);
+    assert_eq!(list.remove(0), Some(2));
+    // One element left in the first node, and the second one fits after it.
+    assert_eq!(list.node_count(), 1);
+    assert_eq!(list.to_string(), "3 -> 5 -> 6 -> 7");
+}
+
+#[test]
+fn retains_odd_values() {
+    let mut list: UnrolledList<u64, 8> = UnrolledList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.retain(|x| x % 2 != 0);
+    assert_eq!(list.len(), 500);
+    assert_eq!(list.node_count(), 63);
+    for _ in 0..list.len() {
+        assert!(!list.pop_front().unwrap().is_multiple_of(2));
+    }
+    assert_eq!(list.len(), 0);
+}
+
+#[test]
+fn sort_is_stable() {
+    let mut list: UnrolledList<(u32, char), 2> = UnrolledList::new();
+    for pair in [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')] {
+        list.push_back(pair);
+    }
+    list.sort(|a, b| a.0.cmp(&b.0));
+    let sorted: Vec<_> = list.iter().copied().collect();
+    assert_eq!(sorted, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    assert_eq!(list.pop_back(), Some((2, 'c')));
+    assert_eq!(list.pop_front(), Some((0, 'e')));
+}
+
+#[test]
+fn mutable_access() {
+    let mut list: UnrolledList<u32, 3> = UnrolledList::new();
+    for i in 0..7 {
+        list.push_front(i);
+    }
+    for element in list.iter_mut() {
+        *element *= 10;
+    }
+    assert_eq!(list.to_string(), "60 -> 50 -> 40 -> 30 -> 20 -> 10 -> 0");
+    *list.get_mut(5).unwrap() = 7;
+    assert_eq!(list.get(5), Some(&7));
+
+    // Chunks out of link order, with a free one left behind.
+    for _ in 0..3 {
+        list.pop_front();
+    }
+    list.push_front(1);
+    for (i, element) in list.iter_mut().enumerate() {
+        *element = i as u32;
+    }
+    assert_eq!(list.to_string(), "0 -> 1 -> 2 -> 3 -> 4");
+}
+
+#[test]
+fn cursor_edits_across_node_boundaries() {
+    let mut list: UnrolledList<u32, 2> = UnrolledList::new();
+    {
+        let mut cursor = list.cursor();
+        cursor.insert_after(4);
+        cursor.insert_after(0);
+        assert!(cursor.move_next());
+        for i in 1..4 {
+            // Inserting after a full node's last element splits it; the
+            // cursor has to follow its element into the new node.
+            cursor.insert_after(i);
+            assert!(cursor.move_next());
+            assert_eq!(cursor.current(), Some(&i));
+        }
+        assert_eq!(cursor.remove_next(), Some(4));
+        assert_eq!(cursor.remove_next(), None);
+        assert!(cursor.move_prev());
+        assert!(cursor.move_prev());
+        *cursor.current_mut().unwrap() = 10;
+        assert_eq!(cursor.remove_next(), Some(2));
+    }
+    assert_eq!(list.to_string(), "0 -> 10 -> 3");
+}
+
+#[test]
+fn random_operations_match_vec() {
+    random_operations::<2>(1);
+    random_operations::<3>(2);
+    random_operations::<4>(3);
+    random_operations::<16>(4);
+    random_operations::<64>(5);
+}
+
+#[test]
+fn random_cursor_edits_match_vec() {
+    let mut state = 42 | 1;
+    let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+    let mut model: Vec<u32> = Vec::new();
+    for round in 0..50 {
+        let mut position: Option<usize> = None; // `None` before the first element
+        {
+            let mut cursor = list.cursor();
+            for step in 0..100 {
+                let value = round * 1000 + step;
+                match xorshift(&mut state) % 4 {
+                    0 | 1 => {
+                        cursor.insert_after(value);
+                        model.insert(position.map_or(0, |p| p + 1), value);
+                    }
+                    2 => {
+                        let at = position.map_or(0, |p| p + 1);
+                        let expected = (at < model.len()).then(|| model.remove(at));
+                        assert_eq!(cursor.remove_next(), expected);
+                    }
+                    _ => {
+                        if cursor.move_next() {
+                            position = Some(position.map_or(0, |p| p + 1));
+                        }
+                    }
+                }
+                assert_eq!(cursor.current(), position.map(|p| &model[p]));
+            }
+        }
+        assert_matches(&mut list, &model);
+        list.retain(|x| x % 3 != 0);
+        model.retain(|x| x % 3 != 0);
+    }
+}
+
+#[test]
+fn clear() {
+    let mut list: UnrolledList<u32, 8> = UnrolledList::new();
+    for i in 0..1000 {
+        list.push_back(i);
+    }
+    list.clear();
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.node_count(), 0);
+    assert_eq!(list.pop_front(), None);
+    list.push_back(1);
+    assert_eq!(list.to_string(), "1");
+}